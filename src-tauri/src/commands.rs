@@ -1,8 +1,19 @@
-use crate::storage::{Database, models::{Item, CustomView, Group, Secret}};
+use crate::storage::query::{ItemQuery, QueryError};
+use crate::storage::rules::{ViewRule, ViewSort};
+use crate::storage::{Database, models::{Item, ItemCursor, ItemEvent, ItemFilter, ItemPage, CustomView, Group, SearchResult, Secret, SourceError, SyncRun}};
 use crate::config::{TokenStore, SecretStore};
 use crate::oauth::github::{GitHubOAuth, GitHubRepository, PollResult};
+use crate::ingestion::discovery::FeedCandidate;
+use crate::ingestion::feed::check_auth_secrets;
+use crate::import::ImportReport;
+use crate::http::HttpSettings;
+use crate::sync::SyncTrigger;
+use crate::sync::cancel::{Cancellations, Task};
+use crate::sync::quiet_hours::{PollingSchedule, QUIET_HOURS_PREFERENCE};
+use crate::sync::scheduler::{Scheduler, SyncStatus};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager, State};
 
@@ -33,17 +44,79 @@ pub async fn get_items(
     group_filter: Option<String>,
     source_ids: Option<Vec<i64>>,
     group_names: Option<Vec<String>>,
+    query: Option<String>, // Inbox query, e.g. `is:unread author:alice -label:bot kubernetes`
+    group_similar: Option<bool>, // One entry per cluster of similar stories
 ) -> Result<Vec<Item>, String> {
+    let query = parse_item_query(query.as_deref())?;
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     db.get_items(
         state_filter.as_deref(),
         group_filter.as_deref(),
         source_ids.as_deref(),
         group_names.as_deref(),
+        query.as_ref(),
+        group_similar.unwrap_or(false),
     )
         .map_err(|e| format!("Failed to get items: {}", e))
 }
 
+/// One page of the item list as slim summaries, with the total count. Start with no
+/// cursor and pass back `next_cursor` for more; bodies come from `get_item_with_content`.
+#[tauri::command]
+pub async fn get_item_page(
+    db: State<'_, Mutex<Database>>,
+    filter: Option<ItemFilter>,
+    cursor: Option<ItemCursor>,
+    limit: Option<i64>,
+) -> Result<ItemPage, String> {
+    let filter = filter.unwrap_or_default();
+    let query = parse_item_query(filter.query.as_deref())?;
+    let limit = limit.unwrap_or(100).clamp(1, 500);
+    let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db.get_item_page(&filter, query.as_ref(), cursor.as_ref(), limit)
+        .map_err(|e| format!("Failed to get items: {}", e))
+}
+
+/// Parses an optional inbox query; blank queries are no query.
+fn parse_item_query(query: Option<&str>) -> Result<Option<ItemQuery>, String> {
+    match query.filter(|q| !q.trim().is_empty()) {
+        Some(q) => ItemQuery::parse(q)
+            .map(Some)
+            .map_err(|e| format!("Invalid query: {}", e)),
+        None => Ok(None),
+    }
+}
+
+/// Checks an inbox query as the user types it. Returns the parse error, if any, with
+/// the character position it refers to.
+#[tauri::command]
+pub async fn validate_item_query(query: String) -> Result<Option<QueryError>, String> {
+    Ok(ItemQuery::parse(&query).err())
+}
+
+/// Full-text search over items, best matches first, within the same filters as `get_items`.
+#[tauri::command]
+pub async fn search_items(
+    db: State<'_, Mutex<Database>>,
+    query: String,
+    state_filter: Option<String>,
+    group_filter: Option<String>,
+    source_ids: Option<Vec<i64>>,
+    group_names: Option<Vec<String>>,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, String> {
+    let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db.search_items(
+        &query,
+        state_filter.as_deref(),
+        group_filter.as_deref(),
+        source_ids.as_deref(),
+        group_names.as_deref(),
+        limit.unwrap_or(50).clamp(1, 500),
+    )
+        .map_err(|e| format!("Failed to search items: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_item(
     db: State<'_, Mutex<Database>>,
@@ -54,15 +127,39 @@ pub async fn get_item(
         .map_err(|e| format!("Failed to get item: {}", e))
 }
 
+/// An item's change history (title, summary, status, content and state changes), newest first.
+#[tauri::command]
+pub async fn get_item_history(
+    db: State<'_, Mutex<Database>>,
+    item_id: i64,
+) -> Result<Vec<ItemEvent>, String> {
+    let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db.get_item_history(item_id)
+        .map_err(|e| format!("Failed to get item history: {}", e))
+}
+
+/// Removes the per-poll `update` events older versions recorded. Returns rows removed.
+#[tauri::command]
+pub async fn compact_item_history(
+    db: State<'_, Mutex<Database>>,
+) -> Result<usize, String> {
+    let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db.compact_item_history()
+        .map_err(|e| format!("Failed to compact item history: {}", e))
+}
+
 #[tauri::command]
 pub async fn update_item_state(
     db: State<'_, Mutex<Database>>,
     id: i64,
     state: String,
+    group_similar: Option<bool>, // The list groups similar stories, so the whole cluster changes
 ) -> Result<(), String> {
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    db.update_item_state(id, &state)
-        .map_err(|e| format!("Failed to update item state: {}", e))
+    db.update_item_state(id, &state, group_similar.unwrap_or(false))
+        .map_err(|e| format!("Failed to update item state: {}", e))?;
+    refresh_view_counts(&db);
+    Ok(())
 }
 
 #[tauri::command]
@@ -70,10 +167,13 @@ pub async fn bulk_update_item_state(
     db: State<'_, Mutex<Database>>,
     ids: Vec<i64>,
     state: String,
+    group_similar: Option<bool>,
 ) -> Result<usize, String> {
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    db.bulk_update_item_state(&ids, &state)
-        .map_err(|e| format!("Failed to bulk update item state: {}", e))
+    let updated = db.bulk_update_item_state(&ids, &state, group_similar.unwrap_or(false))
+        .map_err(|e| format!("Failed to bulk update item state: {}", e))?;
+    refresh_view_counts(&db);
+    Ok(updated)
 }
 
 #[tauri::command]
@@ -82,8 +182,17 @@ pub async fn clear_source_items(
     source_name: String,
 ) -> Result<usize, String> {
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    db.delete_items_by_source_name(&source_name)
-        .map_err(|e| format!("Failed to clear items for source: {}", e))
+    let deleted = db.delete_items_by_source_name(&source_name)
+        .map_err(|e| format!("Failed to clear items for source: {}", e))?;
+    refresh_view_counts(&db);
+    Ok(deleted)
+}
+
+/// Keeps the unread counts stored on custom views in step after items change.
+fn refresh_view_counts(db: &Database) {
+    if let Err(e) = db.refresh_view_unread_counts() {
+        eprintln!("Failed to refresh custom view unread counts: {}", e);
+    }
 }
 
 #[tauri::command]
@@ -118,6 +227,38 @@ pub async fn get_sources(
     Ok(result)
 }
 
+/// Returns the cached favicons of all sources as `data:` URLs keyed by source id,
+/// so the sidebar and item lists never load icons from remote hosts.
+#[tauri::command]
+pub async fn get_source_favicons(
+    db: State<'_, Mutex<Database>>,
+) -> Result<HashMap<i64, String>, String> {
+    let sources = {
+        let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        db_guard.get_all_sources()
+            .map_err(|e| format!("Failed to get sources: {}", e))?
+    };
+    
+    Ok(sources.iter()
+        .filter_map(|source| {
+            let path = source.favicon_path.as_deref()?;
+            crate::ingestion::favicon::favicon_data_url(std::path::Path::new(path))
+                .map(|data_url| (source.id, data_url))
+        })
+        .collect())
+}
+
+/// Returns the recent sync errors of a source, newest first.
+#[tauri::command]
+pub async fn get_source_errors(
+    db: State<'_, Mutex<Database>>,
+    source_id: i64,
+) -> Result<Vec<SourceError>, String> {
+    let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db_guard.get_source_errors(source_id)
+        .map_err(|e| format!("Failed to get source errors: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_source_secret_id(
     db: State<'_, Mutex<Database>>,
@@ -135,12 +276,20 @@ pub async fn add_source(
     db: State<'_, Mutex<Database>>,
     source: SourceInput,
 ) -> Result<i64, String> {
+    // Feed credentials live in the secret store, only the auth scheme is kept in the config
+    check_auth_secrets(&source.config_json).map_err(|e| e.to_string())?;
     let config_json_str = serde_json::to_string(&source.config_json)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
     
+    // RSS and ATOM sources are stored as the unified "feed" type, which detects the format on each poll
+    let source_type = match source.source_type.as_str() {
+        "rss" | "atom" => "feed",
+        other => other,
+    };
+    
     let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     let source_id = db_guard.create_source(
-        &source.source_type,
+        source_type,
         &source.name,
         &config_json_str,
         source.group_ids.as_deref(),
@@ -179,7 +328,7 @@ pub async fn add_source(
         
         // Sync the source
         use crate::sync_source_internal;
-        if let Err(e) = sync_source_internal(&app_handle, source).await {
+        if let Err(e) = sync_source_internal(&app_handle, source, SyncTrigger::Manual).await {
             eprintln!("Failed to sync newly added source {}: {}", source_id_for_sync, e);
         }
     });
@@ -187,6 +336,106 @@ pub async fn add_source(
     Ok(source_id)
 }
 
+/// Discovers feeds for any URL (homepage, feed, or well-known site) so `add_source` gets a real feed.
+#[tauri::command]
+pub async fn discover_feeds(url: String) -> Result<Vec<FeedCandidate>, String> {
+    tokio::task::spawn_blocking(move || crate::ingestion::discovery::discover_feeds(&url))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map_err(|e| format!("Failed to discover feeds: {}", e))
+}
+
+/// Imports feeds from an OPML document, creating missing groups and skipping known URLs.
+/// New sources are synced one after another in the background.
+#[tauri::command]
+pub async fn import_opml(
+    app: AppHandle,
+    db: State<'_, Mutex<Database>>,
+    content: String,
+) -> Result<ImportReport, String> {
+    let report = {
+        let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        crate::import::opml::import_opml(&db_guard, &content)
+            .map_err(|e| format!("Failed to import OPML: {}", e))?
+    };
+    
+    sync_imported_sources(app, report.created_source_ids.clone());
+    Ok(report)
+}
+
+/// Imports saved/starred articles exported by another reader ("miniflux", "freshrss" or "feedly")
+/// as archived items, creating their feed sources and groups.
+#[tauri::command]
+pub async fn import_reader_export(
+    app: AppHandle,
+    db: State<'_, Mutex<Database>>,
+    format: String,
+    content: String,
+) -> Result<ImportReport, String> {
+    let report = {
+        let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        crate::import::import_reader_export(&db_guard, &format, &content)
+            .map_err(|e| format!("Failed to import {} export: {}", format, e))?
+    };
+    
+    sync_imported_sources(app, report.created_source_ids.clone());
+    Ok(report)
+}
+
+/// Exports all feed sources and their groups as an OPML 2.0 document.
+#[tauri::command]
+pub async fn export_opml(
+    db: State<'_, Mutex<Database>>,
+) -> Result<String, String> {
+    let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    crate::import::opml::export_opml(&db_guard)
+        .map_err(|e| format!("Failed to export OPML: {}", e))
+}
+
+/// Syncs freshly imported sources sequentially so a large import doesn't hit every host at once.
+fn sync_imported_sources(app: AppHandle, source_ids: Vec<i64>) {
+    if source_ids.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        for source_id in source_ids {
+            let source = {
+                let db_state: State<'_, Mutex<Database>> = app.state();
+                let db_guard = match db_state.lock() {
+                    Ok(db) => db,
+                    Err(_) => return,
+                };
+                match db_guard.get_source(source_id) {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                }
+            };
+            
+            if let Err(e) = crate::sync_source_internal(&app, source, SyncTrigger::Manual).await {
+                eprintln!("Failed to sync imported source {}: {}", source_id, e);
+            }
+        }
+    });
+}
+
+/// Overwrites the top-level keys of `config` present in `changes`, removing those set to null.
+fn merge_config(config: &mut serde_json::Value, changes: serde_json::Value) {
+    let (config, changes) = match (config.as_object_mut(), changes) {
+        (Some(config), serde_json::Value::Object(changes)) => (config, changes),
+        (_, changes) => {
+            *config = changes;
+            return;
+        }
+    };
+    for (key, value) in changes {
+        if value.is_null() {
+            config.remove(&key);
+        } else {
+            config.insert(key, value);
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn update_source(
     app: AppHandle,
@@ -196,11 +445,21 @@ pub async fn update_source(
 ) -> Result<(), String> {
     let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
-    let config_json_str = update.config_json
-        .as_ref()
-        .map(|c| serde_json::to_string(c))
-        .transpose()
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    // The edit form only sends the keys it edits, so they're merged into the stored config
+    // (a null removes a key) and settings like auth or user_agent survive the edit
+    let config_json_str = match update.config_json {
+        Some(changes) => {
+            let source = db_guard.get_source(id)
+                .map_err(|e| format!("Failed to load source: {}", e))?;
+            let mut config: serde_json::Value = serde_json::from_str(&source.config_json)
+                .unwrap_or_else(|_| serde_json::json!({}));
+            merge_config(&mut config, changes);
+            check_auth_secrets(&config).map_err(|e| e.to_string())?;
+            Some(serde_json::to_string(&config)
+                .map_err(|e| format!("Failed to serialize config: {}", e))?)
+        }
+        None => None,
+    };
     
     // Convert Option<Vec<i64>> to Option<Option<&[i64]>>
     // None = don't update groups, Some(vec) = set groups (empty vec clears)
@@ -238,6 +497,12 @@ pub async fn remove_source(
     let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     db_guard.delete_source(id)
         .map_err(|e| format!("Failed to delete source: {}", e))?;
+    refresh_view_counts(&db_guard);
+    
+    // Remove cached favicon if exists
+    if let Ok(dir) = crate::favicon_dir(&app) {
+        crate::ingestion::favicon::remove_cached_favicon(&dir, id);
+    }
     
     // Remove token if exists
     let token_store: State<'_, Mutex<TokenStore>> = app.state();
@@ -291,8 +556,7 @@ pub async fn test_github_notifications(secret_id: i64, app: tauri::AppHandle) ->
 pub async fn test_github_token(secret_id: i64, app: tauri::AppHandle) -> Result<String, String> {
     use std::sync::Mutex;
     use tauri::State;
-    use reqwest::blocking::Client;
-    use std::time::Duration;
+    use crate::http::{self, ClientOptions};
     
     // Get token from SecretStore
     let token = {
@@ -306,10 +570,7 @@ pub async fn test_github_token(secret_id: i64, app: tauri::AppHandle) -> Result<
     
     // Test the token by making a simple API call to /user
     let result = tokio::task::spawn_blocking(move || {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
+        let client = http::client(ClientOptions::with_timeout(10))?;
         
         let response = client
             .get("https://api.github.com/user")
@@ -357,22 +618,100 @@ pub async fn sync_source(
     
     // Use the internal sync function which handles all source types properly
     use crate::sync_source_internal;
-    sync_source_internal(&app, source)
+    sync_source_internal(&app, source, SyncTrigger::Manual)
         .await
         .map_err(|e| format!("Failed to sync source: {}", e))?;
     
     Ok(())
 }
 
+/// Returns the most recent sync runs, newest first, for one source or all of them.
+#[tauri::command]
+pub async fn get_sync_runs(
+    db: State<'_, Mutex<Database>>,
+    source_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<SyncRun>, String> {
+    let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db_guard.get_sync_runs(source_id, limit.unwrap_or(50).clamp(1, 1000))
+        .map_err(|e| format!("Failed to get sync runs: {}", e))
+}
+
+/// When each enabled source is next polled in the background, keyed by source ID.
+/// Accounts for backoff of failing sources.
+#[tauri::command]
+pub async fn get_next_poll_times(
+    db: State<'_, Mutex<Database>>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<HashMap<i64, i64>, String> {
+    let mut next_polls = scheduler.next_poll_times();
+    let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let sources = db_guard.get_all_sources()
+        .map_err(|e| format!("Failed to get sources: {}", e))?;
+    for source in sources {
+        if let (Some(next), Some(until)) = (next_polls.get_mut(&source.id), source.backoff_until) {
+            *next = (*next).max(until);
+        }
+    }
+    Ok(next_polls)
+}
+
+/// Cancels the source's sync if one is running. Returns whether there was one to cancel.
+#[tauri::command]
+pub async fn cancel_sync(
+    cancellations: State<'_, Cancellations>,
+    source_id: i64,
+) -> Result<bool, String> {
+    Ok(cancellations.cancel(Task::Sync(source_id)))
+}
+
+/// Cancels the item's full-text extraction if one is running. The item goes back to
+/// its feed content and can be extracted again later.
+#[tauri::command]
+pub async fn cancel_extraction(
+    cancellations: State<'_, Cancellations>,
+    item_id: i64,
+) -> Result<bool, String> {
+    Ok(cancellations.cancel(Task::Extraction(item_id)))
+}
+
+/// Whether background syncs are paused, in quiet hours or held back while offline.
+#[tauri::command]
+pub async fn get_sync_status(
+    db: State<'_, Mutex<Database>>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<SyncStatus, String> {
+    let schedule = {
+        let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        PollingSchedule::from_preferences(&db_guard)
+    };
+    Ok(scheduler.status(&schedule))
+}
+
+/// Deletes sync runs older than the given number of days. Returns how many were removed.
+#[tauri::command]
+pub async fn prune_sync_runs(
+    db: State<'_, Mutex<Database>>,
+    older_than_days: i64,
+) -> Result<usize, String> {
+    let before = chrono::Utc::now().timestamp() - older_than_days.max(0) * 24 * 60 * 60;
+    let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    db_guard.prune_sync_runs(before)
+        .map_err(|e| format!("Failed to prune sync runs: {}", e))
+}
+
 #[tauri::command]
 pub async fn cleanup_old_items(
     db: State<'_, Mutex<Database>>,
     days: Option<i64>,
+    by_published: Option<bool>,
 ) -> Result<usize, String> {
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     let days_to_keep = days.unwrap_or(30); // Default to 30 days
-    db.cleanup_old_items(days_to_keep)
-        .map_err(|e| format!("Failed to cleanup old items: {}", e))
+    let deleted = db.cleanup_old_items(days_to_keep, by_published.unwrap_or(false))
+        .map_err(|e| format!("Failed to cleanup old items: {}", e))?;
+    refresh_view_counts(&db);
+    Ok(deleted)
 }
 
 #[tauri::command]
@@ -405,18 +744,47 @@ pub async fn get_custom_view(
         .map_err(|e| format!("Failed to get custom view: {}", e))
 }
 
+/// Items in a custom view, filtered by its rules and saved query and in its sort order.
+#[tauri::command]
+pub async fn get_view_items(
+    db: State<'_, Mutex<Database>>,
+    view_id: i64,
+    state_filter: Option<String>,
+) -> Result<Vec<Item>, String> {
+    let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let view = db.get_custom_view(view_id)
+        .map_err(|e| format!("Failed to get custom view: {}", e))?;
+    db.get_view_items(&view, state_filter.as_deref())
+        .map_err(|e| format!("Failed to get view items: {}", e))
+}
+
+/// Checks a view's rules, sort order and saved query before saving.
+fn parse_view_settings(rules: Option<&ViewRule>, sort_order: Option<&str>, query: Option<&str>) -> Result<ViewSort, String> {
+    if let Some(rules) = rules {
+        rules.validate().map_err(|e| format!("Invalid rules: {}", e))?;
+    }
+    parse_item_query(query)?;
+    match sort_order {
+        Some(sort) => ViewSort::parse(sort).ok_or_else(|| format!("Unknown sort order: {}", sort)),
+        None => Ok(ViewSort::default()),
+    }
+}
+
 #[tauri::command]
 pub async fn add_custom_view(
     db: State<'_, Mutex<Database>>,
     name: String,
-    source_ids: Option<String>, // JSON array string
-    group_names: Option<String>, // JSON array string
+    rules: Option<ViewRule>, // None shows every item
+    sort_order: Option<String>, // newest (default), oldest, title or source
+    query: Option<String>, // Inbox query, validated before saving
 ) -> Result<i64, String> {
+    let sort_order = parse_view_settings(rules.as_ref(), sort_order.as_deref(), query.as_deref())?;
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     db.create_custom_view(
         &name,
-        source_ids.as_deref(),
-        group_names.as_deref(),
+        rules.as_ref(),
+        sort_order,
+        query.as_deref().filter(|q| !q.trim().is_empty()),
     )
         .map_err(|e| format!("Failed to create custom view: {}", e))
 }
@@ -426,15 +794,18 @@ pub async fn update_custom_view(
     db: State<'_, Mutex<Database>>,
     id: i64,
     name: String,
-    source_ids: Option<String>, // JSON array string
-    group_names: Option<String>, // JSON array string
+    rules: Option<ViewRule>, // None shows every item
+    sort_order: Option<String>, // newest (default), oldest, title or source
+    query: Option<String>, // Inbox query, validated before saving
 ) -> Result<(), String> {
+    let sort_order = parse_view_settings(rules.as_ref(), sort_order.as_deref(), query.as_deref())?;
     let db = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
     db.update_custom_view(
         id,
         &name,
-        source_ids.as_deref(),
-        group_names.as_deref(),
+        rules.as_ref(),
+        sort_order,
+        query.as_deref().filter(|q| !q.trim().is_empty()),
     )
         .map_err(|e| format!("Failed to update custom view: {}", e))
 }
@@ -504,9 +875,12 @@ pub async fn sync_all_sources(
         all_sources.into_iter().filter(|s| s.enabled).collect::<Vec<_>>()
     };
 
-    // Sync each source
-    for source in sources {
-        let _ = sync_source(app.clone(), db.clone(), source.id).await;
+    // The scheduler runs them within its concurrency and per-host limits
+    let scheduler = app.state::<Scheduler>();
+    for (source_id, result) in scheduler.sync_now(&sources).await {
+        if let Err(e) = result {
+            eprintln!("Failed to sync source {}: {}", source_id, e);
+        }
     }
 
     Ok(())
@@ -525,12 +899,37 @@ pub async fn get_user_preference(
 #[tauri::command]
 pub async fn set_user_preference(
     db: State<'_, Mutex<Database>>,
+    scheduler: State<'_, Scheduler>,
     key: String,
     value: String,
 ) -> Result<(), String> {
+    if key == QUIET_HOURS_PREFERENCE {
+        PollingSchedule::parse_quiet_hours(&value)
+            .map_err(|e| format!("Invalid quiet hours: {:#}", e))?;
+    }
+    
     let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let previous = db_guard.get_user_preference(&key)
+        .map_err(|e| format!("Failed to get user preference: {}", e))?;
     db_guard.set_user_preference(&key, &value)
-        .map_err(|e| format!("Failed to set user preference: {}", e))
+        .map_err(|e| format!("Failed to set user preference: {}", e))?;
+    
+    // Network preferences are applied to the shared HTTP clients right away
+    if key.starts_with("http_") {
+        let settings = HttpSettings::from_preferences(&db_guard);
+        if let Err(e) = settings.validate() {
+            let _ = db_guard.set_user_preference(&key, previous.as_deref().unwrap_or(""));
+            return Err(format!("Invalid network settings: {:#}", e));
+        }
+        crate::http::configure(settings);
+    }
+    
+    // Pausing, quiet hours and limits apply to the scheduler's next pass
+    if key.starts_with("sync_") {
+        scheduler.wake();
+    }
+    
+    Ok(())
 }
 
 /// Manually trigger extraction for a specific item
@@ -540,8 +939,6 @@ pub async fn trigger_extraction(
     db: State<'_, Mutex<Database>>,
     item_id: i64,
 ) -> Result<(), String> {
-    use crate::ingestion::extraction::extract_full_text;
-    
     // Get item to extract
     let (url, completeness) = {
         let db_guard = db.lock().map_err(|e| format!("Database lock error: {}", e))?;
@@ -572,17 +969,13 @@ pub async fn trigger_extraction(
     let url_clone = url.clone();
     let completeness_clone = completeness.clone();
     tauri::async_runtime::spawn(async move {
-        let extraction_result = tokio::task::spawn_blocking(move || {
-            extract_full_text(&url_clone)
-        })
-        .await
-        .map_err(|e| anyhow::anyhow!("Task join error: {}", e));
+        let extraction_result = crate::extract_cancellable(&app_clone, item_id, url_clone).await;
         
         let db_state: State<'_, Mutex<Database>> = app_clone.state();
         let db_guard = db_state.lock().unwrap();
         
         match extraction_result {
-            Ok(Ok(result)) => {
+            Ok(result) => {
                 let _ = db_guard.update_item_content_status(
                     item_id,
                     "extracted",
@@ -590,8 +983,20 @@ pub async fn trigger_extraction(
                     Some(&completeness_clone),
                     None,
                 );
+                if let Some(canonical_url) = &result.canonical_url {
+                    let _ = db_guard.set_item_canonical_url(item_id, canonical_url);
+                }
+            }
+            Err(e) if e.is::<crate::http::Cancelled>() => {
+                let _ = db_guard.update_item_content_status(
+                    item_id,
+                    "feed_only",
+                    None,
+                    Some(&completeness_clone),
+                    None,
+                );
             }
-            Ok(Err(e)) | Err(e) => {
+            Err(e) => {
                 let _ = db_guard.update_item_content_status(
                     item_id,
                     "failed",
@@ -747,8 +1152,7 @@ pub async fn get_secret_value(
 pub async fn detect_github_token_expiration(
     token: String,
 ) -> Result<Option<serde_json::Value>, String> {
-    use reqwest::blocking::Client;
-    use std::time::Duration;
+    use crate::http::{self, ClientOptions};
     use chrono::Utc;
     
     // Check if it looks like a GitHub token (starts with ghp_ for PAT or gho_ for OAuth)
@@ -762,9 +1166,7 @@ pub async fn detect_github_token_expiration(
     // However, we can try to make an API call to check if the token is valid
     // and see if we can get any expiration info
     
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
+    let client = http::client(ClientOptions::with_timeout(10))
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
     
     // Try to get user info to verify token and check rate limit headers
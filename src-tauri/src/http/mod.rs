@@ -0,0 +1,359 @@
+use crate::storage::Database;
+use anyhow::{Result, Context};
+use once_cell::sync::Lazy;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Certificate, NoProxy, Proxy};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+pub const DEFAULT_USER_AGENT: &str = "UmbraRelay/1.0";
+
+/// Connect timeout used unless the user configured one.
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+// User preference keys read by `HttpSettings::from_preferences`
+pub const PROXY_PREFERENCE: &str = "http_proxy"; // e.g. "http://proxy:3128" or "socks5h://127.0.0.1:1080"
+pub const NO_PROXY_PREFERENCE: &str = "http_no_proxy"; // comma-separated hosts, domains and CIDRs
+pub const CA_CERTIFICATES_PREFERENCE: &str = "http_ca_certificates"; // PEM file paths, one per line
+pub const USER_AGENT_PREFERENCE: &str = "http_user_agent";
+pub const TIMEOUT_PREFERENCE: &str = "http_timeout_secs";
+pub const CONNECT_TIMEOUT_PREFERENCE: &str = "http_connect_timeout_secs";
+
+/// Network settings shared by every HTTP client in the app.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpSettings {
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    pub ca_certificates: Vec<PathBuf>,
+    pub user_agent: Option<String>,
+    pub timeout_secs: Option<u64>, // Overrides the per-client default timeouts when set
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl HttpSettings {
+    /// Reads the settings from user preferences; blank values mean "not set".
+    pub fn from_preferences(db: &Database) -> Self {
+        let get = |key: &str| {
+            db.get_user_preference(key)
+                .ok()
+                .flatten()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        HttpSettings {
+            proxy: get(PROXY_PREFERENCE),
+            no_proxy: get(NO_PROXY_PREFERENCE),
+            ca_certificates: get(CA_CERTIFICATES_PREFERENCE)
+                .map(|paths| {
+                    paths.lines()
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            user_agent: get(USER_AGENT_PREFERENCE),
+            timeout_secs: get(TIMEOUT_PREFERENCE).and_then(|v| v.parse().ok()).filter(|t| *t > 0),
+            connect_timeout_secs: get(CONNECT_TIMEOUT_PREFERENCE).and_then(|v| v.parse().ok()).filter(|t| *t > 0),
+        }
+    }
+
+    fn proxy(&self) -> Result<Option<Proxy>> {
+        let url = match &self.proxy {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+        let proxy = Proxy::all(url)
+            .with_context(|| format!("Invalid proxy URL: {}", url))?
+            .no_proxy(self.no_proxy.as_deref().and_then(NoProxy::from_string));
+        Ok(Some(proxy))
+    }
+
+    fn root_certificates(&self) -> Result<Vec<Certificate>> {
+        let mut certificates = Vec::new();
+        for path in &self.ca_certificates {
+            let pem = std::fs::read(path)
+                .with_context(|| format!("Failed to read CA certificate: {:?}", path))?;
+            let bundle = Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("Invalid PEM certificate: {:?}", path))?;
+            if bundle.is_empty() {
+                anyhow::bail!("No certificates found in {:?}", path);
+            }
+            certificates.extend(bundle);
+        }
+        Ok(certificates)
+    }
+
+    /// Checks the proxy URL and CA certificates without building a client.
+    pub fn validate(&self) -> Result<()> {
+        self.proxy()?;
+        self.root_certificates()?;
+        Ok(())
+    }
+}
+
+/// What a caller needs from its client. Clients are cached per distinct options.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClientOptions {
+    pub timeout: Option<Duration>, // Default request timeout for this use, e.g. 60s for feeds
+    pub user_agent: Option<String>, // Per-source override of the configured user agent
+}
+
+impl ClientOptions {
+    pub fn with_timeout(secs: u64) -> Self {
+        ClientOptions {
+            timeout: Some(Duration::from_secs(secs)),
+            user_agent: None,
+        }
+    }
+
+    pub fn user_agent(mut self, user_agent: Option<String>) -> Self {
+        self.user_agent = user_agent.map(|ua| ua.trim().to_string()).filter(|ua| !ua.is_empty());
+        self
+    }
+}
+
+struct ClientFactory {
+    settings: HttpSettings,
+    pending: Option<HttpSettings>,
+    clients: HashMap<ClientOptions, Client>,
+}
+
+static FACTORY: Lazy<Mutex<ClientFactory>> = Lazy::new(|| {
+    Mutex::new(ClientFactory {
+        settings: HttpSettings::default(),
+        pending: None,
+        clients: HashMap::new(),
+    })
+});
+
+/// Applies new network settings. Cached clients are replaced on their next use, so this
+/// is safe to call from async code (blocking clients must not be dropped there).
+pub fn configure(settings: HttpSettings) {
+    let mut factory = FACTORY.lock().unwrap();
+    if factory.settings != settings || factory.pending.is_some() {
+        factory.pending = Some(settings);
+    }
+}
+
+fn build_client(settings: &HttpSettings, options: &ClientOptions) -> Result<Client> {
+    let user_agent = options.user_agent.as_deref()
+        .or(settings.user_agent.as_deref())
+        .unwrap_or(DEFAULT_USER_AGENT);
+    let timeout = settings.timeout_secs
+        .map(Duration::from_secs)
+        .or(options.timeout)
+        .unwrap_or(Duration::from_secs(30));
+
+    let mut builder = Client::builder()
+        .user_agent(user_agent)
+        .timeout(timeout)
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS)))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .gzip(true)
+        .brotli(true);
+
+    if let Some(proxy) = settings.proxy()? {
+        builder = builder.proxy(proxy);
+    }
+    for certificate in settings.root_certificates()? {
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    builder.build().context("Failed to create HTTP client")
+}
+
+/// Returns the shared client for these options, building it on first use. Clients keep
+/// their connection pools, so connections are reused across polls of the same host.
+/// Must be called from blocking code (e.g. inside `spawn_blocking`).
+pub fn client(options: ClientOptions) -> Result<Client> {
+    let mut factory = FACTORY.lock().unwrap();
+    if let Some(settings) = factory.pending.take() {
+        factory.settings = settings;
+        factory.clients.clear();
+    }
+
+    if let Some(client) = factory.clients.get(&options) {
+        return Ok(client.clone());
+    }
+
+    let client = build_client(&factory.settings, &options)?;
+    factory.clients.insert(options, client.clone());
+    Ok(client)
+}
+
+/// Error returned for requests made after their sync or extraction was cancelled.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Called with the running request count before each request of a context.
+type RequestCallback = Box<dyn Fn(u32) + Send + Sync>;
+
+#[derive(Default)]
+struct ContextInner {
+    requests: AtomicU32,
+    cancelled: AtomicBool,
+    cancel_notify: Notify,
+    on_request: Option<RequestCallback>,
+}
+
+/// State of one sync run or extraction shared with the blocking tasks it spawns: the
+/// number of HTTP requests made, cancellation and progress. Applies to requests sent
+/// with `send_counted` on threads inside a `scope()`.
+#[derive(Clone, Default)]
+pub struct RequestContext(Arc<ContextInner>);
+
+thread_local! {
+    static CURRENT_CONTEXT: RefCell<Option<RequestContext>> = const { RefCell::new(None) };
+}
+
+/// Restores the thread's previous context when dropped.
+pub struct ContextScope(Option<RequestContext>);
+
+impl Drop for ContextScope {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT_CONTEXT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+impl RequestContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Context that reports each request (e.g. each page of a paginated API) to `callback`.
+    pub fn with_progress(callback: impl Fn(u32) + Send + Sync + 'static) -> Self {
+        RequestContext(Arc::new(ContextInner {
+            on_request: Some(Box::new(callback)),
+            ..Default::default()
+        }))
+    }
+
+    /// Makes this the current context of this thread until the scope is dropped.
+    pub fn scope(&self) -> ContextScope {
+        let previous = CURRENT_CONTEXT.with(|current| current.borrow_mut().replace(self.clone()));
+        ContextScope(previous)
+    }
+
+    pub fn count(&self) -> u32 {
+        self.0.requests.load(Ordering::Relaxed)
+    }
+
+    /// Fails the context's next request and wakes everyone waiting on `cancelled()`.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.cancel_notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the context is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.cancel_notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// The typed error for a response's HTTP error status, without the URL (it may carry a
+/// token). Callers add their own message as context; the status stays readable from the
+/// error chain (see `sync::health::classify_error`).
+pub fn status_error(response: &Response) -> anyhow::Error {
+    match response.error_for_status_ref() {
+        Err(e) => anyhow::Error::new(e.without_url()),
+        Ok(response) => anyhow::anyhow!("HTTP status {}", response.status()),
+    }
+}
+
+/// `send()` that counts the request towards the current context, if any, and refuses
+/// to send once that context was cancelled.
+pub trait SendCounted {
+    fn send_counted(self) -> Result<Response>;
+}
+
+impl SendCounted for RequestBuilder {
+    fn send_counted(self) -> Result<Response> {
+        let context = CURRENT_CONTEXT.with(|current| current.borrow().clone());
+        if let Some(context) = context {
+            if context.is_cancelled() {
+                return Err(anyhow::Error::new(Cancelled));
+            }
+            let count = context.0.requests.fetch_add(1, Ordering::Relaxed) + 1;
+            if let Some(callback) = &context.0.on_request {
+                callback(count);
+            }
+        }
+        Ok(self.send()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_settings() {
+        let settings = HttpSettings {
+            proxy: Some("socks5h://127.0.0.1:1080".to_string()),
+            no_proxy: Some("localhost, .internal.example.com, 10.0.0.0/8".to_string()),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        let missing_ca = HttpSettings {
+            ca_certificates: vec![PathBuf::from("/nonexistent/corporate-ca.pem")],
+            ..Default::default()
+        };
+        assert!(missing_ca.validate().is_err());
+    }
+
+    #[test]
+    fn test_request_context_scope_and_cancel() {
+        let pages = Arc::new(AtomicU32::new(0));
+        let context = RequestContext::with_progress({
+            let pages = pages.clone();
+            move |count| pages.store(count, Ordering::Relaxed)
+        });
+        let client = Client::new();
+        {
+            let _scope = context.scope();
+            // Unroutable requests still count; the send itself fails
+            let _ = client.get("http://127.0.0.1:9/").timeout(Duration::from_millis(200)).send_counted();
+            context.cancel();
+            let error = client.get("http://127.0.0.1:9/").send_counted().unwrap_err();
+            assert!(error.is::<Cancelled>());
+        }
+        assert_eq!(context.count(), 1);
+        assert_eq!(pages.load(Ordering::Relaxed), 1);
+        assert!(CURRENT_CONTEXT.with(|current| current.borrow().is_none()));
+    }
+
+    #[test]
+    fn test_client_options_user_agent() {
+        let options = ClientOptions::with_timeout(60).user_agent(Some("  ".to_string()));
+        assert_eq!(options.user_agent, None);
+        let options = ClientOptions::with_timeout(60).user_agent(Some("Feedfetcher/2.0".to_string()));
+        assert_eq!(options.user_agent.as_deref(), Some("Feedfetcher/2.0"));
+    }
+}
@@ -0,0 +1,146 @@
+use super::{stream_feed_url, summary_from_html, ImportedArticle};
+use anyhow::{Result, Context};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FeedlyExport {
+    Stream { items: Vec<FeedlyEntry> },
+    List(Vec<FeedlyEntry>),
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedlyEntry {
+    #[serde(rename = "originId")]
+    origin_id: Option<String>, // The feed's own guid / ATOM id
+    title: Option<String>,
+    author: Option<String>,
+    published: Option<i64>, // milliseconds
+    crawled: Option<i64>,   // milliseconds
+    #[serde(rename = "canonicalUrl")]
+    canonical_url: Option<String>,
+    #[serde(default)]
+    canonical: Vec<FeedlyLink>,
+    #[serde(default)]
+    alternate: Vec<FeedlyLink>,
+    content: Option<FeedlyContent>,
+    summary: Option<FeedlyContent>,
+    origin: Option<FeedlyOrigin>,
+    #[serde(default)]
+    categories: Vec<FeedlyCategory>,
+    #[serde(default)]
+    keywords: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedlyLink {
+    #[serde(default)]
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedlyContent {
+    #[serde(default)]
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedlyOrigin {
+    #[serde(rename = "streamId", default)]
+    stream_id: String,
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedlyCategory {
+    #[serde(default)]
+    label: String,
+}
+
+/// Parses a Feedly saved-items export (a JSON array of entries, or a stream with `items`).
+/// Feedly keeps the feed's guid in `originId`, so later polls match these items exactly.
+pub fn parse_feedly(content: &str) -> Result<Vec<ImportedArticle>> {
+    let export: FeedlyExport = serde_json::from_str(content)
+        .context("Not a Feedly saved items export")?;
+    let entries = match export {
+        FeedlyExport::Stream { items } => items,
+        FeedlyExport::List(items) => items,
+    };
+
+    let articles = entries.into_iter()
+        .filter_map(|entry| {
+            let origin_id = entry.origin_id.filter(|id| !id.trim().is_empty());
+            let url = entry.canonical_url
+                .into_iter()
+                .chain(entry.canonical.into_iter().map(|l| l.href))
+                .chain(entry.alternate.into_iter().map(|l| l.href))
+                .chain(origin_id.clone().filter(|id| id.starts_with("http://") || id.starts_with("https://")))
+                .map(|href| href.trim().to_string())
+                .find(|href| !href.is_empty())?;
+
+            let groups: Vec<String> = entry.categories.into_iter()
+                .map(|c| c.label.trim().to_string())
+                .filter(|label| !label.is_empty())
+                .collect();
+            let content_html = entry.content.or(entry.summary)
+                .map(|c| c.content)
+                .filter(|c| !c.trim().is_empty());
+            let (feed_url, feed_title) = match entry.origin {
+                Some(origin) => (stream_feed_url(&origin.stream_id), Some(origin.title)),
+                None => (None, None),
+            };
+
+            Some(ImportedArticle {
+                external_id: origin_id.unwrap_or_else(|| url.clone()),
+                title: entry.title.filter(|t| !t.is_empty()).unwrap_or_else(|| "Untitled".to_string()),
+                url,
+                summary: content_html.as_deref().and_then(summary_from_html),
+                content_html,
+                author: entry.author.filter(|a| !a.trim().is_empty()),
+                categories: entry.keywords,
+                published_at: entry.published.or(entry.crawled).map(|ms| ms / 1000),
+                state: "archived".to_string(),
+                feed_url,
+                feed_title,
+                groups,
+            })
+        })
+        .collect();
+
+    Ok(articles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feedly_saved_items() {
+        let json = r#"[{
+            "id": "abc=_18f2c:1d2e:3f4a",
+            "originId": "https://example.com/?p=42",
+            "title": "Saved post",
+            "author": "Ann",
+            "published": 1714557600000,
+            "crawled": 1714557700000,
+            "canonicalUrl": "https://example.com/saved-post/",
+            "alternate": [{"href": "https://example.com/saved-post/", "type": "text/html"}],
+            "summary": {"content": "<p>Short</p>", "direction": "ltr"},
+            "origin": {"streamId": "feed/https://example.com/feed/", "title": "Example", "htmlUrl": "https://example.com"},
+            "categories": [{"id": "user/123/category/abc", "label": "Tech"}],
+            "keywords": ["rust"]
+        }]"#;
+
+        let articles = parse_feedly(json).unwrap();
+        assert_eq!(articles.len(), 1);
+        let article = &articles[0];
+        assert_eq!(article.external_id, "https://example.com/?p=42");
+        assert_eq!(article.url, "https://example.com/saved-post/");
+        assert_eq!(article.published_at, Some(1714557600));
+        assert_eq!(article.state, "archived");
+        assert_eq!(article.groups, vec!["Tech".to_string()]);
+        assert_eq!(article.categories, vec!["rust".to_string()]);
+        assert_eq!(article.feed_url.as_deref(), Some("https://example.com/feed/"));
+    }
+}
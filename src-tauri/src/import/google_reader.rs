@@ -0,0 +1,142 @@
+use super::{stream_feed_url, summary_from_html, ImportedArticle};
+use anyhow::{Result, Context};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct GoogleReaderExport {
+    #[serde(default)]
+    id: String, // e.g. "user/-/state/com.google/starred"
+    #[serde(default)]
+    items: Vec<GoogleReaderItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleReaderItem {
+    #[serde(default)]
+    title: String,
+    published: Option<i64>, // seconds
+    #[serde(default)]
+    canonical: Vec<GoogleReaderLink>,
+    #[serde(default)]
+    alternate: Vec<GoogleReaderLink>,
+    #[serde(default)]
+    categories: Vec<String>,
+    content: Option<GoogleReaderContent>,
+    summary: Option<GoogleReaderContent>,
+    author: Option<String>,
+    origin: Option<GoogleReaderOrigin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleReaderLink {
+    #[serde(default)]
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleReaderContent {
+    #[serde(default)]
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleReaderOrigin {
+    #[serde(rename = "streamId", default)]
+    stream_id: String,
+    #[serde(default)]
+    title: String,
+}
+
+/// Parses a FreshRSS / Google Reader JSON export (typically `starred.json`).
+/// Labels (`user/-/label/...`) become groups; item ids are Google Reader ids,
+/// so articles are keyed by URL.
+pub fn parse_google_reader(content: &str) -> Result<Vec<ImportedArticle>> {
+    let export: GoogleReaderExport = serde_json::from_str(content)
+        .context("Not a FreshRSS / Google Reader JSON export")?;
+    let starred_export = export.id.ends_with("/state/com.google/starred");
+
+    let articles = export.items.into_iter()
+        .filter_map(|item| {
+            let url = item.canonical.iter()
+                .chain(item.alternate.iter())
+                .map(|link| link.href.trim())
+                .find(|href| !href.is_empty())?
+                .to_string();
+
+            let is_state = |suffix: &str| item.categories.iter().any(|c| c.ends_with(suffix));
+            let state = if starred_export || is_state("/state/com.google/starred") {
+                "archived"
+            } else if is_state("/state/com.google/read") {
+                "read"
+            } else {
+                "unread"
+            };
+            let labels: Vec<String> = item.categories.iter()
+                .filter_map(|c| c.split_once("/label/").map(|(_, label)| label.trim().to_string()))
+                .filter(|label| !label.is_empty())
+                .collect();
+
+            let content_html = item.content.or(item.summary)
+                .map(|c| c.content)
+                .filter(|c| !c.trim().is_empty());
+            let (feed_url, feed_title) = match item.origin {
+                Some(origin) => (stream_feed_url(&origin.stream_id), Some(origin.title)),
+                None => (None, None),
+            };
+
+            Some(ImportedArticle {
+                external_id: url.clone(),
+                title: if item.title.is_empty() { "Untitled".to_string() } else { item.title },
+                url,
+                summary: content_html.as_deref().and_then(summary_from_html),
+                content_html,
+                author: item.author.filter(|a| !a.trim().is_empty()),
+                categories: labels.clone(),
+                published_at: item.published,
+                state: state.to_string(),
+                feed_url,
+                feed_title,
+                groups: labels,
+            })
+        })
+        .collect();
+
+    Ok(articles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_freshrss_starred_export() {
+        let json = r#"{
+            "id": "user/-/state/com.google/starred",
+            "title": "List of favourite articles",
+            "items": [{
+                "id": "tag:google.com,2005:reader/item/0005e0b3a1b2c3d4",
+                "categories": ["user/-/state/com.google/starred", "user/-/label/Tech"],
+                "title": "Starred post",
+                "published": 1714557600,
+                "canonical": [{"href": "https://example.com/a"}],
+                "alternate": [{"href": "https://example.com/a", "type": "text/html"}],
+                "content": {"content": "<p>Body</p>"},
+                "author": "Ann",
+                "origin": {"streamId": "feed/https://example.com/feed.xml", "title": "Example", "htmlUrl": "https://example.com/"}
+            }, {
+                "title": "No link",
+                "origin": {"streamId": "feed/https://example.com/feed.xml"}
+            }]
+        }"#;
+
+        let articles = parse_google_reader(json).unwrap();
+        assert_eq!(articles.len(), 1);
+        let article = &articles[0];
+        assert_eq!(article.state, "archived");
+        assert_eq!(article.url, "https://example.com/a");
+        assert_eq!(article.published_at, Some(1714557600));
+        assert_eq!(article.groups, vec!["Tech".to_string()]);
+        assert_eq!(article.feed_url.as_deref(), Some("https://example.com/feed.xml"));
+        assert_eq!(article.summary.as_deref(), Some("Body"));
+    }
+}
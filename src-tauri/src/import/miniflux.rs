@@ -0,0 +1,124 @@
+use super::{summary_from_html, ImportedArticle};
+use anyhow::{Result, Context};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MinifluxExport {
+    Page { entries: Vec<MinifluxEntry> },
+    List(Vec<MinifluxEntry>),
+}
+
+#[derive(Debug, Deserialize)]
+struct MinifluxEntry {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    status: String, // "unread", "read" or "removed"
+    #[serde(default)]
+    starred: bool,
+    published_at: Option<String>,
+    content: Option<String>,
+    author: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    feed: Option<MinifluxFeed>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinifluxFeed {
+    #[serde(default)]
+    feed_url: String,
+    #[serde(default)]
+    title: String,
+    category: Option<MinifluxCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinifluxCategory {
+    #[serde(default)]
+    title: String,
+}
+
+/// Parses Miniflux entries JSON (the `/v1/entries` response or a bare array of entries).
+/// Miniflux doesn't export the feed's guid, so articles are keyed by URL.
+pub fn parse_miniflux(content: &str) -> Result<Vec<ImportedArticle>> {
+    let export: MinifluxExport = serde_json::from_str(content)
+        .context("Not a Miniflux entries export")?;
+    let entries = match export {
+        MinifluxExport::Page { entries } => entries,
+        MinifluxExport::List(entries) => entries,
+    };
+
+    let articles = entries.into_iter()
+        .map(|entry| {
+            let state = if entry.starred {
+                "archived"
+            } else if entry.status == "unread" {
+                "unread"
+            } else {
+                "read"
+            };
+            let (feed_url, feed_title, groups) = match entry.feed {
+                Some(feed) => {
+                    let groups = feed.category
+                        .map(|c| c.title)
+                        .filter(|t| !t.trim().is_empty())
+                        .into_iter()
+                        .collect();
+                    (Some(feed.feed_url), Some(feed.title), groups)
+                }
+                None => (None, None, Vec::new()),
+            };
+            ImportedArticle {
+                external_id: entry.url.clone(),
+                title: if entry.title.is_empty() { "Untitled".to_string() } else { entry.title },
+                url: entry.url,
+                summary: entry.content.as_deref().and_then(summary_from_html),
+                content_html: entry.content.filter(|c| !c.trim().is_empty()),
+                author: entry.author.filter(|a| !a.trim().is_empty()),
+                categories: entry.tags,
+                published_at: entry.published_at
+                    .and_then(|p| chrono::DateTime::parse_from_rfc3339(&p).ok())
+                    .map(|dt| dt.timestamp()),
+                state: state.to_string(),
+                feed_url,
+                feed_title,
+                groups,
+            }
+        })
+        .collect();
+
+    Ok(articles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_miniflux_entries() {
+        let json = r#"{"total": 2, "entries": [
+            {"id": 1, "status": "read", "starred": true, "title": "Starred post", "url": "https://example.com/a",
+             "published_at": "2024-05-01T10:00:00Z", "content": "<p>Hello <b>world</b></p>", "author": "Ann",
+             "tags": ["rust"], "feed": {"feed_url": "https://example.com/feed.xml", "title": "Example",
+             "category": {"id": 3, "title": "Tech"}}},
+            {"id": 2, "status": "unread", "starred": false, "title": "", "url": "https://example.com/b",
+             "published_at": "2024-05-02T10:00:00+02:00", "content": "", "feed": {"feed_url": "https://example.com/feed.xml", "title": "Example"}}
+        ]}"#;
+
+        let articles = parse_miniflux(json).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].state, "archived");
+        assert_eq!(articles[0].external_id, "https://example.com/a");
+        assert_eq!(articles[0].published_at, Some(1714557600));
+        assert_eq!(articles[0].summary.as_deref(), Some("Hello world"));
+        assert_eq!(articles[0].groups, vec!["Tech".to_string()]);
+        assert_eq!(articles[0].feed_url.as_deref(), Some("https://example.com/feed.xml"));
+        assert_eq!(articles[1].state, "unread");
+        assert_eq!(articles[1].title, "Untitled");
+        assert!(articles[1].content_html.is_none());
+    }
+}
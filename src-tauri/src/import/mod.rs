@@ -0,0 +1,389 @@
+pub mod opml;
+pub mod miniflux;
+pub mod google_reader;
+pub mod feedly;
+
+use crate::storage::Database;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Outcome of importing a single entry (a feed, or a saved item for reader exports).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportEntryResult {
+    pub title: String,
+    pub url: String,
+    pub status: String, // "imported", "duplicate", "invalid", or "failed"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<i64>,
+    pub groups: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>, // "rss" or "atom" when the import declares it; feeds are sniffed on sync anyway
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Summary returned by the import commands, with one result per entry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+    pub entries: Vec<ImportEntryResult>,
+    #[serde(skip)]
+    pub created_source_ids: Vec<i64>, // Sources to sync once the import is done
+}
+
+impl ImportReport {
+    pub fn push(&mut self, entry: ImportEntryResult) {
+        match entry.status.as_str() {
+            "imported" => self.imported += 1,
+            "duplicate" => self.duplicates += 1,
+            _ => self.failed += 1,
+        }
+        self.entries.push(entry);
+    }
+}
+
+/// Normalizes a feed URL for duplicate detection (scheme/host case, trailing slash).
+pub fn normalize_feed_url(url: &str) -> String {
+    let trimmed = url.trim();
+    match reqwest::Url::parse(trimmed) {
+        Ok(parsed) => parsed.to_string().trim_end_matches('/').to_string(),
+        Err(_) => trimmed.trim_end_matches('/').to_string(),
+    }
+}
+
+/// Existing feed sources keyed by normalized URL.
+pub fn existing_feed_sources(db: &Database) -> Result<HashMap<String, i64>> {
+    let sources = db.get_all_sources()?
+        .into_iter()
+        .filter(|s| matches!(s.source_type.as_str(), "feed" | "rss" | "atom"))
+        .filter_map(|s| {
+            serde_json::from_str::<serde_json::Value>(&s.config_json).ok()
+                .and_then(|config| config.get("url").and_then(|u| u.as_str()).map(normalize_feed_url))
+                .map(|url| (url, s.id))
+        })
+        .collect();
+    Ok(sources)
+}
+
+fn is_http_url(url: &str) -> bool {
+    (url.starts_with("http://") || url.starts_with("https://")) && reqwest::Url::parse(url).is_ok()
+}
+
+/// Creates a `feed` source polling `url`, assigning the given groups.
+fn create_feed_source(db: &Database, groups: &mut GroupResolver, title: &str, url: &str, group_names: &[String]) -> Result<i64> {
+    let group_ids = groups.resolve(db, group_names)?;
+    let name = if title.trim().is_empty() { url.to_string() } else { title.trim().to_string() };
+    let config = serde_json::json!({
+        "url": url,
+        "poll_interval": "10m",
+    });
+    Ok(db.create_source("feed", &name, &config.to_string(), Some(&group_ids), None)?)
+}
+
+/// Plain-text summary from article HTML, trimmed to a list-friendly length.
+pub fn summary_from_html(html: &str) -> Option<String> {
+    let text = crate::ingestion::utils::strip_html(html);
+    if text.is_empty() {
+        return None;
+    }
+    let mut summary: String = text.chars().take(500).collect();
+    if summary.len() < text.len() {
+        summary.push_str("...");
+    }
+    Some(summary)
+}
+
+/// Extracts the feed URL from a Google Reader style stream id ("feed/https://...").
+pub fn stream_feed_url(stream_id: &str) -> Option<String> {
+    stream_id.strip_prefix("feed/")
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Resolves group names to ids, matching existing groups case-insensitively
+/// and creating the missing ones on first use.
+pub struct GroupResolver {
+    ids_by_name: HashMap<String, i64>,
+}
+
+impl GroupResolver {
+    pub fn new(db: &Database) -> Result<Self> {
+        let ids_by_name = db.get_all_groups()?
+            .into_iter()
+            .map(|g| (g.name.to_lowercase(), g.id))
+            .collect();
+        Ok(GroupResolver { ids_by_name })
+    }
+
+    pub fn resolve(&mut self, db: &Database, names: &[String]) -> Result<Vec<i64>> {
+        let mut ids = Vec::new();
+        for name in names {
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let id = match self.ids_by_name.get(&name.to_lowercase()) {
+                Some(id) => *id,
+                None => {
+                    let id = db.create_group(name)?;
+                    self.ids_by_name.insert(name.to_lowercase(), id);
+                    id
+                }
+            };
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+}
+
+/// A feed to create as a `feed` source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedFeed {
+    pub title: String,
+    pub url: String,
+    pub groups: Vec<String>,
+    pub format: Option<String>,
+}
+
+/// Creates `feed` sources for the given feeds, skipping URLs that already exist
+/// (or appear twice in the import) and creating missing groups.
+pub fn import_feeds(db: &Database, feeds: Vec<ImportedFeed>) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut known_sources = existing_feed_sources(db)?;
+    let mut groups = GroupResolver::new(db)?;
+
+    for feed in feeds {
+        let mut entry = ImportEntryResult {
+            title: feed.title.clone(),
+            url: feed.url.clone(),
+            status: "imported".to_string(),
+            source_id: None,
+            item_id: None,
+            groups: feed.groups.clone(),
+            format: feed.format.clone(),
+            message: None,
+        };
+
+        if !is_http_url(&feed.url) {
+            entry.status = "invalid".to_string();
+            entry.message = Some("Feed URL must be an absolute http(s) URL".to_string());
+            report.push(entry);
+            continue;
+        }
+
+        let normalized_url = normalize_feed_url(&feed.url);
+        if let Some(source_id) = known_sources.get(&normalized_url) {
+            entry.status = "duplicate".to_string();
+            entry.source_id = Some(*source_id);
+            entry.message = Some("A source with this URL already exists".to_string());
+            report.push(entry);
+            continue;
+        }
+
+        match create_feed_source(db, &mut groups, &feed.title, &feed.url, &feed.groups) {
+            Ok(source_id) => {
+                known_sources.insert(normalized_url, source_id);
+                report.created_source_ids.push(source_id);
+                entry.source_id = Some(source_id);
+            }
+            Err(e) => {
+                entry.status = "failed".to_string();
+                entry.message = Some(e.to_string());
+            }
+        }
+        report.push(entry);
+    }
+
+    Ok(report)
+}
+
+/// A saved or starred article exported by another feed reader.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedArticle {
+    pub external_id: String, // The feed's own guid when the export has it, otherwise the article URL
+    pub title: String,
+    pub url: String,
+    pub summary: Option<String>,
+    pub content_html: Option<String>,
+    pub author: Option<String>,
+    pub categories: Vec<String>,
+    pub published_at: Option<i64>,
+    pub state: String, // "archived" for starred/saved articles, otherwise "read" or "unread"
+    pub feed_url: Option<String>,
+    pub feed_title: Option<String>,
+    pub groups: Vec<String>, // Groups for the feed source if it has to be created
+}
+
+/// Stores articles from another reader under their feed sources, creating
+/// sources and groups as needed. Existing items are kept and marked archived
+/// when the article was starred.
+pub fn import_articles(db: &Database, articles: Vec<ImportedArticle>) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut known_sources = existing_feed_sources(db)?;
+    let mut groups = GroupResolver::new(db)?;
+
+    for article in articles {
+        let mut entry = ImportEntryResult {
+            title: article.title.clone(),
+            url: article.url.clone(),
+            status: "imported".to_string(),
+            source_id: None,
+            item_id: None,
+            groups: article.groups.clone(),
+            format: None,
+            message: None,
+        };
+
+        let feed_url = match article.feed_url.as_deref().map(str::trim) {
+            Some(url) if is_http_url(url) => url.to_string(),
+            _ => {
+                entry.status = "invalid".to_string();
+                entry.message = Some("Article has no http(s) feed URL to attach it to".to_string());
+                report.push(entry);
+                continue;
+            }
+        };
+        if article.url.is_empty() || article.external_id.is_empty() {
+            entry.status = "invalid".to_string();
+            entry.message = Some("Article has no URL".to_string());
+            report.push(entry);
+            continue;
+        }
+
+        let normalized_url = normalize_feed_url(&feed_url);
+        let source_id = match known_sources.get(&normalized_url) {
+            Some(id) => *id,
+            None => {
+                let title = article.feed_title.clone().unwrap_or_default();
+                match create_feed_source(db, &mut groups, &title, &feed_url, &article.groups) {
+                    Ok(id) => {
+                        known_sources.insert(normalized_url, id);
+                        report.created_source_ids.push(id);
+                        id
+                    }
+                    Err(e) => {
+                        entry.status = "failed".to_string();
+                        entry.message = Some(format!("Failed to create source: {}", e));
+                        report.push(entry);
+                        continue;
+                    }
+                }
+            }
+        };
+        entry.source_id = Some(source_id);
+
+        match db.import_item(source_id, &article) {
+            Ok((item_id, created)) => {
+                entry.item_id = Some(item_id);
+                if !created {
+                    entry.status = "duplicate".to_string();
+                    entry.message = Some("Item already exists".to_string());
+                }
+            }
+            Err(e) => {
+                entry.status = "failed".to_string();
+                entry.message = Some(e.to_string());
+            }
+        }
+        report.push(entry);
+    }
+
+    Ok(report)
+}
+
+/// Imports a saved/starred items export from another reader.
+/// `format` is "miniflux", "freshrss" (Google Reader JSON) or "feedly".
+pub fn import_reader_export(db: &Database, format: &str, content: &str) -> Result<ImportReport> {
+    let articles = match format {
+        "miniflux" => miniflux::parse_miniflux(content)?,
+        "freshrss" | "google_reader" => google_reader::parse_google_reader(content)?,
+        "feedly" => feedly::parse_feedly(content)?,
+        other => anyhow::bail!("Unsupported import format: {}", other),
+    };
+    import_articles(db, articles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(title: &str, url: &str, groups: &[&str]) -> ImportedFeed {
+        ImportedFeed {
+            title: title.to_string(),
+            url: url.to_string(),
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+            format: None,
+        }
+    }
+
+    #[test]
+    fn test_import_feeds_dedupes_and_creates_groups() {
+        let db = Database::new(":memory:").unwrap();
+        let tech_id = db.create_group("Tech").unwrap();
+        db.create_source("feed", "Existing", r#"{"url":"https://example.com/feed/"}"#, None, None).unwrap();
+
+        let report = import_feeds(&db, vec![
+            feed("Existing again", "https://EXAMPLE.com/feed", &[]),
+            feed("Rust", "https://blog.rust-lang.org/feed.xml", &["tech", "Rust"]),
+            feed("Rust twice", "https://blog.rust-lang.org/feed.xml", &[]),
+            feed("Broken", "feed://nope", &[]),
+        ]).unwrap();
+
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.entries[3].status, "invalid");
+
+        let source_id = report.created_source_ids[0];
+        let group_ids = db.get_source_groups(source_id).unwrap();
+        assert_eq!(group_ids.len(), 2);
+        assert!(group_ids.contains(&tech_id));
+        assert_eq!(db.get_all_groups().unwrap().len(), 2);
+        assert_eq!(db.get_source(source_id).unwrap().source_type, "feed");
+    }
+
+    #[test]
+    fn test_import_articles_keeps_timestamps_and_dedupes_on_poll() {
+        let db = Database::new(":memory:").unwrap();
+        let article = ImportedArticle {
+            external_id: "https://example.com/a".to_string(),
+            title: "Starred post".to_string(),
+            url: "https://example.com/a".to_string(),
+            published_at: Some(1714557600),
+            state: "archived".to_string(),
+            feed_url: Some("https://example.com/feed.xml".to_string()),
+            feed_title: Some("Example".to_string()),
+            groups: vec!["Tech".to_string()],
+            ..Default::default()
+        };
+
+        let report = import_articles(&db, vec![article.clone(), article]).unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.created_source_ids.len(), 1);
+
+        let source_id = report.created_source_ids[0];
+        let item_id = report.entries[0].item_id.unwrap();
+        let item = db.get_item(item_id).unwrap();
+        assert_eq!(item.state, "archived");
+        assert_eq!(item.created_at, 1714557600);
+
+        // A later poll reporting the feed's own guid adopts the imported item
+        let (polled_id, outcome) = db.upsert_item(
+            source_id, "guid-123", "Starred post", None, "https://example.com/a", "rss",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        assert_eq!(polled_id, item_id);
+        assert_eq!(outcome, crate::storage::UpsertOutcome::Updated);
+        let item = db.get_item(item_id).unwrap();
+        assert_eq!(item.external_id, "guid-123");
+        assert_eq!(item.state, "archived");
+    }
+}
@@ -0,0 +1,284 @@
+use super::{import_feeds, ImportReport, ImportedFeed};
+use crate::storage::Database;
+use anyhow::{Result, Context};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::escape::escape;
+use quick_xml::Reader;
+
+fn attribute(element: &BytesStart, reader: &Reader<&[u8]>, name: &str) -> Option<String> {
+    element.attributes()
+        .with_checks(false)
+        .flatten()
+        .find(|attr| attr.key.as_ref().eq_ignore_ascii_case(name.as_bytes()))
+        .and_then(|attr| attr.decode_and_unescape_value(reader.decoder()).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Maps the `type`/`version` attributes to a feed format. Most readers write
+/// `type="rss"` for every feed, so only an explicit ATOM marker wins.
+fn declared_format(element: &BytesStart, reader: &Reader<&[u8]>) -> Option<String> {
+    let feed_type = attribute(element, reader, "type").map(|t| t.to_lowercase());
+    let version = attribute(element, reader, "version").map(|v| v.to_lowercase());
+    if feed_type.as_deref() == Some("atom") || version.as_deref().map(|v| v.contains("atom")).unwrap_or(false) {
+        Some("atom".to_string())
+    } else if feed_type.as_deref() == Some("rss") || version.is_some() {
+        Some("rss".to_string())
+    } else {
+        None
+    }
+}
+
+/// Groups from the OPML 2.0 `category` attribute ("/Tech/Rust,/News").
+fn category_groups(category: &str) -> Vec<String> {
+    category.split(',')
+        .flat_map(|path| path.split('/'))
+        .map(|segment| segment.trim().to_string())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Parses an OPML 1.0/2.0 subscription list. Folder outlines (outlines without
+/// `xmlUrl`) become groups for every feed nested under them.
+pub fn parse_opml(content: &str) -> Result<Vec<ImportedFeed>> {
+    let mut reader = Reader::from_str(content);
+    let mut feeds = Vec::new();
+    // One entry per open <outline>: Some(name) for folders, None for feeds with children
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut saw_opml = false;
+
+    loop {
+        let event = reader.read_event()
+            .with_context(|| format!("Invalid OPML at position {}", reader.buffer_position()))?;
+        match event {
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                let name = element.local_name();
+                if name.as_ref().eq_ignore_ascii_case(b"opml") {
+                    saw_opml = true;
+                    continue;
+                }
+                if !name.as_ref().eq_ignore_ascii_case(b"outline") {
+                    continue;
+                }
+                let is_start = matches!(event, Event::Start(_));
+                let title = attribute(element, &reader, "title")
+                    .or_else(|| attribute(element, &reader, "text"));
+
+                match attribute(element, &reader, "xmlUrl") {
+                    Some(url) => {
+                        let mut groups: Vec<String> = folders.iter().flatten().cloned().collect();
+                        if let Some(category) = attribute(element, &reader, "category") {
+                            for group in category_groups(&category) {
+                                if !groups.contains(&group) {
+                                    groups.push(group);
+                                }
+                            }
+                        }
+                        feeds.push(ImportedFeed {
+                            title: title.unwrap_or_else(|| url.clone()),
+                            url,
+                            groups,
+                            format: declared_format(element, &reader),
+                        });
+                        if is_start {
+                            folders.push(None);
+                        }
+                    }
+                    None if is_start => folders.push(title),
+                    None => {}
+                }
+            }
+            Event::End(ref element) if element.local_name().as_ref().eq_ignore_ascii_case(b"outline") => {
+                folders.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !saw_opml {
+        anyhow::bail!("Not an OPML document (no <opml> root element)");
+    }
+
+    Ok(feeds)
+}
+
+/// A feed source as written to an OPML export.
+#[derive(Debug, Clone)]
+pub struct OpmlOutline {
+    pub title: String,
+    pub xml_url: String,
+    pub html_url: Option<String>,
+    pub format: Option<String>, // "rss" or "atom" if known; unified feed sources are sniffed on each poll
+    pub groups: Vec<String>,
+}
+
+fn outline_element(outline: &OpmlOutline) -> String {
+    // OPML 2.0 uses type="rss" for every kind of feed, the format goes in `version`
+    let mut element = format!(
+        r#"<outline type="rss" text="{title}" title="{title}" xmlUrl="{url}""#,
+        title = escape(outline.title.as_str()),
+        url = escape(outline.xml_url.as_str()),
+    );
+    match outline.format.as_deref() {
+        Some("atom") => element.push_str(r#" version="ATOM""#),
+        Some("rss") => element.push_str(r#" version="RSS""#),
+        _ => {}
+    }
+    if let Some(html_url) = &outline.html_url {
+        element.push_str(&format!(r#" htmlUrl="{}""#, escape(html_url.as_str())));
+    }
+    // Sources can belong to several groups; folders only hold one, so list them all here
+    if outline.groups.len() > 1 {
+        let category: Vec<String> = outline.groups.iter().map(|g| format!("/{}", g.replace(['/', ','], " "))).collect();
+        element.push_str(&format!(r#" category="{}""#, escape(category.join(",").as_str())));
+    }
+    element.push_str("/>");
+    element
+}
+
+/// Writes an OPML 2.0 document. Each feed goes in a folder named after its
+/// first group; feeds without groups sit at the top level.
+pub fn write_opml(title: &str, outlines: &[OpmlOutline], created_at: chrono::DateTime<chrono::Utc>) -> String {
+    let mut opml = String::new();
+    opml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    opml.push_str("<opml version=\"2.0\">\n");
+    opml.push_str("  <head>\n");
+    opml.push_str(&format!("    <title>{}</title>\n", escape(title)));
+    opml.push_str(&format!("    <dateCreated>{}</dateCreated>\n", created_at.to_rfc2822()));
+    opml.push_str("  </head>\n");
+    opml.push_str("  <body>\n");
+
+    // Folders in first-seen order
+    let mut folders: Vec<(&str, Vec<&OpmlOutline>)> = Vec::new();
+    for outline in outlines {
+        if let Some(group) = outline.groups.first() {
+            match folders.iter_mut().find(|(name, _)| *name == group.as_str()) {
+                Some((_, members)) => members.push(outline),
+                None => folders.push((group.as_str(), vec![outline])),
+            }
+        }
+    }
+    for (name, members) in &folders {
+        opml.push_str(&format!("    <outline text=\"{name}\" title=\"{name}\">\n", name = escape(*name)));
+        for outline in members {
+            opml.push_str(&format!("      {}\n", outline_element(outline)));
+        }
+        opml.push_str("    </outline>\n");
+    }
+    for outline in outlines.iter().filter(|o| o.groups.is_empty()) {
+        opml.push_str(&format!("    {}\n", outline_element(outline)));
+    }
+
+    opml.push_str("  </body>\n");
+    opml.push_str("</opml>\n");
+    opml
+}
+
+/// Imports an OPML document into `feed` sources and groups.
+pub fn import_opml(db: &Database, content: &str) -> Result<ImportReport> {
+    let feeds = parse_opml(content)?;
+    import_feeds(db, feeds)
+}
+
+/// Exports every feed source, with its group memberships, as OPML 2.0.
+pub fn export_opml(db: &Database) -> Result<String> {
+    let group_names: std::collections::HashMap<i64, String> = db.get_all_groups()?
+        .into_iter()
+        .map(|g| (g.id, g.name))
+        .collect();
+
+    let mut outlines = Vec::new();
+    for source in db.get_all_sources()? {
+        if !matches!(source.source_type.as_str(), "feed" | "rss" | "atom") {
+            continue;
+        }
+        let config: serde_json::Value = serde_json::from_str(&source.config_json).unwrap_or_default();
+        let url = match config.get("url").and_then(|u| u.as_str()) {
+            Some(url) if !url.trim().is_empty() => url.trim().to_string(),
+            _ => continue,
+        };
+        let groups = db.get_source_groups(source.id)?
+            .into_iter()
+            .filter_map(|id| group_names.get(&id).cloned())
+            .collect();
+        outlines.push(OpmlOutline {
+            title: source.name,
+            xml_url: url,
+            html_url: source.site_url.filter(|u| !u.trim().is_empty()),
+            format: match source.source_type.as_str() {
+                "rss" | "atom" => Some(source.source_type.clone()),
+                _ => None,
+            },
+            groups,
+        });
+    }
+
+    Ok(write_opml("UmbraRelay subscriptions", &outlines, chrono::Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opml_folders_and_categories() {
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head><title>Subscriptions</title></head>
+  <body>
+    <outline text="Tech">
+      <outline text="Rust">
+        <outline type="rss" text="Rust Blog" xmlUrl="https://blog.rust-lang.org/feed.xml" htmlUrl="https://blog.rust-lang.org/"/>
+      </outline>
+      <outline type="rss" version="ATOM" text="GitHub &amp; Co" xmlUrl="https://github.blog/feed/?a=1&amp;b=2"/>
+    </outline>
+    <outline type="rss" title="Loose" text="ignored" xmlUrl="https://example.com/rss" category="/News,/Daily"/>
+    <outline text="Empty folder"/>
+  </body>
+</opml>"#;
+
+        let feeds = parse_opml(opml).unwrap();
+        assert_eq!(feeds.len(), 3);
+        assert_eq!(feeds[0].title, "Rust Blog");
+        assert_eq!(feeds[0].groups, vec!["Tech".to_string(), "Rust".to_string()]);
+        assert_eq!(feeds[1].title, "GitHub & Co");
+        assert_eq!(feeds[1].url, "https://github.blog/feed/?a=1&b=2");
+        assert_eq!(feeds[1].groups, vec!["Tech".to_string()]);
+        assert_eq!(feeds[1].format.as_deref(), Some("atom"));
+        assert_eq!(feeds[2].title, "Loose");
+        assert_eq!(feeds[2].groups, vec!["News".to_string(), "Daily".to_string()]);
+
+        assert!(parse_opml("<html><body></body></html>").is_err());
+    }
+
+    #[test]
+    fn test_write_opml_round_trip() {
+        let outlines = vec![
+            OpmlOutline {
+                title: "Rust <Blog>".to_string(),
+                xml_url: "https://blog.rust-lang.org/feed.xml".to_string(),
+                html_url: None,
+                format: Some("atom".to_string()),
+                groups: vec!["Tech".to_string(), "Daily".to_string()],
+            },
+            OpmlOutline {
+                title: "Loose".to_string(),
+                xml_url: "https://example.com/rss?a=1&b=2".to_string(),
+                html_url: Some("https://example.com/".to_string()),
+                format: None,
+                groups: Vec::new(),
+            },
+        ];
+
+        let opml = write_opml("UmbraRelay", &outlines, chrono::Utc::now());
+        let feeds = parse_opml(&opml).unwrap();
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].title, "Rust <Blog>");
+        assert_eq!(feeds[0].groups, vec!["Tech".to_string(), "Daily".to_string()]);
+        assert_eq!(feeds[0].format.as_deref(), Some("atom"));
+        assert_eq!(feeds[1].url, "https://example.com/rss?a=1&b=2");
+        assert!(opml.contains(r#"htmlUrl="https://example.com/""#));
+        assert!(feeds[1].groups.is_empty());
+    }
+}
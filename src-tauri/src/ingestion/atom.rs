@@ -1,25 +1,7 @@
-use super::traits::{IngestSource, IngestedItem};
+use super::traits::{FeedMetadata, IngestedAttachment, IngestedItem, ParsedFeed};
 use super::utils;
 use anyhow::{Result, Context};
-use reqwest::blocking::Client;
 use atom_syndication::{Feed, Entry};
-use std::time::Duration;
-
-pub struct AtomIngester {
-    url: String,
-    client: Client,
-}
-
-impl AtomIngester {
-    pub fn new(url: String) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(60)) // Increased to 60 seconds for slow feeds
-            .build()
-            .context("Failed to create HTTP client")?;
-        
-        Ok(AtomIngester { url, client })
-    }
-}
 
 /// Extracts content from ATOM entry, trying content element first, then summary fallback.
 fn extract_content(entry: &Entry) -> Option<String> {
@@ -63,161 +45,164 @@ fn extract_image_url(entry: &Entry) -> Option<String> {
     None
 }
 
-impl IngestSource for AtomIngester {
-    fn poll(&self) -> Result<Vec<IngestedItem>> {
-        let response = self.client
-            .get(&self.url)
-            .send()
-            .with_context(|| format!("Failed to fetch ATOM feed from: {}", self.url))?;
-        
-        let status = response.status();
-        if !status.is_success() {
-            let error_msg = match status.as_u16() {
-                504 => format!(
-                    "Gateway timeout (504) when fetching ATOM feed from: {}. The server took too long to respond. This may indicate the feed URL is incorrect or the server is overloaded.",
-                    self.url
-                ),
-                404 => format!(
-                    "Feed not found (404) at: {}. Please verify the URL is correct. For GitHub user feeds, use: https://github.com/USERNAME.private.atom (with authentication) or https://github.com/USERNAME.atom (public)",
-                    self.url
-                ),
-                403 => format!(
-                    "Access forbidden (403) when fetching ATOM feed from: {}. The feed may require authentication or the server is blocking requests.",
-                    self.url
-                ),
-                _ => format!(
-                    "HTTP error {} when fetching ATOM feed from: {}",
-                    status, self.url
-                ),
+/// Collects links with rel="enclosure" as attachments.
+fn extract_attachments(entry: &Entry) -> Vec<IngestedAttachment> {
+    entry.links()
+        .iter()
+        .filter(|link| link.rel == "enclosure" && !link.href.is_empty())
+        .map(|link| IngestedAttachment {
+            url: link.href.clone(),
+            mime_type: link.mime_type.clone(),
+            length: link.length.as_deref().and_then(|l| l.trim().parse().ok()).filter(|len| *len > 0),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Feed title, subtitle, alternate link, language, logo and icon.
+fn extract_metadata(feed: &Feed) -> FeedMetadata {
+    let non_empty = |value: Option<&str>| {
+        value.map(str::trim).filter(|v| !v.is_empty()).map(|v| v.to_string())
+    };
+    FeedMetadata {
+        title: non_empty(Some(feed.title().value.as_str())),
+        description: feed.subtitle()
+            .map(|s| utils::strip_html(&s.value))
+            .filter(|s| !s.is_empty()),
+        site_url: non_empty(
+            feed.links()
+                .iter()
+                .find(|link| link.rel == "alternate")
+                .map(|link| link.href.as_str())
+        ),
+        language: non_empty(feed.lang()),
+        image_url: non_empty(feed.logo()),
+        icon_url: non_empty(feed.icon()),
+    }
+}
+
+/// Parses an ATOM document into its feed metadata and entries. `url` is only used
+/// in error messages; fetching and format detection happen in `FeedIngester`.
+pub fn parse_atom_feed(content: &str, url: &str) -> Result<ParsedFeed> {
+    let feed = Feed::read_from(content.as_bytes())
+        .with_context(|| {
+            // Try to provide more context about the parse error
+            let preview = if content.len() > 200 {
+                format!("{}...", &content[..200])
+            } else {
+                content.to_string()
             };
-            anyhow::bail!("{}", error_msg);
-        }
-        
-        let content = response.text()
-            .with_context(|| format!("Failed to read ATOM feed content from: {}", self.url))?;
-        
-        // Check if content is empty
-        if content.trim().is_empty() {
-            anyhow::bail!("ATOM feed from {} is empty", self.url);
-        }
-        
-        // Check if content looks like XML/ATOM
-        if !content.trim_start().starts_with("<?xml") && !content.trim_start().starts_with("<feed") {
-            anyhow::bail!(
-                "ATOM feed from {} does not appear to be valid XML (starts with: {})",
-                self.url,
-                content.chars().take(50).collect::<String>()
-            );
-        }
-        
-        let feed = Feed::read_from(content.as_bytes())
-            .with_context(|| {
-                // Try to provide more context about the parse error
-                let preview = if content.len() > 200 {
-                    format!("{}...", &content[..200])
-                } else {
-                    content.clone()
-                };
-                format!(
-                    "Failed to parse ATOM feed from {}: Invalid XML structure. Content preview: {}",
-                    self.url,
-                    preview.replace('\n', " ").replace('\r', " ")
-                )
-            })?;
-        
-        let items: Vec<IngestedItem> = feed.entries()
-            .iter()
-            .map(|entry| {
-                // Required fields
-                let external_id = entry.id.clone();
-                let title = if entry.title.value.is_empty() {
-                    "Untitled".to_string()
-                } else {
-                    entry.title.value.clone()
-                };
-                
-                // Updated timestamp (required) - use for occurred_at
-                let occurred_at = entry.updated.timestamp();
-                
-                // Published timestamp (optional) - could be used instead
-                // For now, we'll use updated as occurred_at
-                // If published exists and is different, we could store it separately later
-                
-                // Summary (recommended)
-                let summary = entry.summary()
-                    .and_then(|s| {
-                        if s.value.is_empty() {
-                            None
-                        } else {
-                            let cleaned = utils::strip_html(&s.value);
-                            if cleaned.is_empty() { None } else { Some(cleaned) }
-                        }
-                    });
-                
-                // Content (recommended)
-                let content_html = extract_content(entry);
-                
-                // Link with rel="alternate" (recommended for url)
-                let url = entry.links()
-                    .iter()
-                    .find(|link| link.rel == "alternate" || link.rel == "self")
-                    .map(|link| link.href.clone())
-                    .unwrap_or_else(|| {
-                        // Fallback to id if it's a URL
-                        if external_id.starts_with("http://") || external_id.starts_with("https://") {
-                            external_id.clone()
-                        } else {
-                            String::new()
-                        }
-                    });
-                
-                // Image URL from enclosure links
-                let image_url = extract_image_url(entry);
-                
-                // Author (recommended)
-                let author = entry.authors()
-                    .first()
-                    .map(|a| a.name.clone());
-                
-                // Categories (optional)
-                let category: Option<Vec<String>> = {
-                    let categories: Vec<String> = entry.categories()
-                        .iter()
-                        .map(|c| c.term.clone())
-                        .collect();
-                    if categories.is_empty() {
+            format!(
+                "Failed to parse ATOM feed from {}: Invalid XML structure. Content preview: {}",
+                url,
+                preview.replace('\n', " ").replace('\r', " ")
+            )
+        })?;
+    
+    let items: Vec<IngestedItem> = feed.entries()
+        .iter()
+        .map(|entry| {
+            // Required fields
+            let external_id = entry.id.clone();
+            let title = if entry.title.value.is_empty() {
+                "Untitled".to_string()
+            } else {
+                entry.title.value.clone()
+            };
+            
+            // Updated timestamp (required) - use for occurred_at
+            let occurred_at = entry.updated.timestamp();
+            
+            // Published timestamp (optional); items fall back to updated without it
+            let published_at = entry.published.map(|dt| dt.timestamp());
+            
+            // Summary (recommended)
+            let summary = entry.summary()
+                .and_then(|s| {
+                    if s.value.is_empty() {
                         None
                     } else {
-                        Some(categories)
+                        let cleaned = utils::strip_html(&s.value);
+                        if cleaned.is_empty() { None } else { Some(cleaned) }
                     }
-                };
-                
-                // ATOM doesn't have a comments field like RSS
-                // Comments would typically be in a link with rel="replies"
-                // For now, we'll leave it as None
-                let comments = entry.links()
+                });
+            
+            // Content (recommended)
+            let content_html = extract_content(entry);
+            
+            // Link with rel="alternate" (recommended for url)
+            let url = entry.links()
+                .iter()
+                .find(|link| link.rel == "alternate" || link.rel == "self")
+                .map(|link| link.href.clone())
+                .unwrap_or_else(|| {
+                    // Fallback to id if it's a URL
+                    if external_id.starts_with("http://") || external_id.starts_with("https://") {
+                        external_id.clone()
+                    } else {
+                        String::new()
+                    }
+                });
+            
+            // Image URL from enclosure links
+            let image_url = extract_image_url(entry);
+            
+            // All enclosures, audio/video ones make this a podcast episode
+            let attachments = extract_attachments(entry);
+            let item_type = if utils::has_media_attachment(&attachments) {
+                "podcast_episode"
+            } else {
+                "atom"
+            };
+            
+            // Author (recommended)
+            let author = entry.authors()
+                .first()
+                .map(|a| a.name.clone());
+            
+            // Categories (optional)
+            let category: Option<Vec<String>> = {
+                let categories: Vec<String> = entry.categories()
                     .iter()
-                    .find(|link| link.rel == "replies" || link.rel == "comments")
-                    .map(|link| link.href.clone());
-                
-                IngestedItem {
-                    external_id,
-                    title,
-                    summary,
-                    url,
-                    item_type: "atom".to_string(),
-                    occurred_at: Some(occurred_at),
-                    image_url,
-                    content_html,
-                    author,
-                    category,
-                    comments,
-                    thread_id: None,
+                    .map(|c| c.term.clone())
+                    .collect();
+                if categories.is_empty() {
+                    None
+                } else {
+                    Some(categories)
                 }
-            })
-            .collect();
-        
-        Ok(items)
-    }
+            };
+            
+            // ATOM doesn't have a comments field like RSS
+            // Comments would typically be in a link with rel="replies"
+            // For now, we'll leave it as None
+            let comments = entry.links()
+                .iter()
+                .find(|link| link.rel == "replies" || link.rel == "comments")
+                .map(|link| link.href.clone());
+            
+            IngestedItem {
+                external_id,
+                title,
+                summary,
+                url,
+                item_type: item_type.to_string(),
+                occurred_at: Some(occurred_at),
+                published_at,
+                image_url,
+                content_html,
+                author,
+                category,
+                comments,
+                comment_count: None,
+                thread_id: None,
+                attachments,
+            }
+        })
+        .collect();
+    
+    Ok(ParsedFeed {
+        metadata: extract_metadata(&feed),
+        items,
+    })
 }
-
@@ -216,12 +216,15 @@ mod tests {
             url: "http://example.com".to_string(),
             item_type: "rss".to_string(),
             occurred_at: None,
+            published_at: None,
             image_url: None,
             content_html: Some("<p>This is a long article with substantial content. ".repeat(20)),
             author: None,
             category: None,
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         };
         
         let result = detect_content_completeness(&item);
@@ -238,12 +241,15 @@ mod tests {
             url: "http://example.com".to_string(),
             item_type: "rss".to_string(),
             occurred_at: None,
+            published_at: None,
             image_url: None,
             content_html: Some("Read more...".to_string()),
             author: None,
             category: None,
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         };
         
         let result = detect_content_completeness(&item);
@@ -259,12 +265,15 @@ mod tests {
             url: "http://example.com".to_string(),
             item_type: "rss".to_string(),
             occurred_at: None,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: None,
             category: None,
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         };
         
         let result = detect_content_completeness(&item);
@@ -0,0 +1,417 @@
+use super::feed::FeedFormat;
+use anyhow::{Result, Context};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::http::{self, ClientOptions};
+use reqwest::blocking::Client;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+static LINK_TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<link\b[^>]*>").unwrap());
+static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)([a-zA-Z_:][-a-zA-Z0-9_:.]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+static YOUTUBE_CHANNEL_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#""(?:channelId|externalId)"\s*:\s*"(UC[a-zA-Z0-9_-]{22})""#).unwrap()
+});
+
+/// Paths probed when a page doesn't advertise its feeds.
+const COMMON_FEED_PATHS: &[&str] = &["/feed", "/rss.xml", "/atom.xml", "/feed.xml", "/index.xml", "/rss"];
+
+/// A feed found for a user-supplied URL, ready to be passed to `add_source`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeedCandidate {
+    pub url: String,
+    pub title: Option<String>,
+    pub source_type: String, // detected format, "rss" or "atom"
+    pub discovered_by: String, // "direct", "known_site", "link_tag", or "common_path"
+}
+
+/// Detects whether a document is an RSS or ATOM feed by looking at its root element.
+fn sniff_feed_type(content: &str) -> Option<&'static str> {
+    FeedFormat::detect(content).map(|format| format.item_type())
+}
+
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    ATTRIBUTE_REGEX.captures_iter(tag)
+        .map(|caps| {
+            let name = caps[1].to_lowercase();
+            let value = caps.get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map(|m| m.as_str().trim().to_string())
+                .unwrap_or_default();
+            (name, value)
+        })
+        .collect()
+}
+
+/// Extracts feeds advertised with `<link rel="alternate">` tags, resolving relative hrefs against `base`.
+pub fn find_alternate_links(html: &str, base: &Url) -> Vec<FeedCandidate> {
+    let mut candidates = Vec::new();
+
+    for tag in LINK_TAG_REGEX.find_iter(html) {
+        let attributes = parse_attributes(tag.as_str());
+        let get = |name: &str| attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str());
+
+        let is_alternate = get("rel")
+            .map(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")))
+            .unwrap_or(false);
+        if !is_alternate {
+            continue;
+        }
+
+        let source_type = match get("type").map(|t| t.to_lowercase()) {
+            Some(t) if t.contains("rss") || t.contains("rdf") => "rss",
+            Some(t) if t.contains("atom") => "atom",
+            _ => continue,
+        };
+
+        let href = match get("href") {
+            Some(href) if !href.is_empty() => href,
+            _ => continue,
+        };
+
+        if let Ok(url) = base.join(href) {
+            candidates.push(FeedCandidate {
+                url: url.to_string(),
+                title: get("title").filter(|t| !t.is_empty()).map(|t| t.to_string()),
+                source_type: source_type.to_string(),
+                discovered_by: "link_tag".to_string(),
+            });
+        }
+    }
+
+    candidates
+}
+
+/// Extracts icons advertised with `<link rel="icon">` (including "shortcut icon" and
+/// "apple-touch-icon"), resolving relative hrefs against `base`. Plain icons come first.
+pub fn find_icon_links(html: &str, base: &Url) -> Vec<String> {
+    let mut icons = Vec::new();
+    let mut touch_icons = Vec::new();
+
+    for tag in LINK_TAG_REGEX.find_iter(html) {
+        let attributes = parse_attributes(tag.as_str());
+        let get = |name: &str| attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str());
+
+        let rels: Vec<String> = get("rel")
+            .map(|rel| rel.split_whitespace().map(|r| r.to_lowercase()).collect())
+            .unwrap_or_default();
+        let href = match get("href") {
+            Some(href) if !href.is_empty() && !href.starts_with("data:") => href,
+            _ => continue,
+        };
+        let url = match base.join(href) {
+            Ok(url) => url.to_string(),
+            Err(_) => continue,
+        };
+
+        if rels.iter().any(|r| r == "icon") {
+            icons.push(url);
+        } else if rels.iter().any(|r| r == "apple-touch-icon" || r == "apple-touch-icon-precomposed") {
+            touch_icons.push(url);
+        }
+    }
+
+    icons.extend(touch_icons);
+    icons
+}
+
+/// The page's `<link rel="canonical">` target, resolved against `base`.
+pub fn find_canonical_link(html: &str, base: &Url) -> Option<Url> {
+    LINK_TAG_REGEX.find_iter(html).find_map(|tag| {
+        let attributes = parse_attributes(tag.as_str());
+        let get = |name: &str| attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str());
+        let is_canonical = get("rel")
+            .map(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("canonical")))
+            .unwrap_or(false);
+        match get("href") {
+            Some(href) if is_canonical && !href.is_empty() => base.join(href).ok(),
+            _ => None,
+        }
+    })
+}
+
+fn known_site_candidate(url: String, title: &str, source_type: &str) -> FeedCandidate {
+    FeedCandidate {
+        url,
+        title: Some(title.to_string()),
+        source_type: source_type.to_string(),
+        discovered_by: "known_site".to_string(),
+    }
+}
+
+/// Maps well-known site URLs to their likely feeds without fetching anything. These are
+/// guesses from the URL shape (not every `github.com/a/b` is a repository), so
+/// `discover_feeds` only returns the ones that turn out to serve a feed.
+/// YouTube handles (`/@name`) need the channel page, so they are resolved in `discover_feeds`.
+pub fn resolve_known_site(url: &Url) -> Vec<FeedCandidate> {
+    let host = url.host_str().unwrap_or("").trim_start_matches("www.").trim_start_matches("m.").to_lowercase();
+    let segments: Vec<&str> = url.path_segments()
+        .map(|s| s.filter(|seg| !seg.is_empty()).collect())
+        .unwrap_or_default();
+
+    match host.as_str() {
+        "youtube.com" => {
+            if let Some(playlist_id) = url.query_pairs().find(|(k, _)| k == "list").map(|(_, v)| v.to_string()) {
+                return vec![known_site_candidate(
+                    format!("https://www.youtube.com/feeds/videos.xml?playlist_id={}", playlist_id),
+                    "YouTube playlist videos",
+                    "atom",
+                )];
+            }
+            match segments.as_slice() {
+                ["channel", channel_id, ..] => vec![known_site_candidate(
+                    format!("https://www.youtube.com/feeds/videos.xml?channel_id={}", channel_id),
+                    "YouTube channel videos",
+                    "atom",
+                )],
+                ["user", user, ..] => vec![known_site_candidate(
+                    format!("https://www.youtube.com/feeds/videos.xml?user={}", user),
+                    "YouTube channel videos",
+                    "atom",
+                )],
+                _ => Vec::new(),
+            }
+        }
+        "github.com" => match segments.as_slice() {
+            [owner, repo, ..] if !["orgs", "settings", "notifications", "marketplace", "features", "topics", "collections", "sponsors", "apps", "explore"].contains(owner) => {
+                let repo = repo.trim_end_matches(".git");
+                vec![
+                    known_site_candidate(
+                        format!("https://github.com/{}/{}/releases.atom", owner, repo),
+                        &format!("{}/{} releases", owner, repo),
+                        "atom",
+                    ),
+                    known_site_candidate(
+                        format!("https://github.com/{}/{}/tags.atom", owner, repo),
+                        &format!("{}/{} tags", owner, repo),
+                        "atom",
+                    ),
+                ]
+            }
+            _ => Vec::new(),
+        },
+        "reddit.com" | "old.reddit.com" => match segments.as_slice() {
+            ["r", subreddit, ..] => vec![known_site_candidate(
+                format!("https://www.reddit.com/r/{}/.rss", subreddit),
+                &format!("r/{}", subreddit),
+                "atom",
+            )],
+            ["user", user, ..] | ["u", user, ..] => vec![known_site_candidate(
+                format!("https://www.reddit.com/user/{}/.rss", user),
+                &format!("u/{}", user),
+                "atom",
+            )],
+            _ => Vec::new(),
+        },
+        _ => {
+            // Mastodon (and compatible) profiles: https://instance/@user
+            match segments.as_slice() {
+                [handle] if handle.starts_with('@') && handle.len() > 1 && !handle.ends_with(".rss") => {
+                    let scheme = url.scheme();
+                    let authority = url.host_str().unwrap_or("");
+                    let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+                    vec![known_site_candidate(
+                        format!("{}://{}{}/{}.rss", scheme, authority, port, handle),
+                        &format!("{} on {}", handle, authority),
+                        "rss",
+                    )]
+                }
+                _ => Vec::new(),
+            }
+        }
+    }
+}
+
+/// Normalizes user input into an absolute URL, defaulting to https.
+fn parse_input_url(input: &str) -> Result<Url> {
+    let trimmed = input.trim();
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+    Url::parse(&with_scheme).with_context(|| format!("Invalid URL: {}", input))
+}
+
+fn fetch_text(client: &Client, url: &str) -> Result<(Url, String)> {
+    let response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to fetch {}", url))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP error {} when fetching {}", response.status(), url);
+    }
+
+    let final_url = response.url().clone();
+    let body = response.text()
+        .with_context(|| format!("Failed to read response from {}", url))?;
+    Ok((final_url, body))
+}
+
+/// Whether `url` serves a feed right now, for candidates guessed without fetching.
+fn serves_feed(client: &Client, url: &str) -> bool {
+    match fetch_text(client, url) {
+        Ok((_, body)) => sniff_feed_type(&body).is_some(),
+        Err(e) => {
+            eprintln!("Feed discovery: skipping {} ({})", url, e);
+            false
+        }
+    }
+}
+
+fn push_unique(candidates: &mut Vec<FeedCandidate>, candidate: FeedCandidate) {
+    if !candidates.iter().any(|c| c.url == candidate.url) {
+        candidates.push(candidate);
+    }
+}
+
+/// Finds feeds for any URL: the URL itself if it is a feed, well-known site feeds,
+/// `<link rel="alternate">` tags, and finally common feed paths on the same host.
+pub fn discover_feeds(input: &str) -> Result<Vec<FeedCandidate>> {
+    let url = parse_input_url(input)?;
+    let client = http::client(ClientOptions::with_timeout(15))?;
+
+    let mut candidates = Vec::new();
+    for candidate in resolve_known_site(&url) {
+        if serves_feed(&client, &candidate.url) {
+            push_unique(&mut candidates, candidate);
+        }
+    }
+
+    let page = fetch_text(&client, url.as_str());
+    let (final_url, body) = match page {
+        Ok(page) => page,
+        Err(e) if !candidates.is_empty() => {
+            eprintln!("Feed discovery: failed to fetch {} ({}), using known-site feeds only", url, e);
+            return Ok(candidates);
+        }
+        Err(e) => return Err(e),
+    };
+
+    // The URL may already be a feed
+    if let Some(source_type) = sniff_feed_type(&body) {
+        push_unique(&mut candidates, FeedCandidate {
+            url: final_url.to_string(),
+            title: None,
+            source_type: source_type.to_string(),
+            discovered_by: "direct".to_string(),
+        });
+        return Ok(candidates);
+    }
+
+    // YouTube handle pages embed the channel id
+    let is_youtube = final_url.host_str().map(|h| h.ends_with("youtube.com")).unwrap_or(false);
+    if is_youtube {
+        if let Some(caps) = YOUTUBE_CHANNEL_ID_REGEX.captures(&body) {
+            push_unique(&mut candidates, known_site_candidate(
+                format!("https://www.youtube.com/feeds/videos.xml?channel_id={}", &caps[1]),
+                "YouTube channel videos",
+                "atom",
+            ));
+        }
+    }
+
+    for candidate in find_alternate_links(&body, &final_url) {
+        push_unique(&mut candidates, candidate);
+    }
+
+    if candidates.is_empty() {
+        for path in COMMON_FEED_PATHS {
+            let probe_url = match final_url.join(path) {
+                Ok(u) => u,
+                Err(_) => continue,
+            };
+            if let Ok((resolved_url, probe_body)) = fetch_text(&client, probe_url.as_str()) {
+                if let Some(source_type) = sniff_feed_type(&probe_body) {
+                    push_unique(&mut candidates, FeedCandidate {
+                        url: resolved_url.to_string(),
+                        title: None,
+                        source_type: source_type.to_string(),
+                        discovered_by: "common_path".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_canonical_link() {
+        let html = r#"<head><link rel="alternate" href="/feed"><link href='/posts/1' rel='canonical'></head>"#;
+        let base = Url::parse("https://example.com/posts/1?utm_source=rss").unwrap();
+        assert_eq!(find_canonical_link(html, &base).unwrap().as_str(), "https://example.com/posts/1");
+        assert!(find_canonical_link("<head></head>", &base).is_none());
+    }
+
+    #[test]
+    fn test_find_alternate_links() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link type='application/atom+xml' href='/atom.xml' rel='alternate' title='Atom'>
+            <link rel="alternate" type="application/rss+xml" title="Blog RSS" href="https://cdn.example.com/rss">
+            </head></html>"#;
+        let base = Url::parse("https://example.com/blog/").unwrap();
+        let links = find_alternate_links(html, &base);
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].url, "https://example.com/atom.xml");
+        assert_eq!(links[0].source_type, "atom");
+        assert_eq!(links[1].url, "https://cdn.example.com/rss");
+        assert_eq!(links[1].title.as_deref(), Some("Blog RSS"));
+    }
+
+    #[test]
+    fn test_find_icon_links() {
+        let html = r#"<head>
+            <link rel="apple-touch-icon" href="/touch.png">
+            <link rel="shortcut icon" href="favicon.ico">
+            <link rel="icon" href="data:image/png;base64,AAAA">
+            </head>"#;
+        let base = Url::parse("https://example.com/blog/").unwrap();
+
+        assert_eq!(
+            find_icon_links(html, &base),
+            vec!["https://example.com/blog/favicon.ico".to_string(), "https://example.com/touch.png".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve_known_sites() {
+        let youtube = Url::parse("https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv").unwrap();
+        assert_eq!(
+            resolve_known_site(&youtube)[0].url,
+            "https://www.youtube.com/feeds/videos.xml?channel_id=UCabcdefghijklmnopqrstuv"
+        );
+
+        let github = Url::parse("https://github.com/rust-lang/rust/pulls").unwrap();
+        assert_eq!(resolve_known_site(&github)[0].url, "https://github.com/rust-lang/rust/releases.atom");
+        assert!(resolve_known_site(&Url::parse("https://github.com/features/actions").unwrap()).is_empty());
+
+        let reddit = Url::parse("https://old.reddit.com/r/rust/").unwrap();
+        assert_eq!(resolve_known_site(&reddit)[0].url, "https://www.reddit.com/r/rust/.rss");
+
+        let mastodon = Url::parse("https://hachyderm.io/@alice").unwrap();
+        let feeds = resolve_known_site(&mastodon);
+        assert_eq!(feeds[0].url, "https://hachyderm.io/@alice.rss");
+        assert_eq!(feeds[0].source_type, "rss");
+
+        let blog = Url::parse("https://example.com/posts").unwrap();
+        assert!(resolve_known_site(&blog).is_empty());
+    }
+}
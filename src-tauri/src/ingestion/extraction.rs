@@ -1,38 +1,38 @@
 use anyhow::{Result, Context};
-use reqwest::blocking::Client;
-use std::time::Duration;
+use crate::http::{self, ClientOptions, SendCounted};
 use readabilityrs::{Readability, ReadabilityOptions};
 use ammonia::clean;
+use crate::normalization::canonical::canonical_url;
 
 #[derive(Debug, Clone)]
 pub struct ExtractionResult {
     pub content: String,
     #[allow(dead_code)] // Reserved for future use (e.g., comparing with feed title)
     pub title: Option<String>,
+    pub canonical_url: Option<String>, // From the page's <link rel="canonical">, canonicalized
 }
 
 /// Extracts full article content from a URL using readability algorithm.
 /// Sanitizes HTML for safe rendering.
 pub fn extract_full_text(url: &str) -> Result<ExtractionResult> {
     // Create HTTP client with timeout
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .user_agent("UmbraRelay/1.0")
-        .build()
-        .context("Failed to create HTTP client")?;
+    let client = http::client(ClientOptions::with_timeout(30))?;
     
     // Fetch the HTML
     let response = client
         .get(url)
-        .send()
+        .send_counted()
         .context("Failed to fetch article URL")?;
     
     if !response.status().is_success() {
         anyhow::bail!("HTTP error {} when fetching article", response.status());
     }
     
+    let final_url = response.url().clone();
     let html = response.text()
         .context("Failed to read response body")?;
+    let canonical_url = super::discovery::find_canonical_link(&html, &final_url)
+        .and_then(|link| canonical_url(link.as_str()));
     
     // Use readability to extract main content
     let options = ReadabilityOptions::default();
@@ -53,6 +53,7 @@ pub fn extract_full_text(url: &str) -> Result<ExtractionResult> {
     Ok(ExtractionResult {
         content: sanitized,
         title: extracted_title,
+        canonical_url,
     })
 }
 
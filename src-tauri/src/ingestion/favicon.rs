@@ -0,0 +1,166 @@
+use super::discovery::find_icon_links;
+use super::traits::FeedMetadata;
+use crate::http::{self, ClientOptions};
+use anyhow::{Result, Context};
+use reqwest::blocking::Client;
+use reqwest::Url;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Icons larger than this are skipped; favicons are a few KB at most.
+const MAX_FAVICON_BYTES: u64 = 256 * 1024;
+
+/// Extensions a cached favicon can have, in the order they are probed.
+const FAVICON_EXTENSIONS: &[&str] = &["png", "ico", "gif", "jpg", "webp", "svg"];
+
+/// Detects the image format of a downloaded icon from its leading bytes.
+/// Servers often answer /favicon.ico with an HTML error page, so the content type is not trusted.
+pub fn sniff_image_extension(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.starts_with(&[0, 0, 1, 0]) {
+        Some("ico")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("gif")
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("jpg")
+    } else if bytes.len() > 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_lowercase();
+        if head.contains("<svg") { Some("svg") } else { None }
+    }
+}
+
+fn mime_type_for_extension(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "ico" => "image/x-icon",
+        "gif" => "image/gif",
+        "jpg" => "image/jpeg",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Icon URLs to try for a feed: the ATOM icon, icons linked from the site's homepage,
+/// then /favicon.ico on the site and on the feed's host.
+fn favicon_candidates(client: &Client, metadata: &FeedMetadata, feed_url: &str) -> Vec<String> {
+    let mut candidates: Vec<String> = metadata.icon_url.iter().cloned().collect();
+
+    let site = metadata.site_url.as_deref()
+        .and_then(|u| Url::parse(u).ok())
+        .or_else(|| Url::parse(feed_url).ok().and_then(|u| u.join("/").ok()));
+
+    if let Some(site) = &site {
+        let homepage = client.get(site.as_str())
+            .send()
+            .ok()
+            .filter(|r| r.status().is_success())
+            .and_then(|r| r.text().ok());
+        if let Some(html) = homepage {
+            candidates.extend(find_icon_links(&html, site));
+        }
+    }
+
+    let roots = site.iter().chain(Url::parse(feed_url).ok().iter())
+        .filter_map(|u| u.join("/favicon.ico").ok())
+        .map(|u| u.to_string())
+        .collect::<Vec<_>>();
+    candidates.extend(roots);
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|c| seen.insert(c.clone()));
+    candidates
+}
+
+/// Downloads an icon, returning its bytes and extension if it is a usable image.
+fn download_icon(client: &Client, url: &str) -> Option<(Vec<u8>, &'static str)> {
+    let response = client.get(url).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+
+    let mut bytes = Vec::new();
+    response.take(MAX_FAVICON_BYTES + 1).read_to_end(&mut bytes).ok()?;
+    if bytes.is_empty() || bytes.len() as u64 > MAX_FAVICON_BYTES {
+        return None;
+    }
+
+    let extension = sniff_image_extension(&bytes)?;
+    Some((bytes, extension))
+}
+
+/// Fetches the favicon for a feed source and caches it as `<dir>/<source_id>.<ext>`,
+/// replacing any previously cached icon for the source. Returns the cached file's path.
+pub fn fetch_favicon(metadata: &FeedMetadata, feed_url: &str, dir: &Path, source_id: i64) -> Result<PathBuf> {
+    let client = http::client(ClientOptions::with_timeout(15))?;
+
+    let (bytes, extension) = favicon_candidates(&client, metadata, feed_url)
+        .iter()
+        .find_map(|url| download_icon(&client, url))
+        .ok_or_else(|| anyhow::anyhow!("No favicon found for {}", feed_url))?;
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create favicon directory: {:?}", dir))?;
+    remove_cached_favicon(dir, source_id);
+
+    let path = dir.join(format!("{}.{}", source_id, extension));
+    std::fs::write(&path, bytes)
+        .with_context(|| format!("Failed to write favicon: {:?}", path))?;
+
+    Ok(path)
+}
+
+/// Deletes every cached icon of a source.
+pub fn remove_cached_favicon(dir: &Path, source_id: i64) {
+    for extension in FAVICON_EXTENSIONS {
+        let _ = std::fs::remove_file(dir.join(format!("{}.{}", source_id, extension)));
+    }
+}
+
+/// Reads a cached favicon as a `data:` URL, so the UI can show it without any remote load.
+pub fn favicon_data_url(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    let extension = sniff_image_extension(&bytes)?;
+    Some(format!("data:{};base64,{}", mime_type_for_extension(extension), encode_base64(&bytes)))
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image_extension() {
+        assert_eq!(sniff_image_extension(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("png"));
+        assert_eq!(sniff_image_extension(&[0, 0, 1, 0, 1, 0, 16, 16]), Some("ico"));
+        assert_eq!(sniff_image_extension(b"<?xml version=\"1.0\"?><svg xmlns=\"http://www.w3.org/2000/svg\"/>"), Some("svg"));
+        assert_eq!(sniff_image_extension(b"<!DOCTYPE html><html><body>Not found</body></html>"), None);
+    }
+
+    #[test]
+    fn test_encode_base64() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+    }
+}
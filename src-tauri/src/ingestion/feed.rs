@@ -0,0 +1,395 @@
+use super::traits::{FeedMetadata, IngestSource, IngestedItem, ParsedFeed};
+use super::atom::parse_atom_feed;
+use super::rss::parse_rss_feed;
+use crate::http::{self, ClientOptions, SendCounted};
+use anyhow::{Result, Context};
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// Syndication formats understood by the `feed` source type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Rdf,
+    Atom,
+}
+
+impl FeedFormat {
+    /// Detects the format of a document from its root element.
+    pub fn detect(content: &str) -> Option<Self> {
+        let root = root_element_name(content)?;
+        let local_name = root.rsplit(':').next().unwrap_or(&root);
+        match local_name {
+            "rss" => Some(FeedFormat::Rss),
+            "rdf" => Some(FeedFormat::Rdf),
+            "feed" => Some(FeedFormat::Atom),
+            _ => None,
+        }
+    }
+
+    /// Item type stored for entries of this format (RDF is RSS 0.9/1.0).
+    pub fn item_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss | FeedFormat::Rdf => "rss",
+            FeedFormat::Atom => "atom",
+        }
+    }
+}
+
+/// Returns the lowercased name of the document's root element, skipping the XML
+/// declaration, processing instructions, comments and doctype.
+pub fn root_element_name(content: &str) -> Option<String> {
+    let mut rest = content.trim_start_matches('\u{feff}');
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("<?") {
+            rest = &rest[rest.find("?>")? + 2..];
+        } else if rest.starts_with("<!--") {
+            rest = &rest[rest.find("-->")? + 3..];
+        } else if rest.starts_with("<!") {
+            rest = &rest[rest.find('>')? + 1..];
+        } else if let Some(tag) = rest.strip_prefix('<') {
+            let name: String = tag
+                .chars()
+                .take_while(|c| !c.is_whitespace() && *c != '>' && *c != '/')
+                .collect();
+            return if name.is_empty() { None } else { Some(name.to_lowercase()) };
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Makes the metadata's links absolute; ATOM logos and icons are often relative to the feed.
+fn resolve_metadata_urls(metadata: &mut FeedMetadata, feed_url: &str) {
+    let base = match Url::parse(feed_url) {
+        Ok(base) => base,
+        Err(_) => return,
+    };
+    for link in [&mut metadata.site_url, &mut metadata.image_url, &mut metadata.icon_url] {
+        *link = link.take().and_then(|href| {
+            base.join(&href)
+                .ok()
+                .filter(|u| u.scheme() == "http" || u.scheme() == "https")
+                .map(|u| u.to_string())
+        });
+    }
+}
+
+/// Parses an already fetched feed document, routing it to the parser for its format.
+pub fn parse_feed(content: &str, url: &str) -> Result<ParsedFeed> {
+    if content.trim().is_empty() {
+        anyhow::bail!("Feed from {} is empty", url);
+    }
+
+    let mut parsed = match FeedFormat::detect(content) {
+        Some(FeedFormat::Rss) | Some(FeedFormat::Rdf) => parse_rss_feed(content)
+            .with_context(|| format!("Failed to parse RSS feed from {}", url))?,
+        Some(FeedFormat::Atom) => parse_atom_feed(content, url)?,
+        None => anyhow::bail!(
+            "Content from {} is not an RSS, RDF or ATOM feed (starts with: {})",
+            url,
+            content.trim_start().chars().take(50).collect::<String>()
+        ),
+    };
+    resolve_metadata_urls(&mut parsed.metadata, url);
+    Ok(parsed)
+}
+
+/// How a private feed authenticates, as stored under `auth` in the source config.
+/// Only the scheme and non-secret parameters live here; the credential itself comes
+/// from the secret referenced by the source's `secret_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedAuthConfig {
+    pub scheme: String, // "basic", "bearer", "header" or "query"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>, // basic: if missing, the secret is "username:password"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_name: Option<String>, // header: e.g. "X-API-Key"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_param: Option<String>, // query: e.g. "token"
+}
+
+impl FeedAuthConfig {
+    /// Reads the `auth` object of a feed source config. Returns None for public feeds.
+    pub fn from_config(config: &serde_json::Value) -> Result<Option<Self>> {
+        match config.get("auth") {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(auth) => serde_json::from_value(auth.clone())
+                .map(Some)
+                .context("Invalid feed auth config"),
+        }
+    }
+
+    /// Combines the config with the secret value into the credentials to send.
+    pub fn resolve(&self, secret: String) -> Result<FeedAuth> {
+        let non_empty = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(|v| v.to_string());
+
+        match self.scheme.as_str() {
+            "basic" => {
+                let (username, password) = match non_empty(&self.username) {
+                    Some(username) => (username, secret),
+                    None => {
+                        let (username, password) = secret.split_once(':')
+                            .ok_or_else(|| anyhow::anyhow!("Basic auth needs a username in the source config or a \"username:password\" secret"))?;
+                        (username.to_string(), password.to_string())
+                    }
+                };
+                Ok(FeedAuth::Basic { username, password })
+            }
+            "bearer" => Ok(FeedAuth::Bearer(secret)),
+            "header" => {
+                let name = non_empty(&self.header_name)
+                    .ok_or_else(|| anyhow::anyhow!("Header auth needs a header_name"))?;
+                let name = HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("Invalid auth header name: {}", name))?;
+                let mut value = HeaderValue::from_str(&secret)
+                    .context("Secret is not a valid header value")?;
+                value.set_sensitive(true);
+                Ok(FeedAuth::Header { name, value })
+            }
+            "query" => {
+                let param = non_empty(&self.query_param)
+                    .ok_or_else(|| anyhow::anyhow!("Query auth needs a query_param"))?;
+                Ok(FeedAuth::Query { param, token: secret })
+            }
+            other => anyhow::bail!("Unknown feed auth scheme: {}", other),
+        }
+    }
+}
+
+/// Rejects a feed config whose `auth` object carries anything but the non-secret
+/// auth parameters, so a credential is never written to `config_json`.
+pub fn check_auth_secrets(config: &serde_json::Value) -> Result<()> {
+    if let Some(auth) = config.get("auth").and_then(|a| a.as_object()) {
+        if let Some(key) = auth.keys().find(|key| !matches!(key.as_str(), "scheme" | "username" | "header_name" | "query_param")) {
+            anyhow::bail!("Feed auth config can't hold \"{}\", store the credential as the source's secret instead", key);
+        }
+    }
+    Ok(())
+}
+
+/// Credentials applied to every feed request.
+#[derive(Clone)]
+pub enum FeedAuth {
+    Basic { username: String, password: String },
+    Bearer(String),
+    Header { name: HeaderName, value: HeaderValue },
+    Query { param: String, token: String },
+}
+
+impl FeedAuth {
+    fn apply(&self, client: &Client, url: &str) -> Result<RequestBuilder> {
+        Ok(match self {
+            FeedAuth::Basic { username, password } => client.get(url).basic_auth(username, Some(password)),
+            FeedAuth::Bearer(token) => client.get(url).bearer_auth(token),
+            FeedAuth::Header { name, value } => client.get(url).header(name.clone(), value.clone()),
+            FeedAuth::Query { param, token } => {
+                let mut url = Url::parse(url).with_context(|| format!("Invalid feed URL: {}", url))?;
+                url.query_pairs_mut().append_pair(param, token);
+                client.get(url)
+            }
+        })
+    }
+}
+
+/// Ingester for the unified `feed` source type: fetches the URL once and
+/// parses it as RSS 2.0, RDF or ATOM depending on what the server returned.
+pub struct FeedIngester {
+    url: String,
+    client: Client,
+    auth: Option<FeedAuth>,
+}
+
+impl FeedIngester {
+    /// `user_agent` overrides the configured user agent for this source (config `user_agent`).
+    pub fn new(url: String, user_agent: Option<String>) -> Result<Self> {
+        // 60 seconds for slow feeds
+        let client = http::client(ClientOptions::with_timeout(60).user_agent(user_agent))?;
+
+        Ok(FeedIngester { url, client, auth: None })
+    }
+
+    /// Sends the given credentials with every request of this ingester.
+    pub fn with_auth(mut self, auth: Option<FeedAuth>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Fetches and parses the feed, keeping the channel metadata that `poll` drops.
+    pub fn fetch(&self) -> Result<ParsedFeed> {
+        let request = match &self.auth {
+            Some(auth) => auth.apply(&self.client, &self.url)?,
+            None => self.client.get(&self.url),
+        };
+        // Drop the URL from transport errors, it may carry a query token
+        let response = request
+            .send_counted()
+            .map_err(|e| match e.downcast::<reqwest::Error>() {
+                Ok(e) => anyhow::Error::new(e.without_url()),
+                Err(e) => e,
+            })
+            .with_context(|| format!("Failed to fetch feed from: {}", self.url))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_msg = match status.as_u16() {
+                504 => format!(
+                    "Gateway timeout (504) when fetching feed from: {}. The server took too long to respond. This may indicate the feed URL is incorrect or the server is overloaded.",
+                    self.url
+                ),
+                404 => format!(
+                    "Feed not found (404) at: {}. Please verify the URL is correct. For GitHub user feeds, use: https://github.com/USERNAME.private.atom (with authentication) or https://github.com/USERNAME.atom (public)",
+                    self.url
+                ),
+                401 => format!(
+                    "Authentication failed (401) when fetching feed from: {}. Check the auth scheme and the secret configured for this source.",
+                    self.url
+                ),
+                403 => format!(
+                    "Access forbidden (403) when fetching feed from: {}. The feed may require authentication or the server is blocking requests.",
+                    self.url
+                ),
+                _ => format!(
+                    "HTTP error {} when fetching feed from: {}",
+                    status, self.url
+                ),
+            };
+            return Err(http::status_error(&response).context(error_msg));
+        }
+
+        let content = response.text()
+            .map_err(|e| e.without_url())
+            .with_context(|| format!("Failed to read feed content from: {}", self.url))?;
+
+        parse_feed(&content, &self.url)
+    }
+}
+
+impl IngestSource for FeedIngester {
+    fn poll(&self) -> Result<Vec<IngestedItem>> {
+        Ok(self.fetch()?.items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_feed_format() {
+        assert_eq!(FeedFormat::detect("<?xml version=\"1.0\"?><rss version=\"2.0\"></rss>"), Some(FeedFormat::Rss));
+        assert_eq!(FeedFormat::detect("<feed xmlns=\"http://www.w3.org/2005/Atom\"></feed>"), Some(FeedFormat::Atom));
+        assert_eq!(
+            FeedFormat::detect("<?xml version=\"1.0\"?>\n<!-- generator -->\n<rdf:RDF xmlns:rdf=\"x\"></rdf:RDF>"),
+            Some(FeedFormat::Rdf)
+        );
+        assert_eq!(FeedFormat::detect("<!DOCTYPE html><html><body>feed</body></html>"), None);
+    }
+
+    #[test]
+    fn test_feed_auth_config() {
+        let config = serde_json::json!({"url": "https://example.org/feed", "auth": {"scheme": "basic"}});
+        let auth = FeedAuthConfig::from_config(&config).unwrap().unwrap();
+        match auth.resolve("alice:s3:cret".to_string()).unwrap() {
+            FeedAuth::Basic { username, password } => {
+                assert_eq!(username, "alice");
+                assert_eq!(password, "s3:cret");
+            }
+            _ => panic!("expected basic auth"),
+        }
+        assert!(auth.resolve("no-separator".to_string()).is_err());
+
+        let header = FeedAuthConfig { scheme: "header".to_string(), username: None, header_name: None, query_param: None };
+        assert!(header.resolve("token".to_string()).is_err());
+        assert!(FeedAuthConfig::from_config(&serde_json::json!({"url": "https://example.org/feed"})).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_check_auth_secrets() {
+        let config = serde_json::json!({
+            "url": "https://example.org/feed",
+            "auth": {"scheme": "query", "query_param": "key", "token": "leaked"}
+        });
+        let err = check_auth_secrets(&config).unwrap_err();
+        assert!(err.to_string().contains("\"token\""));
+        let config = serde_json::json!({"url": "https://example.org/feed", "auth": {"scheme": "basic", "username": "reader"}});
+        assert!(check_auth_secrets(&config).is_ok());
+        assert!(check_auth_secrets(&serde_json::json!({"url": "https://example.org/feed"})).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rdf_feed() {
+        let rdf = r#"<?xml version="1.0" encoding="utf-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel rdf:about="https://example.org/">
+    <title>Example</title>
+    <link>https://example.org/</link>
+    <description>RSS 1.0 feed</description>
+    <items>
+      <rdf:Seq>
+        <rdf:li rdf:resource="https://example.org/one" />
+        <rdf:li rdf:resource="https://example.org/two" />
+      </rdf:Seq>
+    </items>
+  </channel>
+  <item rdf:about="https://example.org/one">
+    <title>First</title>
+    <link>https://example.org/one</link>
+    <description>First entry</description>
+    <dc:creator>Alice</dc:creator>
+    <dc:date>2024-05-01T10:00:00Z</dc:date>
+  </item>
+  <item rdf:about="https://example.org/two">
+    <title>Second</title>
+    <link>https://example.org/two</link>
+  </item>
+</rdf:RDF>"#;
+
+        let parsed = parse_feed(rdf, "https://example.org/index.rdf").unwrap();
+        assert_eq!(parsed.metadata.title.as_deref(), Some("Example"));
+        assert_eq!(parsed.metadata.description.as_deref(), Some("RSS 1.0 feed"));
+        assert_eq!(parsed.metadata.site_url.as_deref(), Some("https://example.org/"));
+        let items = parsed.items;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].external_id, "https://example.org/one");
+        assert_eq!(items[0].item_type, "rss");
+        assert_eq!(items[0].author.as_deref(), Some("Alice"));
+        assert_eq!(items[0].occurred_at, Some(1714557600));
+        assert_eq!(items[1].title, "Second");
+    }
+
+    #[test]
+    fn test_parse_feed_routes_atom_and_rejects_html() {
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example</title>
+  <subtitle>Notes and links</subtitle>
+  <id>urn:example</id>
+  <updated>2024-05-01T10:00:00Z</updated>
+  <link rel="alternate" href="/"/>
+  <icon>/favicon.png</icon>
+  <logo>https://cdn.example.org/logo.png</logo>
+  <entry>
+    <title>Entry</title>
+    <id>urn:example:1</id>
+    <updated>2024-05-01T10:00:00Z</updated>
+    <link rel="alternate" href="https://example.org/1"/>
+  </entry>
+</feed>"#;
+
+        let parsed = parse_feed(atom, "https://example.org/atom.xml").unwrap();
+        assert_eq!(parsed.metadata.description.as_deref(), Some("Notes and links"));
+        assert_eq!(parsed.metadata.site_url.as_deref(), Some("https://example.org/"));
+        assert_eq!(parsed.metadata.icon_url.as_deref(), Some("https://example.org/favicon.png"));
+        assert_eq!(parsed.metadata.image_url.as_deref(), Some("https://cdn.example.org/logo.png"));
+        let items = parsed.items;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type, "atom");
+        assert_eq!(items[0].url, "https://example.org/1");
+
+        assert!(parse_feed("<html><body>Not a feed</body></html>", "https://example.org/").is_err());
+    }
+}
@@ -1,8 +1,8 @@
 use super::traits::{IngestSource, IngestedItem};
 use anyhow::{Result, Context};
+use crate::http::{self, ClientOptions, SendCounted};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct GitHubCommit {
@@ -46,21 +46,235 @@ struct GitHubPullRequest {
 pub struct GitHubIngester {
     token: String,
     repositories: Vec<String>, // Format: "owner/repo"
-    endpoints: Vec<String>, // "user", "repository", "commits", "prs"
+    endpoints: Vec<String>, // "user", "repository", "commits", "prs", "user_events"
+    users: Vec<String>, // GitHub logins followed by the "user_events" endpoint
     client: Client,
 }
 
+// The public events API only serves the most recent 300 events per user
+const MAX_USER_EVENT_PAGES: usize = 3;
+
+/// Human-readable rendering of a GitHub event (title, summary and best link).
+struct EventDescription {
+    title: String,
+    summary: Option<String>,
+    url: String,
+}
+
+fn truncate_body(body: &str) -> String {
+    if body.chars().count() > 500 {
+        format!("{}...", body.chars().take(500).collect::<String>())
+    } else {
+        body.to_string()
+    }
+}
+
+fn str_at<'a>(value: &'a serde_json::Value, path: &[&str]) -> Option<&'a str> {
+    let mut current = value;
+    for key in path {
+        current = current.get(*key)?;
+    }
+    current.as_str()
+}
+
+/// Describes a GitHub event as a sentence, e.g. "alice opened PR #12 in owner/repo: Fix parser".
+/// Falls back to the raw event type for events we don't know how to render.
+fn describe_event(event: &serde_json::Value) -> EventDescription {
+    let event_type = event.get("type").and_then(|v| v.as_str()).unwrap_or("unknown");
+    let actor = str_at(event, &["actor", "display_login"])
+        .or_else(|| str_at(event, &["actor", "login"]))
+        .unwrap_or("someone");
+    let repo = str_at(event, &["repo", "name"]).unwrap_or("unknown");
+    let repo_url = format!("https://github.com/{}", repo);
+    let payload = event.get("payload").cloned().unwrap_or(serde_json::Value::Null);
+    let action = payload.get("action").and_then(|v| v.as_str()).unwrap_or("updated");
+
+    match event_type {
+        "PushEvent" => {
+            let branch = payload.get("ref")
+                .and_then(|v| v.as_str())
+                .map(|r| r.trim_start_matches("refs/heads/").to_string())
+                .unwrap_or_else(|| "a branch".to_string());
+            let title = match payload.get("size").and_then(|v| v.as_u64()) {
+                Some(1) => format!("{} pushed 1 commit to {} in {}", actor, branch, repo),
+                Some(n) => format!("{} pushed {} commits to {} in {}", actor, n, branch, repo),
+                None => format!("{} pushed to {} in {}", actor, branch, repo),
+            };
+            let summary = payload.get("commits")
+                .and_then(|v| v.as_array())
+                .map(|commits| commits.iter()
+                    .filter_map(|c| c.get("message").and_then(|m| m.as_str()))
+                    .filter_map(|m| m.lines().next())
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+                .filter(|s| !s.is_empty());
+            let url = payload.get("head")
+                .and_then(|v| v.as_str())
+                .map(|sha| format!("{}/commit/{}", repo_url, sha))
+                .unwrap_or(repo_url);
+            EventDescription { title, summary, url }
+        }
+        "PullRequestEvent" => {
+            let pr = &payload["pull_request"];
+            let merged = pr.get("merged").and_then(|v| v.as_bool()).unwrap_or(false);
+            let verb = if action == "closed" && merged { "merged" } else { action };
+            let number = payload.get("number").or_else(|| pr.get("number")).and_then(|v| v.as_u64()).unwrap_or(0);
+            let pr_title = pr.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            EventDescription {
+                title: format!("{} {} PR #{} in {}: {}", actor, verb, number, repo, pr_title),
+                summary: pr.get("body").and_then(|v| v.as_str()).map(truncate_body),
+                url: pr.get("html_url").and_then(|v| v.as_str()).map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/pull/{}", repo_url, number)),
+            }
+        }
+        "PullRequestReviewEvent" => {
+            let pr = &payload["pull_request"];
+            let review = &payload["review"];
+            let verb = match review.get("state").and_then(|v| v.as_str()).unwrap_or("") {
+                "approved" => "approved",
+                "changes_requested" => "requested changes on",
+                _ => "reviewed",
+            };
+            let number = pr.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+            let pr_title = pr.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            EventDescription {
+                title: format!("{} {} PR #{} in {}: {}", actor, verb, number, repo, pr_title),
+                summary: review.get("body").and_then(|v| v.as_str()).filter(|b| !b.is_empty()).map(truncate_body),
+                url: review.get("html_url").or_else(|| pr.get("html_url")).and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/pull/{}", repo_url, number)),
+            }
+        }
+        "PullRequestReviewCommentEvent" => {
+            let pr = &payload["pull_request"];
+            let comment = &payload["comment"];
+            let number = pr.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+            let pr_title = pr.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            EventDescription {
+                title: format!("{} commented on PR #{} in {}: {}", actor, number, repo, pr_title),
+                summary: comment.get("body").and_then(|v| v.as_str()).map(truncate_body),
+                url: comment.get("html_url").and_then(|v| v.as_str()).map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/pull/{}", repo_url, number)),
+            }
+        }
+        "IssuesEvent" => {
+            let issue = &payload["issue"];
+            let number = issue.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+            let issue_title = issue.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            EventDescription {
+                title: format!("{} {} issue #{} in {}: {}", actor, action, number, repo, issue_title),
+                summary: issue.get("body").and_then(|v| v.as_str()).map(truncate_body),
+                url: issue.get("html_url").and_then(|v| v.as_str()).map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/issues/{}", repo_url, number)),
+            }
+        }
+        "IssueCommentEvent" => {
+            let issue = &payload["issue"];
+            let comment = &payload["comment"];
+            let kind = if issue.get("pull_request").is_some() { "PR" } else { "issue" };
+            let number = issue.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+            let issue_title = issue.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            EventDescription {
+                title: format!("{} commented on {} #{} in {}: {}", actor, kind, number, repo, issue_title),
+                summary: comment.get("body").and_then(|v| v.as_str()).map(truncate_body),
+                url: comment.get("html_url").or_else(|| issue.get("html_url")).and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/issues/{}", repo_url, number)),
+            }
+        }
+        "ReleaseEvent" => {
+            let release = &payload["release"];
+            let name = release.get("name").and_then(|v| v.as_str()).filter(|n| !n.is_empty())
+                .or_else(|| release.get("tag_name").and_then(|v| v.as_str()))
+                .unwrap_or("a release");
+            EventDescription {
+                title: format!("{} {} release {} in {}", actor, action, name, repo),
+                summary: release.get("body").and_then(|v| v.as_str()).filter(|b| !b.is_empty()).map(truncate_body),
+                url: release.get("html_url").and_then(|v| v.as_str()).map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/releases", repo_url)),
+            }
+        }
+        "CreateEvent" | "DeleteEvent" => {
+            let verb = if event_type == "CreateEvent" { "created" } else { "deleted" };
+            let ref_type = payload.get("ref_type").and_then(|v| v.as_str()).unwrap_or("ref");
+            let title = match payload.get("ref").and_then(|v| v.as_str()) {
+                Some(git_ref) if ref_type != "repository" => format!("{} {} {} {} in {}", actor, verb, ref_type, git_ref, repo),
+                _ => format!("{} {} repository {}", actor, verb, repo),
+            };
+            EventDescription {
+                title,
+                summary: payload.get("description").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                url: repo_url,
+            }
+        }
+        "ForkEvent" => {
+            let forkee = &payload["forkee"];
+            let fork_name = forkee.get("full_name").and_then(|v| v.as_str()).unwrap_or("a fork");
+            EventDescription {
+                title: format!("{} forked {} to {}", actor, repo, fork_name),
+                summary: None,
+                url: forkee.get("html_url").and_then(|v| v.as_str()).map(|s| s.to_string()).unwrap_or(repo_url),
+            }
+        }
+        "WatchEvent" => EventDescription {
+            title: format!("{} starred {}", actor, repo),
+            summary: None,
+            url: repo_url,
+        },
+        "PublicEvent" => EventDescription {
+            title: format!("{} made {} public", actor, repo),
+            summary: None,
+            url: repo_url,
+        },
+        "MemberEvent" => {
+            let member = str_at(&payload, &["member", "login"]).unwrap_or("someone");
+            EventDescription {
+                title: format!("{} {} {} as a collaborator on {}", actor, action, member, repo),
+                summary: None,
+                url: repo_url,
+            }
+        }
+        "GollumEvent" => {
+            let pages = payload.get("pages").and_then(|v| v.as_array());
+            let page_count = pages.map(|p| p.len()).unwrap_or(0);
+            EventDescription {
+                title: format!("{} updated {} wiki page{} in {}", actor, page_count, if page_count == 1 { "" } else { "s" }, repo),
+                summary: pages.map(|p| p.iter()
+                    .filter_map(|page| page.get("title").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ")),
+                url: pages.and_then(|p| p.first())
+                    .and_then(|page| page.get("html_url"))
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("{}/wiki", repo_url)),
+            }
+        }
+        "CommitCommentEvent" => {
+            let comment = &payload["comment"];
+            EventDescription {
+                title: format!("{} commented on a commit in {}", actor, repo),
+                summary: comment.get("body").and_then(|v| v.as_str()).map(truncate_body),
+                url: comment.get("html_url").and_then(|v| v.as_str()).map(|s| s.to_string()).unwrap_or(repo_url),
+            }
+        }
+        _ => EventDescription {
+            title: format!("{}: {} in {}", actor, event_type, repo),
+            summary: Some(format!("Event type: {}", event_type)),
+            url: repo_url,
+        },
+    }
+}
+
 impl GitHubIngester {
-    pub fn new(_secret_id: i64, token: String, repositories: Vec<String>, endpoints: Vec<String>) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
+    pub fn new(_secret_id: i64, token: String, repositories: Vec<String>, endpoints: Vec<String>, users: Vec<String>) -> Result<Self> {
+        let client = http::client(ClientOptions::with_timeout(30))?;
         
         Ok(GitHubIngester {
             token,
             repositories,
             endpoints,
+            users,
             client,
         })
     }
@@ -72,28 +286,29 @@ impl GitHubIngester {
             .header("Authorization", format!("Bearer {}", self.token))
             .header("Accept", "application/vnd.github.v3+json")
             .header("User-Agent", "UmbraRelay")
-            .send()
+            .send_counted()
             .context("Failed to send GitHub API request")?;
         
         // Check for 401 Unauthorized - token expired
         if response.status() == 401 {
-            return Err(anyhow::anyhow!("GitHub API returned 401 Unauthorized - token may be expired"));
+            return Err(http::status_error(&response).context("GitHub API returned 401 Unauthorized - token may be expired"));
         }
         
         // Check for 403 Forbidden - might be missing scope or insufficient permissions
         if response.status() == 403 {
+            let status_error = http::status_error(&response);
             let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow::anyhow!(
+            return Err(status_error.context(format!(
                 "GitHub API returned 403 Forbidden - token may be missing required scope or have insufficient permissions. Error: {}",
                 error_text
-            ));
+            )));
         }
         
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
+            return Err(http::status_error(&response).context(format!(
                 "GitHub API error: {}",
                 response.status()
-            ));
+            )));
         }
         
         Ok(response)
@@ -106,12 +321,12 @@ impl GitHubIngester {
             .header("Authorization", format!("Bearer {}", self.token))
             .header("Accept", "application/vnd.github.v3+json")
             .header("User-Agent", "UmbraRelay")
-            .send()
+            .send_counted()
             .context("Failed to send GitHub API request")?;
         
         // Check for 401 Unauthorized - token expired
         if response.status() == 401 {
-            return Err(anyhow::anyhow!("GitHub API returned 401 Unauthorized - token may be expired"));
+            return Err(http::status_error(&response).context("GitHub API returned 401 Unauthorized - token may be expired"));
         }
         
         // 404/410 means feature not available - return None
@@ -120,10 +335,10 @@ impl GitHubIngester {
         }
         
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
+            return Err(http::status_error(&response).context(format!(
                 "GitHub API error: {}",
                 response.status()
-            ));
+            )));
         }
         
         Ok(Some(response))
@@ -228,12 +443,15 @@ impl GitHubIngester {
             url: commit.html_url,
             item_type: "commit".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author,
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -259,12 +477,15 @@ impl GitHubIngester {
             url: pr.html_url,
             item_type: "pr".to_string(),
             occurred_at,
+            published_at: chrono::DateTime::parse_from_rfc3339(&pr.created_at).ok().map(|dt| dt.timestamp()),
             image_url: None,
             content_html: None,
             author: Some(pr.user.login),
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         })
     }
     
@@ -333,6 +554,7 @@ impl GitHubIngester {
             url,
             item_type: "event".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: event.get("actor")
@@ -341,7 +563,90 @@ impl GitHubIngester {
                 .map(|s| s.to_string()),
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
+        }))
+    }
+    
+    // Fetch public activity of followed users
+    fn fetch_user_events(&self) -> Result<Vec<IngestedItem>> {
+        let mut all_items = Vec::new();
+        
+        for user in &self.users {
+            let mut page = 1;
+            let per_page = 100;
+            
+            loop {
+                let url = format!(
+                    "https://api.github.com/users/{}/events?per_page={}&page={}",
+                    user, per_page, page
+                );
+                
+                let response = match self.make_request_optional(&url)? {
+                    Some(r) => r,
+                    None => {
+                        eprintln!("GitHub user {} not found, skipping", user);
+                        break;
+                    }
+                };
+                
+                let events: Vec<serde_json::Value> = response.json()
+                    .context("Failed to parse user events response")?;
+                
+                let events_len = events.len();
+                if events_len == 0 {
+                    break;
+                }
+                
+                for event in &events {
+                    if let Some(item) = self.user_event_to_item(event, user)? {
+                        all_items.push(item);
+                    }
+                }
+                
+                if events_len < per_page || page >= MAX_USER_EVENT_PAGES {
+                    break;
+                }
+                
+                page += 1;
+            }
+        }
+        
+        Ok(all_items)
+    }
+    
+    fn user_event_to_item(&self, event: &serde_json::Value, user: &str) -> Result<Option<IngestedItem>> {
+        let id = match event.get("id").and_then(|v| v.as_str()) {
+            Some(id) if !id.is_empty() => id,
+            _ => return Ok(None),
+        };
+        let external_id = format!("users/{}/event/{}", user, id);
+        let description = describe_event(event);
+        
+        let occurred_at = event.get("created_at")
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.timestamp());
+        
+        let repo = str_at(event, &["repo", "name"]).unwrap_or("unknown");
+        
+        Ok(Some(IngestedItem {
+            external_id,
+            title: description.title,
+            summary: description.summary,
+            url: description.url,
+            item_type: "user_event".to_string(),
+            occurred_at,
+            published_at: None,
+            image_url: None,
+            content_html: None,
+            author: str_at(event, &["actor", "login"]).map(|s| s.to_string()),
+            category: Some(vec![repo.to_string()]),
+            comments: None,
+            comment_count: None,
+            thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -407,6 +712,10 @@ impl GitHubIngester {
             .and_then(|v| v.as_str())
             .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
             .map(|dt| dt.timestamp());
+        let published_at = issue.get("created_at")
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.timestamp());
         
         Ok(Some(IngestedItem {
             external_id,
@@ -415,6 +724,7 @@ impl GitHubIngester {
             url,
             item_type: "issue".to_string(),
             occurred_at,
+            published_at,
             image_url: None,
             content_html: None,
             author: issue.get("user")
@@ -423,7 +733,9 @@ impl GitHubIngester {
                 .map(|s| s.to_string()),
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -491,6 +803,7 @@ impl GitHubIngester {
             url,
             item_type: "action".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: run.get("actor")
@@ -499,7 +812,9 @@ impl GitHubIngester {
                 .map(|s| s.to_string()),
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -568,6 +883,10 @@ impl GitHubIngester {
             .and_then(|v| v.as_str())
             .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
             .map(|dt| dt.timestamp());
+        let published_at = discussion.get("created_at")
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.timestamp());
         
         Ok(Some(IngestedItem {
             external_id,
@@ -576,6 +895,7 @@ impl GitHubIngester {
             url,
             item_type: "discussion".to_string(),
             occurred_at,
+            published_at,
             image_url: None,
             content_html: None,
             author: discussion.get("user")
@@ -584,7 +904,9 @@ impl GitHubIngester {
                 .map(|s| s.to_string()),
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -657,12 +979,15 @@ impl GitHubIngester {
             url,
             item_type: "code_scanning_alert".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: None,
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -720,12 +1045,15 @@ impl GitHubIngester {
             url,
             item_type: "check".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: None,
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -786,12 +1114,15 @@ impl GitHubIngester {
             url,
             item_type: "package".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: None,
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -861,12 +1192,15 @@ impl GitHubIngester {
             url,
             item_type: "project".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: None,
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
     
@@ -926,6 +1260,7 @@ impl GitHubIngester {
             url,
             item_type: "administration".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: event.get("actor")
@@ -934,7 +1269,9 @@ impl GitHubIngester {
                 .map(|s| s.to_string()),
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: None,
+            attachments: Vec::new(),
         }))
     }
 }
@@ -953,6 +1290,16 @@ impl IngestSource for GitHubIngester {
             }
         }
         
+        // Fetch public activity of followed users
+        if self.endpoints.contains(&"user_events".to_string()) {
+            match self.fetch_user_events() {
+                Ok(items) => all_items.extend(items),
+                Err(e) => {
+                    eprintln!("Failed to fetch user events: {}", e);
+                }
+            }
+        }
+        
         // Fetch repository-specific data for each repository
         for repo in &self.repositories {
             // Commits
@@ -1087,3 +1434,52 @@ impl IngestSource for GitHubIngester {
         Ok(all_items)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_describe_pull_request_event() {
+        let event = serde_json::json!({
+            "id": "1",
+            "type": "PullRequestEvent",
+            "actor": { "login": "alice", "display_login": "alice" },
+            "repo": { "name": "owner/repo" },
+            "payload": {
+                "action": "closed",
+                "number": 12,
+                "pull_request": {
+                    "number": 12,
+                    "title": "Fix parser",
+                    "merged": true,
+                    "html_url": "https://github.com/owner/repo/pull/12"
+                }
+            }
+        });
+        
+        let description = describe_event(&event);
+        assert_eq!(description.title, "alice merged PR #12 in owner/repo: Fix parser");
+        assert_eq!(description.url, "https://github.com/owner/repo/pull/12");
+    }
+    
+    #[test]
+    fn test_describe_push_and_unknown_events() {
+        let push = serde_json::json!({
+            "type": "PushEvent",
+            "actor": { "login": "bob" },
+            "repo": { "name": "owner/repo" },
+            "payload": { "ref": "refs/heads/main", "size": 2, "head": "abc123" }
+        });
+        let description = describe_event(&push);
+        assert_eq!(description.title, "bob pushed 2 commits to main in owner/repo");
+        assert_eq!(description.url, "https://github.com/owner/repo/commit/abc123");
+        
+        let unknown = serde_json::json!({
+            "type": "SponsorshipEvent",
+            "actor": { "login": "bob" },
+            "repo": { "name": "owner/repo" }
+        });
+        assert_eq!(describe_event(&unknown).title, "bob: SponsorshipEvent in owner/repo");
+    }
+}
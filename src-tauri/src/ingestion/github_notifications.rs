@@ -1,7 +1,7 @@
 use super::traits::{IngestSource, IngestedItem};
 use anyhow::{Result, Context};
+use crate::http::{self, ClientOptions, SendCounted};
 use reqwest::blocking::Client;
-use std::time::Duration;
 
 pub struct GitHubNotificationsIngester {
     token: String,
@@ -10,10 +10,7 @@ pub struct GitHubNotificationsIngester {
 
 impl GitHubNotificationsIngester {
     pub fn new(token: String) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
+        let client = http::client(ClientOptions::with_timeout(30))?;
         
         Ok(GitHubNotificationsIngester {
             token,
@@ -91,12 +88,15 @@ impl GitHubNotificationsIngester {
             url,
             item_type: "notification".to_string(),
             occurred_at,
+            published_at: None,
             image_url: None,
             content_html: None,
             author: None,
             category: Some(vec![repo.to_string()]),
             comments: None,
+            comment_count: None,
             thread_id: Some(thread_id),
+            attachments: Vec::new(),
         }))
     }
 }
@@ -121,7 +121,7 @@ impl IngestSource for GitHubNotificationsIngester {
                 .header("Authorization", format!("Bearer {}", self.token))
                 .header("Accept", "application/vnd.github.v3+json")
                 .header("User-Agent", "UmbraRelay")
-                .send()
+                .send_counted()
                 .context("Failed to send GitHub API request")?;
             
             let status = response.status();
@@ -132,31 +132,34 @@ impl IngestSource for GitHubNotificationsIngester {
             }
             
             if status == 401 {
+                let status_error = http::status_error(&response);
                 let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
-                return Err(anyhow::anyhow!(
+                return Err(status_error.context(format!(
                     "GitHub API returned 401 Unauthorized - token may be expired or invalid. Response: {}",
                     error_text
-                ));
+                )));
             }
             
             if status == 403 {
+                let status_error = http::status_error(&response);
                 let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
-                return Err(anyhow::anyhow!(
+                return Err(status_error.context(format!(
                     "GitHub API returned 403 Forbidden. \
                     Your Personal Access Token must have the 'notifications' scope. \
                     Please create a new PAT with notifications read permission. \
                     Response: {}",
                     error_text
-                ));
+                )));
             }
             
             if !status.is_success() {
+                let status_error = http::status_error(&response);
                 let error_text = response.text().unwrap_or_else(|_| format!("Status: {}", status));
-                return Err(anyhow::anyhow!(
+                return Err(status_error.context(format!(
                     "GitHub API error: {} - {}",
                     status,
                     error_text
-                ));
+                )));
             }
             
             let notifications: Vec<serde_json::Value> = response.json()
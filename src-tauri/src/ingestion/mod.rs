@@ -1,14 +1,16 @@
 pub mod traits;
 pub mod rss;
 pub mod atom;
+pub mod feed;
 pub mod github;
 pub mod github_notifications;
 pub mod utils;
 pub mod content_detection;
 pub mod extraction;
+pub mod discovery;
+pub mod favicon;
 
-pub use rss::*;
-pub use atom::*;
+pub use feed::*;
 pub use github::*;
 pub use github_notifications::*;
 
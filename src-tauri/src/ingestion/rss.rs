@@ -1,238 +1,568 @@
-use super::traits::{IngestSource, IngestedItem};
+use super::traits::{FeedMetadata, IngestedAttachment, IngestedItem, ParsedFeed};
 use super::utils;
 use anyhow::{Result, Context};
-use reqwest::blocking::Client;
+use quick_xml::encoding::Decoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::ResolveResult;
+use quick_xml::NsReader;
+use rss::extension::Extension;
 use rss::Channel;
-use std::time::Duration;
-use regex::Regex;
+use std::collections::BTreeMap;
 
-pub struct RssIngester {
-    url: String,
-    client: Client,
+const MEDIA_NAMESPACE: &str = "http://search.yahoo.com/mrss/";
+const CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+const SLASH_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/slash/";
+/// Namespaces RSS elements may be in: RSS 1.0 (RDF), and the ones some RSS 2.0 feeds declare.
+const RSS_NAMESPACES: &[&str] = &[
+    "http://purl.org/rss/1.0/",
+    "http://backend.userland.com/rss2",
+    "http://blogs.law.harvard.edu/tech/rss",
+];
+/// `enc:enclosure` (with `rdf:resource`), how RSS 1.0 feeds attach files.
+const ENCLOSURE_NAMESPACE: &str = "http://purl.oclc.org/net/rss_2.0/enc#";
+
+/// Extension prefixes used by this feed. The rss crate keys unknown extensions
+/// by the prefix written in the document, so resolve them from the namespace URIs.
+struct FeedPrefixes {
+    media: String,
+    content: String,
+    slash: String,
 }
 
-impl RssIngester {
-    pub fn new(url: String) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(60)) // Increased to 60 seconds for slow feeds
-            .build()
-            .context("Failed to create HTTP client")?;
-        
-        Ok(RssIngester { url, client })
+impl FeedPrefixes {
+    fn from_namespaces(namespaces: &BTreeMap<String, String>) -> Self {
+        let prefix_for = |uri: &str, default: &str| {
+            namespaces.iter()
+                .find(|(_, ns)| ns.trim_end_matches('/') == uri.trim_end_matches('/'))
+                .map(|(prefix, _)| prefix.clone())
+                .unwrap_or_else(|| default.to_string())
+        };
+        FeedPrefixes {
+            media: prefix_for(MEDIA_NAMESPACE, "media"),
+            content: prefix_for(CONTENT_NAMESPACE, "content"),
+            slash: prefix_for(SLASH_NAMESPACE, "slash"),
+        }
     }
 }
 
-/// Extracts image URL and HTML content from RSS item XML.
-/// Tries multiple methods: media:content, enclosure, content:encoded, and description fallback.
-fn extract_item_extras(item_xml: &str) -> (Option<String>, Option<String>) {
-    let mut image_url = None;
-    let mut content_html = None;
-    
-    // Try media:content first (Media RSS) - look for url attribute
-    let media_content_re = Regex::new(r#"<media:content[^>]*url=["']([^"']+)["']"#).ok();
-    if let Some(re) = media_content_re {
-        if let Some(captures) = re.captures(item_xml) {
-            if let Some(url) = captures.get(1) {
-                let url_str = url.as_str();
-                if url_str.starts_with("http://") || url_str.starts_with("https://") {
-                    image_url = Some(url_str.to_string());
-                }
-            }
+/// Parses an iTunes duration ("1:02:03", "62:03" or plain seconds) into seconds.
+fn parse_itunes_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let mut seconds = 0i64;
+    for part in value.split(':') {
+        let part: i64 = part.trim().split('.').next()?.parse().ok()?;
+        seconds = seconds * 60 + part;
+    }
+    Some(seconds)
+}
+
+/// Parses itunes:explicit, which feeds write as yes/no, true/false or explicit/clean.
+fn parse_itunes_explicit(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "yes" | "true" | "explicit" => Some(true),
+        "no" | "false" | "clean" => Some(false),
+        _ => None,
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn enclosure_from_element(element: &BytesStart, decoder: Decoder) -> Option<IngestedAttachment> {
+    let mut attachment = IngestedAttachment::default();
+    for attr in element.attributes().with_checks(false).flatten() {
+        let value = match attr.decode_and_unescape_value(decoder) {
+            Ok(value) => value.trim().to_string(),
+            Err(_) => continue,
+        };
+        match attr.key.local_name().as_ref() {
+            b"url" | b"resource" => attachment.url = value,
+            b"type" if !value.is_empty() => attachment.mime_type = Some(value),
+            b"length" => attachment.length = value.parse().ok().filter(|len| *len > 0),
+            _ => {}
         }
     }
-    
-    // Try enclosure with image type (standard RSS)
-    if image_url.is_none() {
-        let enclosure_re = Regex::new(r#"<enclosure[^>]*url=["']([^"']+)["'][^>]*type=["']([^"']+)["']"#).ok();
-        if let Some(re) = enclosure_re {
-            if let Some(captures) = re.captures(item_xml) {
-                if let Some(mime_type) = captures.get(2) {
-                    if mime_type.as_str().to_lowercase().starts_with("image/") {
-                        if let Some(url) = captures.get(1) {
-                            let url_str = url.as_str();
-                            if url_str.starts_with("http://") || url_str.starts_with("https://") {
-                                image_url = Some(url_str.to_string());
-                            }
-                        }
-                    }
+    if attachment.url.is_empty() { None } else { Some(attachment) }
+}
+
+/// Whether a resolved element name is the RSS element `name`, in no namespace (plain
+/// RSS 2.0) or in one of `namespaces`, whatever prefix the document uses for it.
+fn is_element(ns: &ResolveResult, local_name: &[u8], name: &[u8], namespaces: &[&str]) -> bool {
+    local_name == name && match ns {
+        ResolveResult::Unbound => true,
+        ResolveResult::Bound(ns) => namespaces.iter().any(|uri| ns.as_ref() == uri.as_bytes()),
+        ResolveResult::Unknown(_) => false,
+    }
+}
+
+/// Collects every `<enclosure>` of every `<item>`, in document order.
+/// The rss crate only keeps one enclosure per item, so this is a separate XML pass.
+fn collect_enclosures(content: &str) -> Vec<Vec<IngestedAttachment>> {
+    let mut reader = NsReader::from_str(content);
+    let mut items: Vec<Vec<IngestedAttachment>> = Vec::new();
+    let mut in_item = false;
+    let enclosure_namespaces: Vec<&str> = RSS_NAMESPACES.iter().copied().chain([ENCLOSURE_NAMESPACE]).collect();
+
+    loop {
+        match reader.read_resolved_event() {
+            Ok((ns, Event::Start(element))) if is_element(&ns, element.local_name().as_ref(), b"item", RSS_NAMESPACES) => {
+                items.push(Vec::new());
+                in_item = true;
+            }
+            Ok((ns, Event::End(element))) if is_element(&ns, element.local_name().as_ref(), b"item", RSS_NAMESPACES) => in_item = false,
+            Ok((ns, Event::Start(element))) | Ok((ns, Event::Empty(element)))
+                if in_item && is_element(&ns, element.local_name().as_ref(), b"enclosure", &enclosure_namespaces) =>
+            {
+                if let (Some(attachment), Some(current)) = (enclosure_from_element(&element, reader.decoder()), items.last_mut()) {
+                    current.push(attachment);
                 }
             }
+            Ok((_, Event::Eof)) => break,
+            // The rss crate already accepted the document, so stop quietly on anything odd
+            Err(_) => break,
+            _ => {}
         }
     }
-    
-    // Try content:encoded (Content module) - handle both CDATA and regular content, multiline
-    // First try CDATA format: <content:encoded><![CDATA[...]]></content:encoded>
-    if let Some(cdata_start) = item_xml.find("<content:encoded><![") {
-        if let Some(cdata_content_start) = item_xml[cdata_start..].find("CDATA[") {
-            let actual_content_start = cdata_start + cdata_content_start + 6; // 6 = len("CDATA[")
-            if let Some(cdata_end) = item_xml[actual_content_start..].find("]]></content:encoded>") {
-                let content = item_xml[actual_content_start..actual_content_start + cdata_end].trim();
-                if !content.is_empty() {
-                    content_html = Some(content.to_string());
-                }
-            }
+
+    items
+}
+
+/// Collects the item's enclosures and applies its iTunes episode metadata to them.
+fn extract_attachments(item: &rss::Item, enclosures: Option<&Vec<IngestedAttachment>>) -> Vec<IngestedAttachment> {
+    let mut attachments = enclosures.cloned().unwrap_or_default();
+    if attachments.is_empty() {
+        if let Some(enclosure) = item.enclosure() {
+            attachments.push(IngestedAttachment {
+                url: enclosure.url().to_string(),
+                mime_type: Some(enclosure.mime_type().to_string()).filter(|m| !m.is_empty()),
+                length: enclosure.length().parse().ok().filter(|len| *len > 0),
+                ..Default::default()
+            });
         }
     }
     
-    // If no CDATA, try regular format: <content:encoded>...</content:encoded>
-    if content_html.is_none() {
-        if let Some(start) = item_xml.find("<content:encoded>") {
-            let content_start = start + 17; // length of "<content:encoded>"
-            if let Some(end) = item_xml[content_start..].find("</content:encoded>") {
-                let content = item_xml[content_start..content_start + end].trim();
-                if !content.is_empty() {
-                    content_html = Some(content.to_string());
-                }
-            }
+    if let Some(itunes) = item.itunes_ext() {
+        for attachment in attachments.iter_mut() {
+            attachment.duration_seconds = itunes.duration().and_then(parse_itunes_duration);
+            attachment.episode = itunes.episode().and_then(|e| e.trim().parse().ok());
+            attachment.season = itunes.season().and_then(|s| s.trim().parse().ok());
+            attachment.explicit = itunes.explicit().and_then(parse_itunes_explicit);
+            attachment.image_url = itunes.image().map(|i| i.to_string());
         }
     }
     
-    // Fallback: check if description contains HTML
-    if content_html.is_none() {
-        let desc_re = Regex::new(r#"<description><!\[CDATA\[(.*?)\]\]></description>"#).ok()
-            .or_else(|| Regex::new(r#"<description>(.*?)</description>"#).ok());
-        if let Some(re) = desc_re {
-            if let Some(captures) = re.captures(item_xml) {
-                if let Some(desc) = captures.get(1) {
-                    let desc_str = desc.as_str().trim();
-                    // Check if it contains HTML tags
-                    if desc_str.contains('<') && desc_str.contains('>') && desc_str.len() > 50 {
-                        content_html = Some(desc_str.to_string());
-                    }
-                }
-            }
+    attachments
+}
+
+/// Returns the extension elements with the given local name under a prefix.
+fn extension_elements<'a>(item: &'a rss::Item, prefix: &str, name: &str) -> Vec<&'a Extension> {
+    item.extensions()
+        .get(prefix)
+        .and_then(|elements| elements.get(name))
+        .map(|elements| elements.iter().collect())
+        .unwrap_or_default()
+}
+
+fn child_elements<'a>(extension: &'a Extension, name: &str) -> impl Iterator<Item = &'a Extension> {
+    extension.children.get(name).into_iter().flatten()
+}
+
+/// Picks an image from Media RSS: image media:content first, then media:thumbnail,
+/// looking inside media:group and media:content as well as at the item level.
+fn extract_media_image(item: &rss::Item, prefixes: &FeedPrefixes) -> Option<String> {
+    let groups = extension_elements(item, &prefixes.media, "group");
+    let contents: Vec<&Extension> = extension_elements(item, &prefixes.media, "content")
+        .into_iter()
+        .chain(groups.iter().flat_map(|group| child_elements(group, "content")))
+        .collect();
+    let thumbnails: Vec<&Extension> = extension_elements(item, &prefixes.media, "thumbnail")
+        .into_iter()
+        .chain(groups.iter().flat_map(|group| child_elements(group, "thumbnail")))
+        .chain(contents.iter().flat_map(|content| child_elements(content, "thumbnail")))
+        .collect();
+
+    let image_content = contents.iter().find(|content| {
+        let medium = content.attrs.get("medium").map(|m| m.to_lowercase());
+        let mime_type = content.attrs.get("type").map(|t| t.to_lowercase());
+        match (medium.as_deref(), mime_type.as_deref()) {
+            (Some(medium), _) => medium == "image",
+            (None, Some(mime_type)) => mime_type.starts_with("image/"),
+            // Untyped media:content is almost always a picture
+            (None, None) => true,
         }
+    });
+
+    image_content.into_iter()
+        .chain(thumbnails.iter())
+        .filter_map(|element| element.attrs.get("url"))
+        .map(|url| url.trim())
+        .find(|url| is_http_url(url))
+        .map(|url| url.to_string())
+}
+
+/// Returns the full HTML body: content:encoded, or a description that carries HTML.
+/// Both come back from the rss crate with CDATA and entities already decoded.
+fn extract_content_html(item: &rss::Item, prefixes: &FeedPrefixes) -> Option<String> {
+    let encoded = item.content()
+        .map(|c| c.to_string())
+        .or_else(|| {
+            // The rss crate only recognises the literal "content:" prefix
+            extension_elements(item, &prefixes.content, "encoded")
+                .first()
+                .and_then(|e| e.value.clone())
+        })
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if encoded.is_some() {
+        return encoded;
     }
+
+    item.description()
+        .map(|d| d.trim())
+        .filter(|d| d.contains('<') && d.contains('>') && d.len() > 50)
+        .map(|d| d.to_string())
+}
+
+/// Reads slash:comments, the number of comments on the item.
+fn extract_comment_count(item: &rss::Item, prefixes: &FeedPrefixes) -> Option<i64> {
+    extension_elements(item, &prefixes.slash, "comments")
+        .first()
+        .and_then(|e| e.value.as_deref())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Returns trimmed text, or None when it is blank.
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(|v| v.to_string())
+}
+
+/// Channel title, description, site link, language and image.
+fn extract_metadata(channel: &Channel) -> FeedMetadata {
+    FeedMetadata {
+        title: non_empty(Some(channel.title())),
+        description: non_empty(Some(channel.description()))
+            .map(|d| utils::strip_html(&d))
+            .filter(|d| !d.is_empty()),
+        site_url: non_empty(Some(channel.link())).filter(|l| is_http_url(l)),
+        language: non_empty(channel.language()),
+        image_url: non_empty(channel.image().map(|i| i.url()))
+            .or_else(|| non_empty(channel.itunes_ext().and_then(|i| i.image())))
+            .filter(|u| is_http_url(u)),
+        icon_url: None,
+    }
+}
+
+/// Parses an RSS 2.0 or RDF (RSS 0.9/1.0) document into its channel metadata and items.
+/// Fetching and format detection happen in `FeedIngester`.
+pub fn parse_rss_feed(content: &str) -> Result<ParsedFeed> {
+    let channel = Channel::read_from(content.as_bytes())
+        .context("Failed to parse RSS feed")?;
+    let prefixes = FeedPrefixes::from_namespaces(channel.namespaces());
     
-    (image_url, content_html)
-}
-
-impl IngestSource for RssIngester {
-    fn poll(&self) -> Result<Vec<IngestedItem>> {
-        let response = self.client
-            .get(&self.url)
-            .send()
-            .context("Failed to fetch RSS feed")?;
-        
-        let content = response.text()
-            .context("Failed to read RSS feed content")?;
-        
-        let channel = Channel::read_from(content.as_bytes())
-            .context("Failed to parse RSS feed")?;
-        
-        // Parse items with enhanced extraction
-        // Extract all item blocks from XML first
-        let item_blocks: Vec<String> = {
-            let mut blocks = Vec::new();
-            let mut start = 0;
-            while let Some(item_start) = content[start..].find("<item") {
-                let actual_start = start + item_start;
-                // Find the end of this item
-                if let Some(item_end) = content[actual_start..].find("</item>") {
-                    let block = content[actual_start..actual_start + item_end + 7].to_string();
-                    blocks.push(block);
-                    start = actual_start + item_end + 7;
-                } else {
-                    break;
-                }
-            }
-            blocks
-        };
-        
-        // Match parsed items with their XML blocks
-        let items: Vec<IngestedItem> = channel.items()
-            .iter()
-            .enumerate()
-            .map(|(idx, item)| {
-                let external_id = item.guid()
-                    .map(|g| g.value().to_string())
-                    .unwrap_or_else(|| {
-                        // Fallback to link if no GUID
-                        item.link().unwrap_or("").to_string()
-                    });
-                
-                let title = item.title().unwrap_or("Untitled").to_string();
-                let summary = item.description()
-                    .and_then(|s| {
-                        let cleaned = utils::strip_html(s);
-                        if cleaned.is_empty() { None } else { Some(cleaned) }
-                    });
-                let url = item.link().unwrap_or("").to_string();
-                
-                // Parse pub_date if available
-                let occurred_at = item.pub_date()
-                    .and_then(|date_str| {
-                        chrono::DateTime::parse_from_rfc2822(date_str)
-                            .ok()
-                            .map(|dt| dt.timestamp())
-                    });
-                
-                // Get the corresponding XML block by matching link or title
-                // Use index as primary method since items should be in same order
-                let item_xml = if idx < item_blocks.len() {
-                    item_blocks[idx].clone()
-                } else if let Some(link) = item.link() {
-                    // Fallback: try to find by link
-                    item_blocks.iter()
-                        .find(|block| {
-                            // Check for link tag containing this URL (try various formats)
-                            block.contains(link) || 
-                            block.contains(&format!("<link>{}</link>", link)) ||
-                            block.contains(&format!(">{}</link>", link)) ||
-                            block.contains(&format!("<link>{}</link>", link.replace("https://", "").replace("http://", "")))
+    // Enclosures per item, in the same order as channel.items()
+    let enclosures = collect_enclosures(content);
+    let enclosures_match = enclosures.len() == channel.items().len();
+    
+    let items: Vec<IngestedItem> = channel.items()
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let external_id = item.guid()
+                .map(|g| g.value().to_string())
+                .unwrap_or_else(|| {
+                    // Fallback to link if no GUID
+                    item.link().unwrap_or("").to_string()
+                });
+            
+            let title = item.title().unwrap_or("Untitled").to_string();
+            let summary = item.description()
+                .and_then(|s| {
+                    let cleaned = utils::strip_html(s);
+                    if cleaned.is_empty() { None } else { Some(cleaned) }
+                });
+            let url = item.link().unwrap_or("").to_string();
+            
+            // Parse pub_date if available, falling back to dc:date (RDF feeds have no pubDate)
+            let occurred_at = item.pub_date()
+                .and_then(|date_str| {
+                    chrono::DateTime::parse_from_rfc2822(date_str.trim())
+                        .ok()
+                        .map(|dt| dt.timestamp())
+                })
+                .or_else(|| {
+                    item.dublin_core_ext()
+                        .and_then(|dc| dc.dates().first())
+                        .and_then(|date_str| chrono::DateTime::parse_from_rfc3339(date_str.trim()).ok())
+                        .map(|dt| dt.timestamp())
+                });
+            
+            // Enclosures and iTunes metadata (podcasts)
+            let item_enclosures = if enclosures_match { enclosures.get(idx) } else { None };
+            let attachments = extract_attachments(item, item_enclosures);
+            let item_type = if utils::has_media_attachment(&attachments) {
+                "podcast_episode"
+            } else {
+                "rss"
+            };
+            
+            // Image: Media RSS, then an image enclosure, then the episode artwork
+            let image_url = extract_media_image(item, &prefixes)
+                .or_else(|| {
+                    attachments.iter()
+                        .find(|a| {
+                            a.mime_type.as_deref().map(|m| m.to_lowercase().starts_with("image/")).unwrap_or(false)
+                                && is_http_url(&a.url)
                         })
-                        .cloned()
-                        .unwrap_or_default()
-                } else if let Some(title) = item.title() {
-                    // Fallback: try to match by title
-                    item_blocks.iter()
-                        .find(|block| block.contains(title))
-                        .cloned()
-                        .unwrap_or_default()
-                } else {
-                    String::new()
-                };
-                
-                // Extract image and content from this item's XML
-                let (image_url, content_html) = if !item_xml.is_empty() {
-                    extract_item_extras(&item_xml)
+                        .map(|a| a.url.clone())
+                })
+                .or_else(|| item.itunes_ext().and_then(|i| i.image()).map(|i| i.to_string()));
+            
+            let content_html = extract_content_html(item, &prefixes);
+            
+            // Extract RSS 2.0 optional fields, with Dublin Core fallbacks
+            let author = item.author()
+                .or_else(|| item.dublin_core_ext().and_then(|dc| dc.creators().first().map(|s| s.as_str())))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+            let category: Option<Vec<String>> = {
+                let categories: Vec<String> = item.categories()
+                    .iter()
+                    .map(|c| c.name().to_string())
+                    .chain(item.dublin_core_ext().into_iter().flat_map(|dc| dc.subjects().iter().cloned()))
+                    .collect();
+                if categories.is_empty() {
+                    None
                 } else {
-                    (None, None)
-                };
-                
-                // Extract RSS 2.0 optional fields
-                let author = item.author().map(|s| s.to_string());
-                let category: Option<Vec<String>> = {
-                    let categories: Vec<String> = item.categories()
-                        .iter()
-                        .map(|c| c.name().to_string())
-                        .collect();
-                    if categories.is_empty() {
-                        None
-                    } else {
-                        Some(categories)
-                    }
-                };
-                let comments = item.comments().map(|s| s.to_string());
-                
-                IngestedItem {
-                    external_id,
-                    title,
-                    summary,
-                    url,
-                    item_type: "rss".to_string(),
-                    occurred_at,
-                    image_url,
-                    content_html,
-                    author,
-                    category,
-                    comments,
-                    thread_id: None,
+                    Some(categories)
                 }
-            })
-            .collect();
-        
-        Ok(items)
+            };
+            let comments = item.comments().map(|s| s.to_string());
+            let comment_count = extract_comment_count(item, &prefixes);
+            
+            IngestedItem {
+                external_id,
+                title,
+                summary,
+                url,
+                item_type: item_type.to_string(),
+                occurred_at,
+                published_at: occurred_at, // pubDate is the publication date
+                image_url,
+                content_html,
+                author,
+                category,
+                comments,
+                comment_count,
+                thread_id: None,
+                attachments,
+            }
+        })
+        .collect();
+    
+    Ok(ParsedFeed {
+        metadata: extract_metadata(&channel),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_itunes_fields() {
+        assert_eq!(parse_itunes_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_itunes_duration("62:03"), Some(3723));
+        assert_eq!(parse_itunes_duration("3723"), Some(3723));
+        assert_eq!(parse_itunes_duration("soon"), None);
+        assert_eq!(parse_itunes_explicit("Yes"), Some(true));
+        assert_eq!(parse_itunes_explicit("clean"), Some(false));
+    }
+
+    #[test]
+    fn test_parse_podcast_episode() {
+        let feed = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Example Podcast</title>
+    <link>https://example.org/</link>
+    <description>Episodes</description>
+    <item>
+      <title>Episode 12</title>
+      <link>https://example.org/12</link>
+      <guid>ep-12</guid>
+      <enclosure url="https://cdn.example.org/12.mp3?a=1&amp;b=2" length="12345678" type="audio/mpeg"/>
+      <enclosure url="https://cdn.example.org/12.pdf" length="2048" type="application/pdf"/>
+      <itunes:duration>45:30</itunes:duration>
+      <itunes:episode>12</itunes:episode>
+      <itunes:season>2</itunes:season>
+      <itunes:explicit>no</itunes:explicit>
+      <itunes:image href="https://example.org/12.jpg"/>
+    </item>
+    <item>
+      <title>Blog post</title>
+      <link>https://example.org/post</link>
+    </item>
+  </channel>
+</rss>"#;
+
+        let items = parse_rss_feed(feed).unwrap().items;
+        assert_eq!(items.len(), 2);
+
+        let episode = &items[0];
+        assert_eq!(episode.item_type, "podcast_episode");
+        assert_eq!(episode.image_url.as_deref(), Some("https://example.org/12.jpg"));
+        assert_eq!(episode.attachments.len(), 2);
+        let audio = &episode.attachments[0];
+        assert_eq!(audio.url, "https://cdn.example.org/12.mp3?a=1&b=2");
+        assert_eq!(audio.mime_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(audio.length, Some(12345678));
+        assert_eq!(audio.duration_seconds, Some(2730));
+        assert_eq!(audio.episode, Some(12));
+        assert_eq!(audio.season, Some(2));
+        assert_eq!(audio.explicit, Some(false));
+        assert_eq!(episode.attachments[1].mime_type.as_deref(), Some("application/pdf"));
+
+        assert_eq!(items[1].item_type, "rss");
+        assert!(items[1].attachments.is_empty());
+    }
+
+    #[test]
+    fn test_namespaces_resolved_by_uri_not_prefix() {
+        let feed = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:mrss="http://search.yahoo.com/mrss/" xmlns:c="http://purl.org/rss/1.0/modules/content/" xmlns:sl="http://purl.org/rss/1.0/modules/slash/">
+  <channel>
+    <title>Unusual prefixes</title>
+    <link>https://example.org/</link>
+    <description>Same namespaces, different prefixes</description>
+    <item>
+      <title>Prefixes are just aliases</title>
+      <link>https://example.org/posts/aliases</link>
+      <mrss:thumbnail url="https://example.org/img/aliases.png"/>
+      <c:encoded>&lt;p&gt;Entity-encoded &lt;em&gt;body&lt;/em&gt; under a custom prefix.&lt;/p&gt;</c:encoded>
+      <sl:comments>3</sl:comments>
+    </item>
+  </channel>
+</rss>"#;
+        let item = &parse_rss_feed(feed).unwrap().items[0];
+        assert_eq!(item.image_url.as_deref(), Some("https://example.org/img/aliases.png"));
+        assert_eq!(
+            item.content_html.as_deref(),
+            Some("<p>Entity-encoded <em>body</em> under a custom prefix.</p>")
+        );
+        assert_eq!(item.comment_count, Some(3));
+    }
+
+    #[test]
+    fn test_enclosures_matched_by_namespace() {
+        // RSS 1.0 attaches files with enc:enclosure and rdf:resource
+        let rdf = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/" xmlns:enc="http://purl.oclc.org/net/rss_2.0/enc#">
+  <channel rdf:about="https://example.org/">
+    <title>Talks</title>
+    <link>https://example.org/</link>
+    <description>Recorded talks</description>
+  </channel>
+  <item rdf:about="https://example.org/talks/1">
+    <title>Talk 1</title>
+    <link>https://example.org/talks/1</link>
+    <enc:enclosure rdf:resource="https://example.org/talks/1.mp3" enc:type="audio/mpeg" enc:length="1234"/>
+  </item>
+</rdf:RDF>"#;
+        let items = parse_rss_feed(rdf).unwrap().items;
+        assert_eq!(items[0].item_type, "podcast_episode");
+        assert_eq!(items[0].attachments[0].url, "https://example.org/talks/1.mp3");
+        assert_eq!(items[0].attachments[0].length, Some(1234));
+
+        // RSS 2.0 declared as the default namespace; other namespaces' enclosures aren't ours
+        let namespaced = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns="http://backend.userland.com/rss2" xmlns:x="https://example.org/ns">
+  <channel>
+    <title>Talks</title>
+    <link>https://example.org/</link>
+    <description>Recorded talks</description>
+    <item>
+      <title>Talk 2</title>
+      <link>https://example.org/talks/2</link>
+      <enclosure url="https://example.org/talks/2.mp3" type="audio/mpeg" length="5678"/>
+      <x:enclosure url="https://example.org/talks/2.txt" type="text/plain"/>
+      <enclosure url="https://example.org/talks/2.pdf" type="application/pdf" length="90"/>
+    </item>
+  </channel>
+</rss>"#;
+        let items = parse_rss_feed(namespaced).unwrap().items;
+        assert_eq!(items[0].attachments.len(), 2);
+        assert_eq!(items[0].attachments[1].url, "https://example.org/talks/2.pdf");
     }
 }
 
+/// Trimmed copies of real feeds in `fixtures/rss`, one per quirk the parser has to handle.
+#[cfg(test)]
+mod fixture_tests {
+    use super::*;
+
+    #[test]
+    fn test_wordpress_content_encoded_and_categories() {
+        let items = parse_rss_feed(include_str!("fixtures/rss/wordpress.xml")).unwrap().items;
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert_eq!(item.external_id, "https://wordpress.org/news/?p=17145");
+        assert_eq!(item.author.as_deref(), Some("Jonathan Desrosiers"));
+        assert_eq!(item.occurred_at, Some(1715097697));
+        assert_eq!(item.category, Some(vec!["Releases".to_string(), "maintenance".to_string()]));
+        // No slash:comments once comments are closed
+        assert_eq!(item.comment_count, None);
+        let content = item.content_html.as_deref().unwrap();
+        assert!(content.starts_with("<p><strong>WordPress 6.5.3 is now available!</strong></p>"));
+        assert!(content.contains("wp-block-image"));
+        assert!(item.summary.as_deref().unwrap().starts_with("WordPress 6.5.3 is now available! This minor release"));
+    }
+
+    #[test]
+    fn test_flickr_media_content() {
+        let items = parse_rss_feed(include_str!("fixtures/rss/flickr_media.xml")).unwrap().items;
+        let item = &items[0];
+        // The full-size media:content wins over the thumbnail and the inline image
+        assert_eq!(
+            item.image_url.as_deref(),
+            Some("https://live.staticflickr.com/65535/53694511288_6f4e8c9a1b_b.jpg")
+        );
+        // Entity-encoded description HTML is kept as HTML content
+        assert!(item.content_html.as_deref().unwrap().contains("<img src=\"https://live.staticflickr.com/"));
+        assert!(item.summary.as_deref().unwrap().starts_with("NASA HQ PHOTO posted a photo:"));
+        assert_eq!(item.author.as_deref(), Some("nobody@flickr.com (\"NASA HQ PHOTO\")"));
+    }
+
+    #[test]
+    fn test_peertube_media_group_prefers_thumbnail_over_video() {
+        let items = parse_rss_feed(include_str!("fixtures/rss/media_group.xml")).unwrap().items;
+        let item = &items[0];
+        assert_eq!(
+            item.image_url.as_deref(),
+            Some("https://framatube.org/lazy-static/thumbnails/1d8e6a0b-b1f2-4c3e-9e5b-2f63c1a0e7d4.jpg")
+        );
+        // The videos are only in media:group, not enclosures
+        assert_eq!(item.item_type, "rss");
+        assert!(item.attachments.is_empty());
+        assert!(item.content_html.is_none());
+    }
+
+    #[test]
+    fn test_slashdot_rdf_dublin_core_and_slash() {
+        let items = parse_rss_feed(include_str!("fixtures/rss/slashdot.rdf")).unwrap().items;
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert_eq!(
+            item.external_id,
+            "https://developers.slashdot.org/story/24/05/04/0420249/rust-foundation-launches-new-security-initiative?utm_source=rss1.0mainlinkanon&utm_medium=feed"
+        );
+        assert_eq!(item.author.as_deref(), Some("msmash"));
+        assert_eq!(item.occurred_at, Some(1714824000));
+        assert_eq!(item.comment_count, Some(142));
+        assert_eq!(item.category, Some(vec!["programming".to_string()]));
+    }
+}
@@ -8,13 +8,50 @@ pub struct IngestedItem {
     pub summary: Option<String>,
     pub url: String,
     pub item_type: String,
-    pub occurred_at: Option<i64>,
+    pub occurred_at: Option<i64>, // When the source last changed it; stored as source_updated_at
+    #[serde(default)]
+    pub published_at: Option<i64>, // When the source first published it, if it says so
     pub image_url: Option<String>,
     pub content_html: Option<String>,
     pub author: Option<String>,
     pub category: Option<Vec<String>>,
     pub comments: Option<String>,
+    #[serde(default)]
+    pub comment_count: Option<i64>, // slash:comments
     pub thread_id: Option<String>, // For GitHub notification synchronization
+    #[serde(default)]
+    pub attachments: Vec<IngestedAttachment>, // Enclosures (podcast audio, video, files)
+}
+
+/// An enclosure attached to a feed item, with iTunes podcast metadata when present.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct IngestedAttachment {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub length: Option<i64>, // Size in bytes as advertised by the feed
+    pub duration_seconds: Option<i64>,
+    pub episode: Option<i64>,
+    pub season: Option<i64>,
+    pub explicit: Option<bool>,
+    pub image_url: Option<String>, // Episode artwork
+}
+
+/// Channel-level metadata of a feed, captured on every poll.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FeedMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub site_url: Option<String>, // The website the feed belongs to, not the feed itself
+    pub language: Option<String>,
+    pub image_url: Option<String>, // Channel image / ATOM logo
+    pub icon_url: Option<String>, // ATOM icon, preferred over the site's favicon
+}
+
+/// A parsed feed document: its metadata and its items.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFeed {
+    pub metadata: FeedMetadata,
+    pub items: Vec<IngestedItem>,
 }
 
 pub trait IngestSource: Send + Sync {
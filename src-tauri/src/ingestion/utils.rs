@@ -1,4 +1,5 @@
 use regex::Regex;
+use super::traits::IngestedAttachment;
 
 /// Strip HTML tags and decode entities from text.
 /// Removes HTML tags, decodes common entities, and filters out unwanted patterns like "Comments" links.
@@ -38,3 +39,14 @@ pub fn strip_html(html: &str) -> String {
     cleaned
 }
 
+/// Returns true if any attachment is audio or video, which makes the item a podcast episode.
+pub fn has_media_attachment(attachments: &[IngestedAttachment]) -> bool {
+    attachments.iter().any(|a| {
+        a.mime_type.as_deref()
+            .map(|m| {
+                let m = m.to_lowercase();
+                m.starts_with("audio/") || m.starts_with("video/")
+            })
+            .unwrap_or(false)
+    })
+}
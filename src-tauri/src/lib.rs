@@ -4,6 +4,9 @@ mod ingestion;
 mod normalization;
 mod commands;
 mod oauth;
+mod import;
+mod http;
+mod sync;
 
 use storage::Database;
 use config::{TokenStore, SecretStore};
@@ -83,6 +86,9 @@ pub fn run() {
                 }
             };
             
+            // Shared HTTP clients use the proxy, CA and timeout preferences
+            http::configure(http::HttpSettings::from_preferences(&db));
+            
             app.manage(Mutex::new(db));
             
             // Initialize legacy token store (for migration)
@@ -105,64 +111,65 @@ pub fn run() {
             let app_handle = app.handle().clone();
             migrate_tokens_to_secrets(&app_handle);
             
-            // Start background polling service using Tauri's async runtime
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                background_polling_service(app_handle).await;
-            });
-            
             // Start secret cleanup task
             let app_handle_cleanup = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 cleanup_expired_secrets_task(app_handle_cleanup).await;
             });
             
-            // Sync all enabled sources on app startup
+            // Start the sync scheduler; its first pass syncs all enabled sources
+            app.manage(sync::scheduler::Scheduler::new());
+            app.manage(sync::cancel::Cancellations::default());
             let app_handle_sync = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 // Wait a moment for the app to fully initialize
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
                 
+                compact_item_history_on_startup(&app_handle_sync);
+                
                 // Proactively refresh GitHub tokens before syncing
                 refresh_github_tokens_on_startup(&app_handle_sync).await;
                 
-                // Get all enabled sources and sync them
-                let sources = match get_sources_sync(&app_handle_sync) {
-                    Ok(sources) => sources.into_iter().filter(|s| s.enabled).collect::<Vec<_>>(),
-                    Err(e) => {
-                        eprintln!("Failed to get sources for initial sync: {}", e);
-                        return;
-                    }
-                };
-                
-                // Sync each enabled source
-                for source in sources {
-                    let result = sync_source_internal(&app_handle_sync, source).await;
-                    if let Err(e) = result {
-                        eprintln!("Failed to sync source during startup: {}", e);
-                    }
-                }
+                sync::scheduler::run(app_handle_sync).await;
             });
             
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_items,
+            commands::get_item_page,
             commands::get_item,
+            commands::search_items,
+            commands::validate_item_query,
+            commands::get_item_history,
+            commands::compact_item_history,
             commands::update_item_state,
             commands::bulk_update_item_state,
             commands::clear_source_items,
             commands::get_sources,
+            commands::get_source_favicons,
+            commands::get_source_errors,
             commands::get_source_secret_id,
             commands::add_source,
+            commands::discover_feeds,
+            commands::import_opml,
+            commands::import_reader_export,
+            commands::export_opml,
             commands::update_source,
             commands::remove_source,
             commands::sync_source,
+            commands::get_sync_runs,
+            commands::get_next_poll_times,
+            commands::get_sync_status,
+            commands::cancel_sync,
+            commands::cancel_extraction,
+            commands::prune_sync_runs,
             commands::sync_all_sources,
             commands::cleanup_old_items,
             commands::make_items_leaving_soon,
             commands::get_custom_views,
             commands::get_custom_view,
+            commands::get_view_items,
             commands::add_custom_view,
             commands::update_custom_view,
             commands::remove_custom_view,
@@ -207,108 +214,6 @@ fn get_sources_sync(app: &tauri::AppHandle) -> Result<Vec<storage::models::Sourc
     db.get_all_sources()
 }
 
-/// Parses duration strings (e.g., "5m", "10m", "1h") into seconds.
-fn parse_duration(duration_str: &str) -> u64 {
-    let duration_str = duration_str.trim();
-    
-    if duration_str.is_empty() {
-        return 600; // Default 10 minutes
-    }
-    
-    let (num_str, unit) = if duration_str.ends_with('m') {
-        (&duration_str[..duration_str.len() - 1], "m")
-    } else if duration_str.ends_with('h') {
-        (&duration_str[..duration_str.len() - 1], "h")
-    } else if duration_str.ends_with('s') {
-        (&duration_str[..duration_str.len() - 1], "s")
-    } else {
-        return 600; // Default on parse error
-    };
-    
-    let num: u64 = num_str.parse().unwrap_or(10);
-    
-    match unit {
-        "s" => num,
-        "m" => num * 60,
-        "h" => num * 3600,
-        _ => 600, // Default on invalid unit
-    }
-}
-
-/// Background service that periodically polls enabled sources based on their poll intervals.
-async fn background_polling_service(app: tauri::AppHandle) {
-    use std::time::Duration;
-    use tokio::time::sleep;
-    
-    loop {
-        // Wait a bit before starting
-        sleep(Duration::from_secs(10)).await;
-        
-        // Get all enabled sources (helper function ensures guard is dropped)
-        let sources = match get_sources_sync(&app) {
-            Ok(sources) => sources,
-            Err(e) => {
-                eprintln!("Failed to get sources: {}", e);
-                sleep(Duration::from_secs(60)).await;
-                continue;
-            }
-        };
-        
-        // Poll each enabled source
-        for source in sources {
-            if !source.enabled {
-                continue;
-            }
-            
-            // Determine poll interval
-            let poll_interval = match source.source_type.as_str() {
-                "rss" | "atom" => {
-                    let config_json: serde_json::Value = serde_json::from_str(&source.config_json)
-                        .unwrap_or_default();
-                    config_json.get("poll_interval")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| "10m".to_string())
-                }
-                "github" => "5m".to_string(), // Default from config
-                _ => "10m".to_string(),
-            };
-            
-            let interval_seconds = parse_duration(&poll_interval);
-            
-            // Check if it's time to sync
-            let should_sync = match source.last_synced_at {
-                None => true,
-                Some(last_sync) => {
-                    let elapsed = chrono::Utc::now().timestamp() - last_sync;
-                    elapsed >= interval_seconds as i64
-                }
-            };
-            
-            if should_sync {
-                // Sync this source directly
-                let db_state: tauri::State<'_, Mutex<Database>> = app.state();
-                let db_guard = match db_state.lock() {
-                    Ok(db) => db,
-                    Err(_) => continue,
-                };
-                
-                let source_clone = source.clone();
-                drop(db_guard);
-                
-                let result = sync_source_internal(&app, source_clone).await;
-                
-                if let Err(e) = result {
-                    eprintln!("Failed to sync source {}: {}", source.id, e);
-                }
-            }
-        }
-        
-        // Sleep for a minute before next check
-        sleep(Duration::from_secs(60)).await;
-    }
-}
-
 /// Background task that periodically cleans up expired secrets and disables associated sources.
 async fn cleanup_expired_secrets_task(app: tauri::AppHandle) {
     use std::time::Duration;
@@ -324,6 +229,26 @@ async fn cleanup_expired_secrets_task(app: tauri::AppHandle) {
     }
 }
 
+/// Drops the per-poll `update` events older versions wrote, once per launch.
+fn compact_item_history_on_startup(app: &tauri::AppHandle) {
+    use std::sync::Mutex;
+    use tauri::State;
+    
+    let db_state: State<'_, Mutex<Database>> = app.state();
+    let db_guard = match db_state.lock() {
+        Ok(db) => db,
+        Err(_) => {
+            eprintln!("Failed to lock database for history compaction");
+            return;
+        }
+    };
+    match db_guard.compact_item_history() {
+        Ok(0) => {}
+        Ok(removed) => eprintln!("Compacted item history: removed {} legacy events", removed),
+        Err(e) => eprintln!("Failed to compact item history: {}", e),
+    }
+}
+
 /// Internal function to clean up expired secrets and disable sources using them.
 fn cleanup_expired_secrets_internal(app: &tauri::AppHandle) {
     use std::sync::Mutex;
@@ -576,10 +501,199 @@ fn migrate_source_token_to_secret_blocking(
     Ok(secret_id)
 }
 
+/// Favicons are re-fetched once a week, sites rarely change them.
+const FAVICON_REFRESH_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Directory in the app data dir where source favicons are cached.
+pub fn favicon_dir(app: &tauri::AppHandle) -> anyhow::Result<std::path::PathBuf> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| anyhow::anyhow!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("favicons"))
+}
+
+/// Saves the feed's channel metadata on the source and refreshes its favicon in the
+/// background when none is cached yet or the cached one is stale.
+fn store_feed_metadata(
+    app: &tauri::AppHandle,
+    source: &storage::models::Source,
+    feed_url: &str,
+    metadata: crate::ingestion::traits::FeedMetadata,
+) -> anyhow::Result<()> {
+    {
+        let db_state: tauri::State<'_, Mutex<Database>> = app.state();
+        let db_guard = db_state.lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock database"))?;
+        db_guard.update_source_metadata(source.id, &metadata)?;
+    }
+    
+    let now = chrono::Utc::now().timestamp();
+    // Failed attempts are recorded too, so a site without an icon is only retried weekly
+    let fetched_recently = source.favicon_fetched_at
+        .map(|at| now - at < FAVICON_REFRESH_SECS)
+        .unwrap_or(false);
+    if fetched_recently {
+        return Ok(());
+    }
+    
+    let dir = favicon_dir(app)?;
+    let app_clone = app.clone();
+    let source_id = source.id;
+    let feed_url = feed_url.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            crate::ingestion::favicon::fetch_favicon(&metadata, &feed_url, &dir, source_id)
+        })
+        .await;
+        
+        let favicon_path = match result {
+            Ok(Ok(path)) => Some(path.to_string_lossy().to_string()),
+            Ok(Err(e)) => {
+                eprintln!("Failed to fetch favicon for source {}: {}", source_id, e);
+                None
+            }
+            Err(e) => {
+                eprintln!("Favicon task join error for source {}: {}", source_id, e);
+                None
+            }
+        };
+        
+        let db_state: tauri::State<'_, Mutex<Database>> = app_clone.state();
+        let db_guard = match db_state.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        if let Err(e) = db_guard.update_source_favicon(source_id, favicon_path.as_deref()) {
+            eprintln!("Failed to store favicon for source {}: {}", source_id, e);
+        }
+    });
+    
+    Ok(())
+}
+
+/// Syncs a source, or waits for its sync already in flight. Every sync goes through
+/// the scheduler, which then schedules the source's next background sync.
+pub async fn sync_source_internal(
+    app: &tauri::AppHandle,
+    source: storage::models::Source,
+    trigger: sync::SyncTrigger,
+) -> anyhow::Result<()> {
+    sync::events::emit(app, sync::events::SyncEvent::Queued { source_id: source.id, trigger: trigger.as_str() });
+    let scheduler = app.state::<sync::scheduler::Scheduler>();
+    let scheduled = source.clone();
+    scheduler.coalesce(&scheduled, tracked_sync(app, source, trigger)).await
+}
+
+/// Runs a sync, recording the attempt as a sync run and its outcome in the
+/// source's health (failure streak, backoff, error history). Emits lifecycle
+/// events and stops early when cancelled through `cancel_sync`.
+async fn tracked_sync(
+    app: &tauri::AppHandle,
+    source: storage::models::Source,
+    trigger: sync::SyncTrigger,
+) -> anyhow::Result<()> {
+    let source_id = source.id;
+    let started = std::time::Instant::now();
+    
+    let run_id = {
+        let db_state: tauri::State<'_, Mutex<Database>> = app.state();
+        let db_guard = db_state.lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock database"))?;
+        db_guard.start_sync_run(source_id, trigger.as_str())?
+    };
+    sync::events::emit(app, sync::events::SyncEvent::Started { source_id, run_id, trigger: trigger.as_str() });
+    
+    let requests = http::RequestContext::with_progress({
+        let app = app.clone();
+        move |pages| sync::events::emit(&app, sync::events::SyncEvent::Progress { source_id, run_id, pages })
+    });
+    let cancellations = app.state::<sync::cancel::Cancellations>();
+    let registration = cancellations.register(sync::cancel::Task::Sync(source_id), &requests);
+    
+    // Blocking ingesters stop at their next request; the sync returns right away
+    let result = tokio::select! {
+        result = run_source_sync(app, source, &requests) => result,
+        _ = requests.cancelled() => Err(anyhow::Error::new(http::Cancelled)),
+    };
+    drop(registration);
+    let cancelled = matches!(&result, Err(e) if e.is::<http::Cancelled>());
+    
+    // A network failure or timeout while the machine is offline defers the sync instead of failing it
+    let result = match result {
+        Err(e) if matches!(sync::health::classify_error(&e), "network" | "timeout") && is_offline(app).await => {
+            Err(anyhow::Error::new(sync::scheduler::SyncDeferred))
+        }
+        result => result,
+    };
+    let deferred = matches!(&result, Err(e) if e.is::<sync::scheduler::SyncDeferred>());
+    
+    let mut stats = storage::models::SyncRunStats {
+        duration_ms: started.elapsed().as_millis() as i64,
+        http_requests: requests.count() as i64,
+        ..Default::default()
+    };
+    if let Ok(normalized) = &result {
+        stats.items_new = normalized.new;
+        stats.items_updated = normalized.updated;
+        stats.items_unchanged = normalized.unchanged;
+    }
+    let result = result.map(|_| ());
+    
+    let event = match &result {
+        Ok(()) => sync::events::SyncEvent::Finished { source_id, run_id, duration_ms: stats.duration_ms },
+        Err(e) => sync::events::SyncEvent::Failed {
+            source_id,
+            run_id,
+            kind: if cancelled { "cancelled" } else if deferred { "deferred" } else { sync::health::classify_error(e) },
+            error: format!("{:#}", e),
+        },
+    };
+    sync::events::emit(app, event);
+    
+    let db_state: tauri::State<'_, Mutex<Database>> = app.state();
+    if let Ok(db_guard) = db_state.lock() {
+        let error = result.as_ref().err().map(|e| format!("{:#}", e));
+        if let Err(e) = db_guard.finish_sync_run(run_id, &stats, error.as_deref()) {
+            eprintln!("Failed to record sync run for source {}: {}", source_id, e);
+        }
+        // Neither is the source's fault
+        if deferred || cancelled {
+            return result;
+        }
+        if let Err(e) = sync::health::record_sync_outcome(&db_guard, source_id, &result, started.elapsed()) {
+            eprintln!("Failed to record sync health for source {}: {}", source_id, e);
+        }
+    }
+    
+    result
+}
+
+/// Runs the reachability check after a network error, marking the scheduler offline if it fails.
+async fn is_offline(app: &tauri::AppHandle) -> bool {
+    let target = {
+        let db_state: tauri::State<'_, Mutex<Database>> = app.state();
+        let target = match db_state.lock() {
+            Ok(db_guard) => sync::connectivity::reachability_target(&db_guard),
+            Err(_) => return false,
+        };
+        target
+    };
+    let reachable = tokio::task::spawn_blocking(move || sync::connectivity::is_reachable(&target))
+        .await
+        .unwrap_or(true);
+    if !reachable {
+        app.state::<sync::scheduler::Scheduler>().set_offline(true);
+    }
+    !reachable
+}
+
 /// Syncs a source by creating the appropriate ingester, fetching items, and storing them.
 /// Handles token refresh for GitHub sources on 401 errors.
-pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::models::Source) -> anyhow::Result<()> {
-    use crate::ingestion::{RssIngester, AtomIngester, GitHubIngester, GitHubNotificationsIngester, traits::IngestSource};
+async fn run_source_sync(
+    app: &tauri::AppHandle,
+    source: storage::models::Source,
+    requests: &http::RequestContext,
+) -> anyhow::Result<crate::normalization::NormalizeResult> {
+    use crate::ingestion::{FeedAuthConfig, FeedIngester, GitHubIngester, GitHubNotificationsIngester, traits::IngestSource};
     use crate::normalization::normalize_and_dedupe;
     use anyhow::Context;
     
@@ -588,31 +702,54 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
     
     // Create appropriate ingester and poll (using spawn_blocking for blocking operations)
     let items = match source.source_type.as_str() {
-        "rss" => {
+        // "rss" and "atom" are legacy types; the format is detected from the response
+        "feed" | "rss" | "atom" => {
             let url = config.get("url")
                 .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow::anyhow!("Missing RSS URL in config"))?
+                .ok_or_else(|| anyhow::anyhow!("Missing feed URL in config"))?
                 .to_string();
             
-            tokio::task::spawn_blocking(move || {
-                let ingester = RssIngester::new(url)?;
-                ingester.poll()
-            })
-            .await
-            .map_err(|e| anyhow::anyhow!("Task join error: {}", e))?
-        }
-        "atom" => {
-            let url = config.get("url")
+            // Private feeds: the scheme is in the config, the credential in the source's secret
+            let auth = match FeedAuthConfig::from_config(&config)? {
+                Some(auth_config) => {
+                    let secret_id = {
+                        let db_state: tauri::State<'_, Mutex<Database>> = app.state();
+                        let db_guard = db_state.lock()
+                            .map_err(|_| anyhow::anyhow!("Failed to lock database"))?;
+                        db_guard.get_source_secret_id(source.id)
+                            .context("Failed to get source secret_id")?
+                            .ok_or_else(|| anyhow::anyhow!("Feed uses {} authentication but has no secret configured", auth_config.scheme))?
+                    };
+                    let secret = {
+                        let secret_store: tauri::State<'_, Mutex<SecretStore>> = app.state();
+                        let store = secret_store.lock()
+                            .map_err(|_| anyhow::anyhow!("Failed to lock secret store"))?;
+                        store.get(secret_id)
+                            .map_err(|e| anyhow::anyhow!("Failed to get secret: {}", e))?
+                            .ok_or_else(|| anyhow::anyhow!("Secret not found in secure storage"))?
+                    };
+                    Some(auth_config.resolve(secret)?)
+                }
+                None => None,
+            };
+            
+            // Per-source user agent, for feeds that block unknown clients
+            let user_agent = config.get("user_agent")
                 .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow::anyhow!("Missing ATOM URL in config"))?
-                .to_string();
+                .map(|s| s.to_string());
             
-            tokio::task::spawn_blocking(move || {
-                let ingester = AtomIngester::new(url)?;
-                ingester.poll()
+            let feed_url = url.clone();
+            let requests = requests.clone();
+            let parsed = tokio::task::spawn_blocking(move || {
+                let _requests = requests.scope();
+                let ingester = FeedIngester::new(feed_url, user_agent)?.with_auth(auth);
+                ingester.fetch()
             })
             .await
-            .map_err(|e| anyhow::anyhow!("Task join error: {}", e))?
+            .map_err(|e| anyhow::anyhow!("Task join error: {}", e))??;
+            
+            store_feed_metadata(app, &source, &url, parsed.metadata)?;
+            Ok(parsed.items)
         }
         "github" => {
             // Get secret_id from source
@@ -663,13 +800,24 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
                     .ok_or_else(|| anyhow::anyhow!("Secret not found"))?
             };
             
-            // Parse repositories and endpoints from config
-            let repositories: Vec<String> = config.get("repositories")
+            // Parse followed users, repositories and endpoints from config
+            let users: Vec<String> = config.get("users")
                 .and_then(|v| v.as_array())
-                .ok_or_else(|| anyhow::anyhow!("Missing repositories in GitHub config"))?
-                .iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect();
+                .map(|arr| arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.trim().trim_start_matches('@').to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect())
+                .unwrap_or_default();
+            
+            // Repositories are optional for sources that only follow users
+            let repositories: Vec<String> = match config.get("repositories").and_then(|v| v.as_array()) {
+                Some(repos) => repos.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect(),
+                None if !users.is_empty() => Vec::new(),
+                None => return Err(anyhow::anyhow!("Missing repositories in GitHub config")),
+            };
             
             let endpoints: Vec<String> = config.get("endpoints")
                 .and_then(|v| v.as_array())
@@ -686,12 +834,16 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
                 let token_clone = token.clone();
                 let repositories_clone = repositories.clone();
                 let endpoints_clone = endpoints.clone();
+                let users_clone = users.clone();
+                let requests = requests.clone();
                 move || {
+                    let _requests = requests.scope();
                     let ingester = GitHubIngester::new(
                         secret_id_clone,
                         token_clone,
                         repositories_clone,
                         endpoints_clone,
+                        users_clone,
                     )?;
                     ingester.poll()
                 }
@@ -711,12 +863,16 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
                                 // Retry with new token
                                 let repositories_retry = repositories.clone();
                                 let endpoints_retry = endpoints.clone();
+                                let users_retry = users.clone();
+                                let requests = requests.clone();
                                 tokio::task::spawn_blocking(move || {
+                                    let _requests = requests.scope();
                                     let ingester = GitHubIngester::new(
                                         secret_id_clone,
                                         new_token,
                                         repositories_retry,
                                         endpoints_retry,
+                                        users_retry,
                                     )?;
                                     ingester.poll()
                                 })
@@ -777,7 +933,9 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
             // First attempt with current token
             let result = tokio::task::spawn_blocking({
                 let token_clone = token.clone();
+                let requests = requests.clone();
                 move || {
+                    let _requests = requests.scope();
                     let ingester = GitHubNotificationsIngester::new(token_clone)?;
                     ingester.poll()
                 }
@@ -795,7 +953,9 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
                         match crate::commands::refresh_github_token_internal(&app_clone, secret_id_clone).await {
                             Ok(new_token) => {
                                 // Retry with new token
+                                let requests = requests.clone();
                                 tokio::task::spawn_blocking(move || {
+                                    let _requests = requests.scope();
                                     let ingester = GitHubNotificationsIngester::new(new_token)?;
                                     ingester.poll()
                                 })
@@ -836,21 +996,38 @@ pub async fn sync_source_internal(app: &tauri::AppHandle, source: storage::model
     let db_guard = db_state.lock()
         .map_err(|_| anyhow::anyhow!("Failed to lock database"))?;
     
-    let item_ids = normalize_and_dedupe(&db_guard, source.id, items)?;
+    let published = sync::adaptive::publication_times(&items);
+    let result = normalize_and_dedupe(&db_guard, source.id, items)?;
+    
+    // Adaptive polling learns the source's cadence from its item timestamps
+    if let Err(e) = sync::adaptive::update_learned_interval(&db_guard, &source, &published) {
+        eprintln!("Failed to update poll interval for source {}: {}", source.id, e);
+    }
     
     // Update sync time
     db_guard.update_source_sync_time(source.id)?;
+    // Also picks up items that aged out of age-based views since the last sync
+    if let Err(e) = db_guard.refresh_view_unread_counts() {
+        eprintln!("Failed to refresh custom view unread counts: {}", e);
+    }
     drop(db_guard);
+    sync::events::emit(app, sync::events::SyncEvent::ItemsStored {
+        source_id: source.id,
+        new: result.new,
+        updated: result.updated,
+        unchanged: result.unchanged,
+    });
     
     // Spawn background extraction task for items that need it
     let app_clone = app.clone();
+    let item_ids = result.item_ids.clone();
     tauri::async_runtime::spawn(async move {
         if let Err(e) = process_background_extraction(&app_clone, item_ids).await {
             eprintln!("Background extraction error: {}", e);
         }
     });
     
-    Ok(())
+    Ok(result)
 }
 
 /// Proactively refreshes GitHub tokens on startup to prevent 401 errors
@@ -914,13 +1091,35 @@ async fn refresh_github_tokens_on_startup(app: &tauri::AppHandle) {
 }
 
 /// Background task that extracts full content for items marked as partial
+/// Extracts an item's full text in a blocking task that `cancel_extraction` can stop.
+pub async fn extract_cancellable(
+    app: &tauri::AppHandle,
+    item_id: i64,
+    url: String,
+) -> anyhow::Result<crate::ingestion::extraction::ExtractionResult> {
+    let context = http::RequestContext::new();
+    let cancellations = app.state::<sync::cancel::Cancellations>();
+    let _registration = cancellations.register(sync::cancel::Task::Extraction(item_id), &context);
+    
+    let task = tokio::task::spawn_blocking({
+        let context = context.clone();
+        move || {
+            let _context = context.scope();
+            crate::ingestion::extraction::extract_full_text(&url)
+        }
+    });
+    tokio::select! {
+        result = task => result.map_err(|e| anyhow::anyhow!("Task join error: {}", e))?,
+        _ = context.cancelled() => Err(anyhow::Error::new(http::Cancelled)),
+    }
+}
+
 async fn process_background_extraction(
     app: &tauri::AppHandle,
     item_ids: Vec<i64>,
 ) -> anyhow::Result<()> {
     use std::sync::Mutex;
     use tauri::State;
-    use crate::ingestion::extraction::extract_full_text;
     
     // Check user preference for extraction
     let extraction_enabled = {
@@ -1014,12 +1213,7 @@ async fn process_background_extraction(
             // Extract content in blocking task
             // Note: url comes from item.url which is the RSS <link> or Atom <link rel="alternate"> tag
             // This is the canonical article URL, not parsed from CDATA or content_html
-            let extraction_result = tokio::task::spawn_blocking({
-                let url_clone = url.clone();
-                move || extract_full_text(&url_clone)
-            })
-            .await
-            .map_err(|e| anyhow::anyhow!("Task join error: {}", e))?;
+            let extraction_result = extract_cancellable(app, *item_id, url.clone()).await;
             
             // Update database with result
             let db_guard = db_state.lock()
@@ -1035,6 +1229,19 @@ async fn process_background_extraction(
                         Some(&completeness),
                         None,
                     )?;
+                    if let Some(canonical_url) = &result.canonical_url {
+                        db_guard.set_item_canonical_url(*item_id, canonical_url)?;
+                    }
+                }
+                Err(e) if e.is::<http::Cancelled>() => {
+                    // Cancelled - leave it to be extracted later
+                    db_guard.update_item_content_status(
+                        *item_id,
+                        "feed_only",
+                        None,
+                        Some(&completeness),
+                        None,
+                    )?;
                 }
                 Err(e) => {
                     // Failure - update with error reason
@@ -0,0 +1,58 @@
+use reqwest::Url;
+
+/// Query parameters that only track where a click came from.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid",
+    "_hsenc", "_hsmi", "mkt_tok", "ref", "ref_src", "ref_url",
+];
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+/// Normalizes an article URL so copies of it from different sources compare equal:
+/// https scheme, lowercase host without `www.`, no default port, fragment, tracking
+/// parameters or trailing slash, and the remaining query parameters sorted.
+/// Returns None for anything that isn't an http(s) URL.
+pub fn canonical_url(url: &str) -> Option<String> {
+    let parsed = Url::parse(url.trim()).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = parsed.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let port = match parsed.port() {
+        Some(80) | Some(443) | None => String::new(),
+        Some(port) => format!(":{}", port),
+    };
+    let path = parsed.path().trim_end_matches('/');
+
+    // Parameters are kept as written (encoding included), only filtered and sorted
+    let mut query: Vec<&str> = parsed.query().unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !is_tracking_param(pair.split('=').next().unwrap_or_default()))
+        .collect();
+    query.sort_unstable();
+    let query = if query.is_empty() { String::new() } else { format!("?{}", query.join("&")) };
+
+    Some(format!("https://{}{}{}{}", host, port, path, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_url() {
+        let canonical = Some("https://example.org/posts/release?id=7&page=2".to_string());
+        assert_eq!(canonical_url("http://WWW.Example.org:80/posts/release/?page=2&utm_source=rss&id=7#comments"), canonical);
+        assert_eq!(canonical_url("https://example.org/posts/release?id=7&fbclid=abc&page=2"), canonical);
+        assert_eq!(canonical_url("https://example.org/"), Some("https://example.org".to_string()));
+        assert_eq!(canonical_url("https://example.org:8443/a"), Some("https://example.org:8443/a".to_string()));
+        // Paths stay case-sensitive
+        assert_ne!(canonical_url("https://example.org/A"), canonical_url("https://example.org/a"));
+        assert_eq!(canonical_url("mailto:someone@example.org"), None);
+        assert_eq!(canonical_url(""), None);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::ingestion::utils::strip_html;
+
+/// Fingerprints this many bits apart or fewer (about 90% similar) are the same story.
+pub const MAX_DISTANCE: u32 = 6;
+/// Only items published this close together (either way) are compared.
+pub const CLUSTER_WINDOW_SECS: i64 = 3 * 24 * 60 * 60;
+/// Words of body text used; copies of a story differ most in what follows the lead.
+const LEAD_WORDS: usize = 200;
+/// Below this many words there isn't enough text to tell stories apart.
+const MIN_WORDS: usize = 8;
+
+/// 64-bit FNV-1a, so stored fingerprints don't depend on the std hasher.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// SimHash of an item's title and the lead of its summary (or content, without one),
+/// over words and word pairs. Similar texts get fingerprints that differ in few bits. Returns None
+/// when there are fewer than `MIN_WORDS` words. Stored as i64 since that's what SQLite holds.
+pub fn fingerprint(title: &str, summary: Option<&str>, content_html: Option<&str>) -> Option<i64> {
+    let body = summary.filter(|s| !s.trim().is_empty()).or(content_html).map(strip_html).unwrap_or_default();
+    let words: Vec<String> = title.split(|c: char| !c.is_alphanumeric())
+        .chain(body.split(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .take(LEAD_WORDS)
+        .collect();
+    if words.len() < MIN_WORDS {
+        return None;
+    }
+
+    // Words and word pairs: pairs keep some word order, words keep short texts stable
+    let pairs = words.windows(2).map(|pair| format!("{} {}", pair[0], pair[1]));
+    let mut weights = [0i32; 64];
+    for feature in words.iter().cloned().chain(pairs) {
+        let hash = fnv1a(&feature);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    let hash = weights.iter().enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0u64, |hash, (bit, _)| hash | 1 << bit);
+    Some(hash as i64)
+}
+
+/// Number of bits two fingerprints differ in.
+pub fn distance(a: i64, b: i64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_distance() {
+        let summary = "The project shipped version 2.0 today with a new plugin system, faster startup, \
+            a redesigned settings page and dozens of bug fixes contributed by the community over the last year.";
+        let original = fingerprint("Project 2.0 released", Some(summary), None).unwrap();
+        let reworded = fingerprint(
+            "Project 2.0 released",
+            Some(&format!("<p>{} Read the full changelog.</p>", summary)),
+            None,
+        ).unwrap();
+        let unrelated = fingerprint(
+            "Quarterly earnings beat expectations",
+            Some("Shares rose after the company reported revenue growth across all regions and raised its outlook for the year."),
+            None,
+        ).unwrap();
+
+        assert!(distance(original, reworded) <= MAX_DISTANCE);
+        assert!(distance(original, unrelated) > MAX_DISTANCE);
+        // Content stands in for a missing summary; too little text gives no fingerprint
+        assert_eq!(fingerprint("Project 2.0 released", None, Some(summary)), Some(original));
+        assert_eq!(fingerprint("Bump serde", None, None), None);
+    }
+}
@@ -1,55 +1,54 @@
-use crate::storage::Database;
+pub mod canonical;
+pub mod fingerprint;
+
+use crate::storage::{Database, ItemUpsert, UpsertOutcome};
 use crate::ingestion::traits::IngestedItem;
 use crate::ingestion::content_detection::detect_content_completeness;
 use anyhow::Result;
+use fingerprint::fingerprint;
+
+/// What a poll's items did to the database.
+#[derive(Debug, Clone, Default)]
+pub struct NormalizeResult {
+    pub item_ids: Vec<i64>, // Every stored item, in feed order
+    pub new: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+}
 
+/// Stores a poll's items in one transaction; items that didn't change aren't touched.
+/// New items are then clustered with similar stories from other sources.
 pub fn normalize_and_dedupe(
     db: &Database,
     source_id: i64,
     items: Vec<IngestedItem>,
-) -> Result<Vec<i64>> {
-    let mut item_ids = Vec::new();
+) -> Result<NormalizeResult> {
+    let upserts: Vec<ItemUpsert> = items.iter()
+        .map(|item| ItemUpsert {
+            item,
+            // Convert category Vec<String> to JSON string
+            category: item.category.as_ref().map(|cats| serde_json::to_string(cats).unwrap_or_default()),
+            content_completeness: Some(detect_content_completeness(item).completeness.as_str()),
+            fingerprint: fingerprint(&item.title, item.summary.as_deref(), item.content_html.as_deref()),
+        })
+        .collect();
     
-    for item in items {
-        // Detect content completeness
-        let detection_result = detect_content_completeness(&item);
-        let completeness_str = detection_result.completeness.as_str();
-        
-        // Convert category Vec<String> to JSON string
-        let category_json = item.category.as_ref().map(|cats| {
-            serde_json::to_string(cats).unwrap_or_default()
-        });
-        
-        let item_id = db.upsert_item(
-            source_id,
-            &item.external_id,
-            &item.title,
-            item.summary.as_deref(),
-            &item.url,
-            &item.item_type,
-            item.image_url.as_deref(),
-            item.content_html.as_deref(),
-            item.author.as_deref(),
-            category_json.as_deref(),
-            item.comments.as_deref(),
-            item.thread_id.as_deref(),
-            Some(completeness_str),
-        )?;
-        
-        item_ids.push(item_id);
-        
-        // Optionally create an event record
-        if let Some(occurred_at) = item.occurred_at {
-            let _ = db.create_event(
-                item_id,
-                "update",
-                Some(&serde_json::json!({
-                    "occurred_at": occurred_at
-                }).to_string()),
-            );
+    let mut result = NormalizeResult::default();
+    let mut inserted = Vec::new();
+    for (item_id, outcome) in db.upsert_items(source_id, &upserts)? {
+        result.item_ids.push(item_id);
+        match outcome {
+            UpsertOutcome::Inserted => {
+                result.new += 1;
+                inserted.push(item_id);
+            }
+            UpsertOutcome::Updated => result.updated += 1,
+            UpsertOutcome::Unchanged => result.unchanged += 1,
         }
     }
     
-    Ok(item_ids)
+    // New items join the story cluster of a near-identical item from another source
+    db.cluster_items(&inserted)?;
+    
+    Ok(result)
 }
-
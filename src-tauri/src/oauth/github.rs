@@ -1,8 +1,8 @@
 use anyhow::{Result, Context};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::http::{self, ClientOptions};
 use reqwest::blocking::Client;
-use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeviceCodeResponse {
@@ -65,9 +65,7 @@ pub struct GitHubOAuth {
 
 impl GitHubOAuth {
     pub fn new(client_id: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
+        let client = http::client(ClientOptions::with_timeout(30))
             .expect("Failed to create HTTP client");
         
         Self {
@@ -1,8 +1,41 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OptionalExtension, Result, params};
+use rusqlite::types::Value;
 use rusqlite_migration::{Migrations, M};
 use std::sync::{Arc, Mutex};
 use chrono::Utc;
-use super::models::{Source, Item, Secret};
+use super::models::{Source, SourceError, SyncRun, SyncRunStats, Item, ClusterMember, ItemCursor, ItemDuplicate, ItemFilter, ItemPage, ItemSummary, SearchResult, Secret, Attachment, CustomView, ItemEvent};
+use super::query::ItemQuery;
+use super::rules::{ViewRule, ViewSort};
+use super::search;
+use crate::ingestion::traits::{FeedMetadata, IngestedAttachment, IngestedItem};
+use crate::import::ImportedArticle;
+use crate::normalization::canonical::canonical_url;
+use crate::normalization::fingerprint::{fingerprint, distance, CLUSTER_WINDOW_SECS, MAX_DISTANCE};
+
+/// Columns read by `Source::from_row`, in order.
+const SOURCE_COLUMNS: &str = "id, type, name, config_json, enabled, last_synced_at, created_at, updated_at, \
+    feed_title, feed_description, site_url, feed_language, feed_image_url, favicon_path, favicon_fetched_at, \
+    last_error, last_error_kind, last_error_at, consecutive_failures, last_success_at, avg_sync_duration_ms, \
+    backoff_until, disabled_reason, adaptive_interval_secs";
+
+/// Sync errors kept per source in `source_errors`.
+const SOURCE_ERROR_HISTORY: i64 = 20;
+
+/// What `upsert_items` did with an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
+/// A polled item with the values derived from it during normalization.
+pub struct ItemUpsert<'a> {
+    pub item: &'a IngestedItem,
+    pub category: Option<String>, // JSON array string
+    pub content_completeness: Option<&'a str>, // None keeps the stored value on updates
+    pub fingerprint: Option<i64>, // SimHash of title and text, for clustering similar stories
+}
 
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
@@ -11,6 +44,8 @@ pub struct Database {
 impl Database {
     pub fn new(db_path: &str) -> Result<Self> {
         let mut conn = Connection::open(db_path)?;
+        // Used when migrating to the search index, so it must exist before migrations run
+        search::register_functions(&conn)?;
         
         // Run migrations - consolidated single migration for v1.0
         // This represents the final schema state for new installations
@@ -134,6 +169,243 @@ impl Database {
                 CREATE INDEX IF NOT EXISTS idx_items_content_status ON items(content_status);
                 "#
             ),
+            M::up(
+                r#"
+                -- RSS and ATOM sources become the unified feed type (format is detected per poll)
+                UPDATE sources SET type = 'feed' WHERE type IN ('rss', 'atom');
+                "#
+            ),
+            M::up(
+                r#"
+                -- Enclosures (podcast audio, video, files) attached to items
+                CREATE TABLE IF NOT EXISTS attachments (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+                    url TEXT NOT NULL,
+                    mime_type TEXT,
+                    length INTEGER,
+                    duration_seconds INTEGER,
+                    episode INTEGER,
+                    season INTEGER,
+                    explicit INTEGER,
+                    image_url TEXT,
+                    created_at INTEGER NOT NULL,
+                    UNIQUE(item_id, url)
+                );
+                
+                CREATE INDEX IF NOT EXISTS idx_attachments_item_id ON attachments(item_id);
+                "#
+            ),
+            M::up(
+                r#"
+                -- Comment count reported by feeds (slash:comments)
+                ALTER TABLE items ADD COLUMN comment_count INTEGER;
+                "#
+            ),
+            M::up(
+                r#"
+                -- Feed-level metadata captured on each poll, and the locally cached favicon
+                ALTER TABLE sources ADD COLUMN feed_title TEXT;
+                ALTER TABLE sources ADD COLUMN feed_description TEXT;
+                ALTER TABLE sources ADD COLUMN site_url TEXT;
+                ALTER TABLE sources ADD COLUMN feed_language TEXT;
+                ALTER TABLE sources ADD COLUMN feed_image_url TEXT;
+                ALTER TABLE sources ADD COLUMN favicon_path TEXT;
+                ALTER TABLE sources ADD COLUMN favicon_fetched_at INTEGER;
+                "#
+            ),
+            M::up(
+                r#"
+                -- Source health: outcome of recent syncs, backoff and auto-disable
+                ALTER TABLE sources ADD COLUMN last_error TEXT;
+                ALTER TABLE sources ADD COLUMN last_error_kind TEXT;
+                ALTER TABLE sources ADD COLUMN last_error_at INTEGER;
+                ALTER TABLE sources ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE sources ADD COLUMN last_success_at INTEGER;
+                ALTER TABLE sources ADD COLUMN avg_sync_duration_ms INTEGER;
+                ALTER TABLE sources ADD COLUMN backoff_until INTEGER;
+                ALTER TABLE sources ADD COLUMN disabled_reason TEXT;
+                
+                -- Recent sync errors per source, trimmed on insert
+                CREATE TABLE IF NOT EXISTS source_errors (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    source_id INTEGER NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+                    kind TEXT NOT NULL,
+                    message TEXT NOT NULL,
+                    occurred_at INTEGER NOT NULL
+                );
+                
+                CREATE INDEX IF NOT EXISTS idx_source_errors_source_id ON source_errors(source_id, occurred_at);
+                "#
+            ),
+            M::up(
+                r#"
+                -- One row per sync attempt, with what it fetched and stored
+                CREATE TABLE IF NOT EXISTS sync_runs (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    source_id INTEGER NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+                    trigger TEXT NOT NULL,
+                    started_at INTEGER NOT NULL,
+                    finished_at INTEGER,
+                    duration_ms INTEGER,
+                    http_requests INTEGER NOT NULL DEFAULT 0,
+                    items_new INTEGER NOT NULL DEFAULT 0,
+                    items_updated INTEGER NOT NULL DEFAULT 0,
+                    items_unchanged INTEGER NOT NULL DEFAULT 0,
+                    error TEXT
+                );
+                
+                CREATE INDEX IF NOT EXISTS idx_sync_runs_source_id ON sync_runs(source_id, started_at);
+                CREATE INDEX IF NOT EXISTS idx_sync_runs_started_at ON sync_runs(started_at);
+                "#
+            ),
+            M::up(
+                r#"
+                -- Poll interval learned from the source's publication cadence (adaptive polling)
+                ALTER TABLE sources ADD COLUMN adaptive_interval_secs INTEGER;
+                "#
+            ),
+            M::up(
+                r#"
+                -- Full-text index over items; HTML is indexed as plain text
+                CREATE VIRTUAL TABLE IF NOT EXISTS items_fts USING fts5(
+                    title, summary, author, category, content, extracted_content,
+                    tokenize = 'porter unicode61 remove_diacritics 2'
+                );
+                
+                CREATE TRIGGER IF NOT EXISTS items_fts_insert AFTER INSERT ON items BEGIN
+                    INSERT INTO items_fts(rowid, title, summary, author, category, content, extracted_content)
+                    VALUES (new.id, new.title, strip_html(new.summary), new.author, new.category,
+                            strip_html(new.content_html), strip_html(new.extracted_content_html));
+                END;
+                
+                CREATE TRIGGER IF NOT EXISTS items_fts_delete AFTER DELETE ON items BEGIN
+                    DELETE FROM items_fts WHERE rowid = old.id;
+                END;
+                
+                CREATE TRIGGER IF NOT EXISTS items_fts_update
+                AFTER UPDATE OF title, summary, author, category, content_html, extracted_content_html ON items BEGIN
+                    DELETE FROM items_fts WHERE rowid = old.id;
+                    INSERT INTO items_fts(rowid, title, summary, author, category, content, extracted_content)
+                    VALUES (new.id, new.title, strip_html(new.summary), new.author, new.category,
+                            strip_html(new.content_html), strip_html(new.extracted_content_html));
+                END;
+                
+                -- Backfill existing items
+                INSERT INTO items_fts(rowid, title, summary, author, category, content, extracted_content)
+                SELECT id, title, strip_html(summary), author, category, strip_html(content_html), strip_html(extracted_content_html)
+                FROM items;
+                "#
+            ),
+            M::up(
+                r#"
+                -- Saved searches: custom views can filter with an inbox query
+                ALTER TABLE custom_views ADD COLUMN query TEXT;
+                "#
+            ),
+            M::up(
+                r#"
+                -- Rule-based custom views; source_ids / group_names are converted to rules
+                -- on startup and kept only so an older build can still read the view
+                ALTER TABLE custom_views ADD COLUMN rules TEXT;
+                ALTER TABLE custom_views ADD COLUMN sort_order TEXT NOT NULL DEFAULT 'newest';
+                ALTER TABLE custom_views ADD COLUMN unread_count INTEGER NOT NULL DEFAULT 0;
+                "#
+            ),
+            M::up(
+                r#"
+                -- Keyset pagination walks these in order instead of sorting the whole inbox
+                CREATE INDEX IF NOT EXISTS idx_items_created_at_id ON items(created_at, id);
+                CREATE INDEX IF NOT EXISTS idx_items_state_created_at_id ON items(state, created_at, id);
+                "#
+            ),
+            M::up(
+                r#"
+                -- Item history: an event per real change, with the old and new values.
+                -- Long text is cut to 280 characters and content bodies only report their length.
+                CREATE TRIGGER IF NOT EXISTS items_history_content
+                AFTER UPDATE OF title, summary, content_html ON items BEGIN
+                    INSERT INTO events (item_id, event_type, payload_json, occurred_at)
+                    SELECT new.id, 'title_changed', json_object('old', old.title, 'new', new.title), new.updated_at
+                    WHERE old.title IS NOT new.title;
+                    
+                    -- GitHub status items (workflow runs, checks, alerts, notifications) carry their status in the summary
+                    INSERT INTO events (item_id, event_type, payload_json, occurred_at)
+                    SELECT new.id,
+                           CASE WHEN new.item_type IN ('action', 'check', 'code_scanning_alert', 'notification') THEN 'status_changed' ELSE 'summary_changed' END,
+                           json_object('old', substr(old.summary, 1, 280), 'new', substr(new.summary, 1, 280)),
+                           new.updated_at
+                    WHERE old.summary IS NOT new.summary;
+                    
+                    INSERT INTO events (item_id, event_type, payload_json, occurred_at)
+                    SELECT new.id, 'content_changed', json_object('old_length', length(old.content_html), 'new_length', length(new.content_html)), new.updated_at
+                    WHERE old.content_html IS NOT new.content_html;
+                END;
+                
+                CREATE TRIGGER IF NOT EXISTS items_history_state
+                AFTER UPDATE OF state ON items WHEN old.state IS NOT new.state BEGIN
+                    INSERT INTO events (item_id, event_type, payload_json, occurred_at)
+                    VALUES (new.id, 'state_changed', json_object('old', old.state, 'new', new.state), new.updated_at);
+                END;
+                
+                CREATE INDEX IF NOT EXISTS idx_events_item_id_occurred_at ON events(item_id, occurred_at);
+                "#
+            ),
+            M::up(
+                r#"
+                -- The source's own dates; created_at stays the time we first stored the item
+                ALTER TABLE items ADD COLUMN published_at INTEGER;
+                ALTER TABLE items ADD COLUMN source_updated_at INTEGER;
+                
+                -- Until now the dates only lived in the payload of per-poll 'update' events
+                UPDATE items SET
+                    published_at = (SELECT MIN(json_extract(e.payload_json, '$.occurred_at')) FROM events e WHERE e.item_id = items.id AND e.event_type = 'update'),
+                    source_updated_at = (SELECT MAX(json_extract(e.payload_json, '$.occurred_at')) FROM events e WHERE e.item_id = items.id AND e.event_type = 'update');
+                
+                CREATE INDEX IF NOT EXISTS idx_items_published ON items(COALESCE(published_at, created_at), id);
+                "#
+            ),
+            M::up(
+                r#"
+                -- Normalized article URL, shared by copies of an article across sources.
+                -- Filled in from url by Database::backfill_canonical_urls after migrating.
+                ALTER TABLE items ADD COLUMN canonical_url TEXT;
+                
+                CREATE INDEX IF NOT EXISTS idx_items_canonical_url ON items(canonical_url) WHERE canonical_url IS NOT NULL;
+                "#
+            ),
+            M::up(
+                r#"
+                -- SimHash of title and text, and the id of the first item of the story cluster
+                -- an item belongs to (NULL when it has no similar items)
+                ALTER TABLE items ADD COLUMN fingerprint INTEGER;
+                ALTER TABLE items ADD COLUMN cluster_id INTEGER;
+                
+                CREATE INDEX IF NOT EXISTS idx_items_cluster_id ON items(cluster_id) WHERE cluster_id IS NOT NULL;
+                "#
+            ),
+            M::up(
+                r#"
+                -- The first stored item with the same canonical URL; NULL for that item itself, so
+                -- lists show one entry per article by filtering on duplicate_of IS NULL.
+                -- Kept up to date by Database::link_duplicates whenever items are written.
+                ALTER TABLE items ADD COLUMN duplicate_of INTEGER;
+                
+                UPDATE items SET duplicate_of = (SELECT MIN(d.id) FROM items d WHERE d.canonical_url = items.canonical_url AND d.id < items.id)
+                WHERE canonical_url IS NOT NULL;
+                
+                CREATE INDEX IF NOT EXISTS idx_items_duplicate_of ON items(duplicate_of) WHERE duplicate_of IS NOT NULL;
+                "#
+            ),
+            M::up(
+                r#"
+                -- The search index is written by Database::index_items, so that inserting or
+                -- updating items doesn't need strip_html() (only registered by the app) and
+                -- other tools can still write to the database. Removal stays a trigger.
+                DROP TRIGGER IF EXISTS items_fts_insert;
+                DROP TRIGGER IF EXISTS items_fts_update;
+                "#
+            ),
         ]);
 
         // Try to run migrations, but handle the case where database is already at a higher version
@@ -150,12 +422,16 @@ impl Database {
                         "CREATE INDEX IF NOT EXISTS idx_items_content_status ON items(content_status)",
                         [],
                     );
-                    // Update migration version to 2 since columns already exist
-                    let _ = conn.execute(
-                        "UPDATE schema_migrations SET version = 2 WHERE version < 2",
-                        [],
-                    );
-                    // Continue - migration is effectively done, columns will be ensured below
+                    // Mark migration 2 as applied since the columns already exist,
+                    // then run the migrations that come after it
+                    let _ = conn.pragma_update(None, "user_version", 2);
+                    if let Err(e) = migrations.to_latest(&mut conn) {
+                        return Err(rusqlite::Error::SqliteFailure(
+                            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_MISUSE),
+                            Some(format!("Migration error: {}", e))
+                        ));
+                    }
+                    // Continue - columns will be ensured below
                 } else if error_str.contains("DatabaseTooFarAhead") {
                     // If error is "DatabaseTooFarAhead", the database is already migrated
                     // Check if schema is correct and update version if needed
@@ -167,9 +443,9 @@ impl Database {
                     ).unwrap_or(false);
                     
                     if schema_ok {
-                        // Schema is correct, just update the version to match our migrations (we have 2 migrations now)
+                        // Schema is correct, just update the version to match our migrations (we have 19 migrations now)
                         let _ = conn.execute(
-                            "UPDATE schema_migrations SET version = 2",
+                            "UPDATE schema_migrations SET version = 19",
                             [],
                         );
                     } else {
@@ -195,11 +471,109 @@ impl Database {
         // Ensure content extraction columns exist (for databases created before migration 2)
         Self::ensure_content_extraction_columns(&conn);
 
+        // Turn custom views' source/group lists into rules
+        if let Err(e) = Self::migrate_legacy_view_rules(&conn) {
+            eprintln!("Failed to migrate custom views to rules: {}", e);
+        }
+
+        // Canonical URLs for items stored before they were tracked
+        if let Err(e) = Self::backfill_canonical_urls(&conn) {
+            eprintln!("Failed to backfill canonical URLs: {}", e);
+        }
+
+        // Fingerprints for recent items stored before they were computed
+        if let Err(e) = Self::backfill_fingerprints(&conn) {
+            eprintln!("Failed to backfill item fingerprints: {}", e);
+        }
+
         Ok(Database {
             conn: Arc::new(Mutex::new(conn)),
         })
     }
 
+    /// Gives custom views created before rules existed the equivalent rule. Runs on every
+    /// startup but only touches views that have lists and no rules yet.
+    fn migrate_legacy_view_rules(conn: &Connection) -> Result<()> {
+        let mut stmt = conn.prepare(
+            "SELECT id, source_ids, group_names FROM custom_views WHERE rules IS NULL AND (source_ids IS NOT NULL OR group_names IS NOT NULL)"
+        )?;
+        let legacy = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?))
+        })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (id, source_ids, group_names) in legacy {
+            if let Some(rule) = ViewRule::from_legacy(source_ids.as_deref(), group_names.as_deref()) {
+                let rules_json = serde_json::to_string(&rule)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                conn.execute("UPDATE custom_views SET rules = ?1 WHERE id = ?2", params![rules_json, id])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets `canonical_url` on items that don't have one yet. Runs on every startup; items
+    /// whose URL can't be canonicalized are looked at again each time, which is cheap.
+    fn backfill_canonical_urls(conn: &Connection) -> Result<()> {
+        let mut stmt = conn.prepare("SELECT id, url FROM items WHERE canonical_url IS NULL AND url != ''")?;
+        let pending = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut update = conn.prepare("UPDATE items SET canonical_url = ?1 WHERE id = ?2")?;
+        let mut canonical_urls = Vec::new();
+        for (id, url) in pending {
+            if let Some(canonical) = canonical_url(&url) {
+                update.execute(params![canonical, id])?;
+                canonical_urls.push(canonical);
+            }
+        }
+        if !canonical_urls.is_empty() {
+            Self::link_duplicates(conn, &canonical_urls)?;
+        }
+        Ok(())
+    }
+
+    /// Points `duplicate_of` at the first stored item (of a source that still exists) with the
+    /// same canonical URL, or clears it on that item itself. Covers every item with one of the
+    /// given canonical URLs, plus items whose first copy was deleted, lost its source or moved
+    /// to another URL. Runs after every write that can change which item comes first.
+    fn link_duplicates(conn: &Connection, canonical_urls: &[String]) -> Result<()> {
+        const FIRST_COPY: &str = "(SELECT MIN(d.id) FROM items d INNER JOIN sources ds ON d.source_id = ds.id WHERE d.canonical_url = items.canonical_url AND d.id < items.id)";
+        if !canonical_urls.is_empty() {
+            let urls = serde_json::to_string(canonical_urls)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            conn.prepare_cached(&format!(
+                "UPDATE items SET duplicate_of = {} WHERE canonical_url IN (SELECT value FROM json_each(?1))",
+                FIRST_COPY
+            ))?.execute(params![urls])?;
+        }
+        conn.prepare_cached(&format!(
+            "UPDATE items SET duplicate_of = {} WHERE duplicate_of IS NOT NULL AND NOT EXISTS \
+             (SELECT 1 FROM items r INNER JOIN sources rs ON r.source_id = rs.id WHERE r.id = items.duplicate_of AND r.canonical_url = items.canonical_url)",
+            FIRST_COPY
+        ))?.execute([])?;
+        Ok(())
+    }
+
+    /// Computes fingerprints for items without one that are recent enough for new items to
+    /// be clustered with. Older items are never compared, so they're left alone.
+    fn backfill_fingerprints(conn: &Connection) -> Result<()> {
+        let since = Utc::now().timestamp() - CLUSTER_WINDOW_SECS;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, summary, content_html FROM items WHERE fingerprint IS NULL AND COALESCE(published_at, created_at) >= ?1"
+        )?;
+        let pending = stmt.query_map(params![since], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, Option<String>>(3)?))
+        })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut update = conn.prepare("UPDATE items SET fingerprint = ?1 WHERE id = ?2")?;
+        for (id, title, summary, content_html) in pending {
+            if let Some(fingerprint) = fingerprint(&title, summary.as_deref(), content_html.as_deref()) {
+                update.execute(params![fingerprint, id])?;
+            }
+        }
+        Ok(())
+    }
+
     /// Ensures content extraction columns exist in items table
     /// This handles cases where the database was created before migration 2
     fn ensure_content_extraction_columns(conn: &Connection) {
@@ -263,7 +637,7 @@ impl Database {
     pub fn get_all_sources(&self) -> Result<Vec<Source>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            r#"SELECT id, type, name, config_json, enabled, last_synced_at, created_at, updated_at FROM sources ORDER BY created_at DESC"#
+            &format!("SELECT {} FROM sources ORDER BY created_at DESC", SOURCE_COLUMNS)
         )?;
         let sources = stmt.query_map([], |row| Source::from_row(row))?
             .collect::<Result<Vec<_>, _>>()?;
@@ -273,7 +647,7 @@ impl Database {
     pub fn get_source(&self, id: i64) -> Result<Source> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            r#"SELECT id, type, name, config_json, enabled, last_synced_at, created_at, updated_at FROM sources WHERE id = ?1"#
+            &format!("SELECT {} FROM sources WHERE id = ?1", SOURCE_COLUMNS)
         )?;
         stmt.query_row(params![id], |row| Source::from_row(row))
     }
@@ -310,6 +684,13 @@ impl Database {
                 "UPDATE sources SET enabled = ?1, updated_at = ?2 WHERE id = ?3",
                 params![if enabled { 1 } else { 0 }, now, id],
             )?;
+            // Re-enabling gives the source a fresh start
+            if enabled {
+                conn.execute(
+                    "UPDATE sources SET disabled_reason = NULL, backoff_until = NULL, consecutive_failures = 0 WHERE id = ?1",
+                    params![id],
+                )?;
+            }
         }
         
         if let Some(secret_id) = secret_id {
@@ -327,194 +708,959 @@ impl Database {
         Ok(())
     }
 
-    pub fn delete_source(&self, id: i64) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM sources WHERE id = ?1", params![id])?;
-        Ok(())
+    pub fn delete_source(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM sources WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM source_errors WHERE source_id = ?1", params![id])?;
+        conn.execute("DELETE FROM sync_runs WHERE source_id = ?1", params![id])?;
+        // Copies of this source's articles elsewhere take over as first copy
+        Self::link_duplicates(&conn, &[])?;
+        Ok(())
+    }
+
+    pub fn update_source_sync_time(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            "UPDATE sources SET last_synced_at = ?1 WHERE id = ?2",
+            params![now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Records a successful sync: resets the failure streak and backoff, and folds the
+    /// duration into the average (exponential moving average, weight 1/5).
+    pub fn record_sync_success(&self, id: i64, duration_ms: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            r#"UPDATE sources SET
+                consecutive_failures = 0,
+                last_success_at = ?1,
+                backoff_until = NULL,
+                avg_sync_duration_ms = CASE WHEN avg_sync_duration_ms IS NULL THEN ?2 ELSE (avg_sync_duration_ms * 4 + ?2) / 5 END
+            WHERE id = ?3"#,
+            params![now, duration_ms, id],
+        )?;
+        Ok(())
+    }
+
+    /// Records a failed sync in the source's health and error history.
+    /// Returns the number of consecutive failures including this one.
+    pub fn record_sync_failure(&self, id: i64, kind: &str, message: &str, duration_ms: i64) -> Result<i64> {
+        let mut conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        let tx = conn.transaction()?;
+        tx.execute(
+            r#"UPDATE sources SET
+                consecutive_failures = consecutive_failures + 1,
+                last_error = ?1,
+                last_error_kind = ?2,
+                last_error_at = ?3,
+                avg_sync_duration_ms = CASE WHEN avg_sync_duration_ms IS NULL THEN ?4 ELSE (avg_sync_duration_ms * 4 + ?4) / 5 END
+            WHERE id = ?5"#,
+            params![message, kind, now, duration_ms, id],
+        )?;
+        tx.execute(
+            "INSERT INTO source_errors (source_id, kind, message, occurred_at) VALUES (?1, ?2, ?3, ?4)",
+            params![id, kind, message, now],
+        )?;
+        tx.execute(
+            r#"DELETE FROM source_errors WHERE source_id = ?1 AND id NOT IN (
+                SELECT id FROM source_errors WHERE source_id = ?1 ORDER BY id DESC LIMIT ?2
+            )"#,
+            params![id, SOURCE_ERROR_HISTORY],
+        )?;
+        let failures = tx.query_row(
+            "SELECT consecutive_failures FROM sources WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        tx.commit()?;
+        Ok(failures)
+    }
+
+    /// Sets (or clears) the time before which background polling skips the source.
+    pub fn set_source_backoff(&self, id: i64, backoff_until: Option<i64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE sources SET backoff_until = ?1 WHERE id = ?2",
+            params![backoff_until, id],
+        )?;
+        Ok(())
+    }
+
+    /// Stores the poll interval learned by adaptive polling (`None` to forget it).
+    pub fn set_source_adaptive_interval(&self, id: i64, interval_secs: Option<i64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE sources SET adaptive_interval_secs = ?1 WHERE id = ?2",
+            params![interval_secs, id],
+        )?;
+        Ok(())
+    }
+
+    /// Publication times of the source's most recent dated items, newest first.
+    pub fn get_recent_published_timestamps(&self, source_id: i64, limit: i64) -> Result<Vec<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT published_at FROM items WHERE source_id = ?1 AND published_at IS NOT NULL ORDER BY published_at DESC LIMIT ?2"
+        )?;
+        let timestamps = stmt.query_map(params![source_id, limit], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(timestamps)
+    }
+
+    /// Disables a source that keeps failing, keeping the reason for the user.
+    pub fn auto_disable_source(&self, id: i64, reason: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            "UPDATE sources SET enabled = 0, disabled_reason = ?1, updated_at = ?2 WHERE id = ?3",
+            params![reason, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Recent sync errors of a source, newest first.
+    pub fn get_source_errors(&self, source_id: i64) -> Result<Vec<SourceError>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, source_id, kind, message, occurred_at FROM source_errors WHERE source_id = ?1 ORDER BY id DESC"
+        )?;
+        let errors = stmt.query_map(params![source_id], SourceError::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(errors)
+    }
+
+    /// Opens a sync run for a source; `finish_sync_run` fills in the outcome.
+    pub fn start_sync_run(&self, source_id: i64, trigger: &str) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO sync_runs (source_id, trigger, started_at) VALUES (?1, ?2, ?3)",
+            params![source_id, trigger, now],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Closes a sync run with its statistics and error, if it failed.
+    pub fn finish_sync_run(&self, run_id: i64, stats: &SyncRunStats, error: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            r#"UPDATE sync_runs SET finished_at = ?1, duration_ms = ?2, http_requests = ?3,
+                items_new = ?4, items_updated = ?5, items_unchanged = ?6, error = ?7
+            WHERE id = ?8"#,
+            params![
+                now,
+                stats.duration_ms,
+                stats.http_requests,
+                stats.items_new,
+                stats.items_updated,
+                stats.items_unchanged,
+                error,
+                run_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent sync runs, newest first, optionally for a single source.
+    pub fn get_sync_runs(&self, source_id: Option<i64>, limit: i64) -> Result<Vec<SyncRun>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"SELECT r.id, r.source_id, s.name, r.trigger, r.started_at, r.finished_at, r.duration_ms,
+                r.http_requests, r.items_new, r.items_updated, r.items_unchanged, r.error
+            FROM sync_runs r LEFT JOIN sources s ON r.source_id = s.id
+            WHERE ?1 IS NULL OR r.source_id = ?1
+            ORDER BY r.started_at DESC, r.id DESC
+            LIMIT ?2"#
+        )?;
+        let runs = stmt.query_map(params![source_id, limit], SyncRun::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(runs)
+    }
+
+    /// Deletes sync runs that started before `before` (unix seconds). Returns how many were removed.
+    pub fn prune_sync_runs(&self, before: i64) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM sync_runs WHERE started_at < ?1", params![before])
+    }
+
+    /// Stores the channel metadata read from the source's feed on the latest poll.
+    pub fn update_source_metadata(&self, id: i64, metadata: &FeedMetadata) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE sources SET feed_title = ?1, feed_description = ?2, site_url = ?3, feed_language = ?4, feed_image_url = ?5 WHERE id = ?6",
+            params![
+                metadata.title,
+                metadata.description,
+                metadata.site_url,
+                metadata.language,
+                metadata.image_url,
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Records a favicon fetch attempt. A failed fetch (`None`) keeps the previously cached icon.
+    pub fn update_source_favicon(&self, id: i64, favicon_path: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            "UPDATE sources SET favicon_path = COALESCE(?1, favicon_path), favicon_fetched_at = ?2 WHERE id = ?3",
+            params![favicon_path, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Upserts a single item without attachments; see `upsert_items`.
+    #[cfg(test)]
+    pub fn upsert_item(
+        &self,
+        source_id: i64,
+        external_id: &str,
+        title: &str,
+        summary: Option<&str>,
+        url: &str,
+        item_type: &str,
+        image_url: Option<&str>,
+        content_html: Option<&str>,
+        author: Option<&str>,
+        category: Option<&str>,
+        comments: Option<&str>,
+        comment_count: Option<i64>,
+        thread_id: Option<&str>,
+        content_completeness: Option<&str>,
+    ) -> Result<(i64, UpsertOutcome)> {
+        let item = IngestedItem {
+            external_id: external_id.to_string(),
+            title: title.to_string(),
+            summary: summary.map(String::from),
+            url: url.to_string(),
+            item_type: item_type.to_string(),
+            occurred_at: None,
+            published_at: None,
+            image_url: image_url.map(String::from),
+            content_html: content_html.map(String::from),
+            author: author.map(String::from),
+            category: None,
+            comments: comments.map(String::from),
+            comment_count,
+            thread_id: thread_id.map(String::from),
+            attachments: Vec::new(),
+        };
+        let upsert = ItemUpsert { item: &item, category: category.map(String::from), content_completeness, fingerprint: None };
+        let mut outcomes = self.upsert_items_with(source_id, &[upsert], false)?;
+        Ok(outcomes.remove(0))
+    }
+
+    /// Stores a poll's items in one transaction (inserts new ones, updates existing ones
+    /// by source_id + external_id) and keeps their attachments in sync. Preserves item state
+    /// and created_at on updates; an item whose fields are all unchanged is left alone
+    /// (including updated_at) and reported as `Unchanged`. Outcomes are in input order.
+    /// Changes to title, summary and content are recorded in the item's history by triggers.
+    pub fn upsert_items(&self, source_id: i64, items: &[ItemUpsert]) -> Result<Vec<(i64, UpsertOutcome)>> {
+        self.upsert_items_with(source_id, items, true)
+    }
+
+    fn upsert_items_with(&self, source_id: i64, items: &[ItemUpsert], sync_attachments: bool) -> Result<Vec<(i64, UpsertOutcome)>> {
+        let mut conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        let tx = conn.transaction()?;
+        let mut outcomes = Vec::with_capacity(items.len());
+        {
+            // Items imported from other readers are keyed by their link until the feed reports its own id
+            let mut adopt = tx.prepare(
+                "UPDATE items SET external_id = ?2, updated_at = ?4 WHERE source_id = ?1 AND external_id = ?3 AND url = ?3 AND ?2 != ?3 \
+                 AND NOT EXISTS (SELECT 1 FROM items WHERE source_id = ?1 AND external_id = ?2)"
+            )?;
+            let mut adopted = std::collections::HashSet::new();
+            for upsert in items {
+                let item = upsert.item;
+                if adopt.execute(params![source_id, item.external_id, item.url, now])? > 0 {
+                    adopted.insert(item.external_id.as_str());
+                }
+            }
+            
+            // Which items exist already, in one query
+            let external_ids = serde_json::to_string(&items.iter().map(|u| u.item.external_id.as_str()).collect::<Vec<_>>())
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            let mut existing: std::collections::HashMap<String, i64> = tx.prepare(
+                "SELECT external_id, id FROM items WHERE source_id = ?1 AND external_id IN (SELECT value FROM json_each(?2))"
+            )?
+                .query_map(params![source_id, external_ids], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+            
+            // Updates only rows whose content or source dates changed; RETURNING yields nothing
+            // for the rest. The stored content_status (extraction) is preserved. Without an
+            // explicit publication date, the first date the source reports stands in for it.
+            // A new copy of an article already read or archived from another source takes that
+            // copy's state, so reading it once covers both; a canonical URL found by extraction is
+            // kept as long as the item's url stays the same.
+            let mut upsert_stmt = tx.prepare(
+                r#"INSERT INTO items (source_id, external_id, title, summary, url, item_type, image_url, content_html, author, category, comments, thread_id, content_completeness, state, created_at, updated_at, comment_count, published_at, source_updated_at, canonical_url, fingerprint)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, COALESCE((SELECT CASE WHEN d.state IN ('read', 'archived') THEN d.state END FROM items d WHERE d.canonical_url = ?18 AND d.state != 'deleted' ORDER BY d.id LIMIT 1), 'unread'), ?14, ?14, ?15, COALESCE(?16, ?17), ?17, ?18, ?19)
+                ON CONFLICT(source_id, external_id) DO UPDATE SET title = excluded.title, summary = excluded.summary, url = excluded.url, item_type = excluded.item_type, image_url = excluded.image_url, content_html = excluded.content_html, author = excluded.author, category = excluded.category, comments = excluded.comments, thread_id = excluded.thread_id, content_completeness = COALESCE(excluded.content_completeness, items.content_completeness), updated_at = excluded.updated_at, comment_count = excluded.comment_count,
+                    published_at = COALESCE(?16, items.published_at, ?17), source_updated_at = COALESCE(?17, items.source_updated_at),
+                    canonical_url = CASE WHEN items.url IS excluded.url THEN COALESCE(items.canonical_url, excluded.canonical_url) ELSE excluded.canonical_url END, fingerprint = excluded.fingerprint
+                WHERE items.title IS NOT excluded.title OR items.summary IS NOT excluded.summary OR items.url IS NOT excluded.url OR items.item_type IS NOT excluded.item_type OR items.image_url IS NOT excluded.image_url OR items.content_html IS NOT excluded.content_html OR items.author IS NOT excluded.author OR items.category IS NOT excluded.category OR items.comments IS NOT excluded.comments OR items.thread_id IS NOT excluded.thread_id OR items.comment_count IS NOT excluded.comment_count
+                    OR (?16 IS NOT NULL AND items.published_at IS NOT ?16) OR (?17 IS NOT NULL AND items.source_updated_at IS NOT ?17)
+                RETURNING id"#
+            )?;
+            let mut canonical_urls = Vec::new();
+            let mut changed = Vec::new();
+            for upsert in items {
+                let item = upsert.item;
+                let canonical = canonical_url(&item.url);
+                let returned: Option<i64> = upsert_stmt.query_row(
+                    params![source_id, item.external_id, item.title, item.summary, item.url, item.item_type, item.image_url, item.content_html, item.author, upsert.category, item.comments, item.thread_id, upsert.content_completeness, now, item.comment_count, item.published_at, item.occurred_at, canonical, upsert.fingerprint],
+                    |row| row.get(0),
+                ).optional()?;
+                
+                let (id, outcome) = match (existing.get(&item.external_id).copied(), returned) {
+                    (None, Some(id)) => {
+                        // A repeated id later in the same poll updates this row
+                        existing.insert(item.external_id.clone(), id);
+                        (id, UpsertOutcome::Inserted)
+                    }
+                    (Some(id), Some(_)) => (id, UpsertOutcome::Updated),
+                    (Some(id), None) if adopted.contains(item.external_id.as_str()) => (id, UpsertOutcome::Updated),
+                    (Some(id), None) => (id, UpsertOutcome::Unchanged),
+                    (None, None) => {
+                        let id = tx.query_row(
+                            "SELECT id FROM items WHERE source_id = ?1 AND external_id = ?2",
+                            params![source_id, item.external_id],
+                            |row| row.get(0),
+                        )?;
+                        (id, UpsertOutcome::Unchanged)
+                    }
+                };
+                
+                if outcome != UpsertOutcome::Unchanged {
+                    changed.push(id);
+                }
+                
+                outcomes.push((id, outcome));
+                canonical_urls.extend(canonical);
+            }
+            Self::link_duplicates(&tx, &canonical_urls)?;
+            Self::index_items(&tx, &changed)?;
+            
+            // Keep enclosures in sync with the feed (an edited episode may swap its audio file).
+            // Unchanged items are compared with their stored attachments, loaded in one query.
+            if sync_attachments {
+                let unchanged: Vec<i64> = outcomes.iter()
+                    .filter(|(_, outcome)| *outcome == UpsertOutcome::Unchanged)
+                    .map(|(id, _)| *id)
+                    .collect();
+                let stored = Self::stored_attachments(&tx, &unchanged)?;
+                for (upsert, (id, outcome)) in items.iter().zip(&outcomes) {
+                    let matches = *outcome == UpsertOutcome::Unchanged
+                        && Self::attachments_match(stored.get(id).map(Vec::as_slice).unwrap_or_default(), &upsert.item.attachments);
+                    if !matches {
+                        Self::write_attachments(&tx, *id, &upsert.item.attachments, now)?;
+                    }
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(outcomes)
+    }
+
+    /// Puts each of the given items that isn't clustered yet into the story cluster of the
+    /// most similar item from another source published within `CLUSTER_WINDOW_SECS` of it,
+    /// if any is within `MAX_DISTANCE`. A cluster is identified by its first item's id.
+    /// Returns how many items joined a cluster.
+    pub fn cluster_items(&self, item_ids: &[i64]) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut clustered = 0;
+        {
+            let mut item_stmt = tx.prepare(
+                "SELECT fingerprint, source_id, COALESCE(published_at, created_at) FROM items WHERE id = ?1 AND fingerprint IS NOT NULL AND cluster_id IS NULL"
+            )?;
+            let mut candidates_stmt = tx.prepare(
+                "SELECT id, fingerprint, cluster_id FROM items WHERE fingerprint IS NOT NULL AND id != ?1 AND source_id != ?2 AND state != 'deleted' \
+                 AND COALESCE(published_at, created_at) BETWEEN ?3 - ?4 AND ?3 + ?4 ORDER BY id"
+            )?;
+            let mut join_stmt = tx.prepare("UPDATE items SET cluster_id = ?1 WHERE id IN (?2, ?3) AND cluster_id IS NULL")?;
+            for &id in item_ids {
+                let item = item_stmt.query_row(params![id], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+                }).optional()?;
+                let (item_fingerprint, source_id, at) = match item {
+                    Some(item) => item,
+                    None => continue, // Already clustered, or too little text
+                };
+                let candidates = candidates_stmt.query_map(params![id, source_id, at, CLUSTER_WINDOW_SECS], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, Option<i64>>(2)?))
+                })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Closest match wins, the earliest one among equals
+                let closest = candidates.into_iter()
+                    .map(|(candidate, candidate_fingerprint, cluster_id)| (distance(item_fingerprint, candidate_fingerprint), candidate, cluster_id))
+                    .filter(|(distance, _, _)| *distance <= MAX_DISTANCE)
+                    .min_by_key(|(distance, candidate, _)| (*distance, *candidate));
+                if let Some((_, candidate, cluster_id)) = closest {
+                    join_stmt.execute(params![cluster_id.unwrap_or(candidate), candidate, id])?;
+                    clustered += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(clustered)
+    }
+
+    /// Rewrites the search index entries of the given items from their current text, with
+    /// HTML indexed as plain text. Called wherever that text is written, in the same transaction.
+    fn index_items(conn: &Connection, item_ids: &[i64]) -> Result<()> {
+        let mut select = conn.prepare(
+            "SELECT title, summary, author, category, content_html, extracted_content_html FROM items WHERE id = ?1"
+        )?;
+        let mut delete = conn.prepare("DELETE FROM items_fts WHERE rowid = ?1")?;
+        let mut insert = conn.prepare(
+            "INSERT INTO items_fts(rowid, title, summary, author, category, content, extracted_content) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+        )?;
+        let text = |html: Option<String>| html.map(|h| search::html_to_text(&h));
+        for &id in item_ids {
+            let row = select.query_row(params![id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            }).optional()?;
+            delete.execute(params![id])?;
+            if let Some((title, summary, author, category, content, extracted)) = row {
+                insert.execute(params![id, title, text(summary), author, category, text(content), text(extracted)])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores an article imported from another reader, keeping its original timestamp and state.
+    /// Returns the item id and whether it was created; an existing item is only marked archived
+    /// if the import says it was starred.
+    pub fn import_item(&self, source_id: i64, article: &ImportedArticle) -> Result<(i64, bool)> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        
+        let existing: Result<i64, _> = conn.query_row(
+            "SELECT id FROM items WHERE source_id = ?1 AND (external_id = ?2 OR external_id = ?3)",
+            params![source_id, article.external_id, article.url],
+            |row| row.get(0),
+        );
+        if let Ok(id) = existing {
+            if article.state == "archived" {
+                conn.execute(
+                    "UPDATE items SET state = 'archived', updated_at = ?1 WHERE id = ?2 AND state != 'deleted'",
+                    params![now, id],
+                )?;
+            }
+            return Ok((id, false));
+        }
+        
+        let category = if article.categories.is_empty() {
+            None
+        } else {
+            serde_json::to_string(&article.categories).ok()
+        };
+        let created_at = article.published_at.unwrap_or(now);
+        conn.execute(
+            "INSERT INTO items (source_id, external_id, title, summary, url, item_type, content_html, author, category, state, created_at, updated_at, published_at, canonical_url) VALUES (?1, ?2, ?3, ?4, ?5, 'rss', ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![source_id, article.external_id, article.title, article.summary, article.url, article.content_html, article.author, category, article.state, created_at, now, article.published_at, canonical_url(&article.url)],
+        )?;
+        let id = conn.last_insert_rowid();
+        Self::index_items(&conn, &[id])?;
+        if let Some(canonical) = canonical_url(&article.url) {
+            Self::link_duplicates(&conn, &[canonical])?;
+        }
+        Ok((id, true))
+    }
+
+    /// Retrieves items with optional filtering by state, groups, or source IDs.
+    /// Supports both legacy single group filter and new multi-group filter via group_names.
+    /// With `group_similar`, each story cluster is one item listing the others.
+    pub fn get_items(&self, state_filter: Option<&str>, group_filter: Option<&str>, source_ids: Option<&[i64]>, group_names: Option<&[String]>, item_query: Option<&ItemQuery>, group_similar: bool) -> Result<Vec<Item>> {
+        let (mut conditions, params) = Self::item_filter_conditions(state_filter, group_filter, source_ids, group_names, item_query);
+        if source_ids.is_none_or(|ids| ids.is_empty()) {
+            conditions = Self::first_matching_copies(conditions);
+        }
+        // Order by created_at DESC (newest first), then by id DESC for consistent ordering when timestamps are identical
+        self.query_items(&conditions, &params, ViewSort::Newest, group_similar)
+    }
+
+    /// Items in a custom view: its rules and saved query, in the view's sort order.
+    pub fn get_view_items(&self, view: &CustomView, state_filter: Option<&str>) -> Result<Vec<Item>> {
+        let (conditions, params) = Self::view_conditions(view, state_filter)?;
+        self.query_items(&conditions, &params, view.sort_order, false)
+    }
+
+    /// WHERE conditions for a custom view, on top of the usual inbox filters.
+    fn view_conditions(view: &CustomView, state_filter: Option<&str>) -> Result<(Vec<String>, Vec<Value>)> {
+        let item_query = view.query.as_deref()
+            .map(ItemQuery::parse)
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let (mut conditions, mut params) = Self::item_filter_conditions(state_filter, None, None, None, item_query.as_ref());
+        if let Some(rules) = &view.rules {
+            let (rule_condition, rule_params) = rules.to_sql();
+            conditions.push(rule_condition);
+            params.extend(rule_params);
+        }
+        Ok((Self::first_matching_copies(conditions), params))
+    }
+
+    /// Narrows list conditions to the first matching copy (lowest id) of each article, so an
+    /// article is listed once, and still listed when its first copy is outside the list's
+    /// filters (another group, a read copy...). Copies share the `duplicate_of` of the first one.
+    /// Keeps the parameters of `conditions` in the same order.
+    fn first_matching_copies(conditions: Vec<String>) -> Vec<String> {
+        Self::first_matching(conditions, "COALESCE(i.duplicate_of, i.id)")
+    }
+
+    /// Narrows list conditions to the first matching item of each story cluster, which then
+    /// lists the others. A cluster stays listed while any of its items matches, e.g. in an
+    /// unread list after its first item was read on its own.
+    fn cluster_representatives(conditions: Vec<String>) -> Vec<String> {
+        // Unclustered items get a partition of their own (item ids are positive)
+        Self::first_matching(conditions, "COALESCE(i.cluster_id, -i.id)")
+    }
+
+    /// Keeps, among the rows matching `conditions`, the lowest id for each `partition` value.
+    fn first_matching(conditions: Vec<String>, partition: &str) -> Vec<String> {
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        vec![format!(
+            "i.id IN (SELECT id FROM (SELECT i.id, ROW_NUMBER() OVER (PARTITION BY {} ORDER BY i.id) AS row_rank \
+             FROM items i INNER JOIN sources s ON i.source_id = s.id{}) WHERE row_rank = 1)",
+            partition, where_clause
+        )]
+    }
+
+    fn query_items(&self, conditions: &[String], params: &[Value], sort: ViewSort, group_similar: bool) -> Result<Vec<Item>> {
+        let conn = self.conn.lock().unwrap();
+        
+        // Group names are looked up per source afterwards rather than joined and grouped per item
+        let mut query = "SELECT i.id, i.source_id, i.external_id, i.title, i.summary, i.url, i.item_type, i.state, i.created_at, i.updated_at, i.image_url, i.content_html, i.author, i.category, i.comments, s.name as source_name, NULL as source_group, i.content_status, i.extracted_content_html, i.content_completeness, i.extraction_attempted_at, i.extraction_failed_reason, i.comment_count, i.published_at, i.source_updated_at FROM items i INNER JOIN sources s ON i.source_id = s.id".to_string();
+        let mut conditions = conditions.to_vec();
+        if group_similar {
+            conditions = Self::cluster_representatives(conditions);
+        }
+        if !conditions.is_empty() {
+            query.push_str(" WHERE ");
+            query.push_str(&conditions.join(" AND "));
+        }
+        query.push_str(" ORDER BY ");
+        query.push_str(sort.order_by());
+        
+        let mut stmt = conn.prepare(&query)?;
+        let mut items: Vec<Item> = stmt.query_map(rusqlite::params_from_iter(params.iter()), Item::from_row_with_source)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let groups = Self::group_names_by_source(&conn, items.iter().map(|item| item.source_id))?;
+        for item in items.iter_mut() {
+            item.source_group = groups.get(&item.source_id).cloned();
+        }
+        Self::load_attachments(&conn, &mut items)?;
+        let mut duplicates = Self::duplicates_by_item(&conn, items.iter().map(|item| item.id))?;
+        let mut members = if group_similar {
+            Self::cluster_members_by_item(&conn, items.iter().map(|item| item.id))?
+        } else {
+            std::collections::HashMap::new()
+        };
+        for item in items.iter_mut() {
+            item.duplicates = duplicates.remove(&item.id).unwrap_or_default();
+            item.cluster_members = members.remove(&item.id).unwrap_or_default();
+        }
+        Ok(items)
+    }
+
+    /// The other members of each given item's story cluster, oldest first. Deleted items are left out.
+    fn cluster_members_by_item(conn: &Connection, item_ids: impl Iterator<Item = i64>) -> Result<std::collections::HashMap<i64, Vec<ClusterMember>>> {
+        let ids = serde_json::to_string(&item_ids.collect::<Vec<_>>())
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let mut stmt = conn.prepare(
+            "SELECT i.id, m.id, m.source_id, s.name, m.title, m.state FROM items i \
+             INNER JOIN items m ON m.cluster_id = i.cluster_id AND m.id != i.id \
+             INNER JOIN sources s ON m.source_id = s.id \
+             WHERE i.id IN (SELECT value FROM json_each(?1)) AND m.state != 'deleted' ORDER BY m.id"
+        )?;
+        let rows = stmt.query_map(params![ids], |row| {
+            Ok((row.get::<_, i64>(0)?, ClusterMember {
+                item_id: row.get(1)?,
+                source_id: row.get(2)?,
+                source_name: row.get(3)?,
+                title: row.get(4)?,
+                state: row.get(5)?,
+            }))
+        })?;
+        let mut members: std::collections::HashMap<i64, Vec<ClusterMember>> = std::collections::HashMap::new();
+        for row in rows {
+            let (item_id, member) = row?;
+            members.entry(item_id).or_default().push(member);
+        }
+        Ok(members)
     }
 
-    pub fn update_source_sync_time(&self, id: i64) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now().timestamp();
-        conn.execute(
-            "UPDATE sources SET last_synced_at = ?1 WHERE id = ?2",
-            params![now, id],
+    /// The other stored copies of each of the given items, oldest first. Deleted copies are left out.
+    fn duplicates_by_item(conn: &Connection, item_ids: impl Iterator<Item = i64>) -> Result<std::collections::HashMap<i64, Vec<ItemDuplicate>>> {
+        let ids = serde_json::to_string(&item_ids.collect::<Vec<_>>())
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let mut stmt = conn.prepare(
+            "SELECT i.id, d.id, d.source_id, s.name FROM items i \
+             INNER JOIN items d ON d.canonical_url = i.canonical_url AND d.id != i.id \
+             INNER JOIN sources s ON d.source_id = s.id \
+             WHERE i.id IN (SELECT value FROM json_each(?1)) AND d.state != 'deleted' ORDER BY d.id"
         )?;
-        Ok(())
+        let rows = stmt.query_map(params![ids], |row| {
+            Ok((row.get::<_, i64>(0)?, ItemDuplicate { item_id: row.get(1)?, source_id: row.get(2)?, source_name: row.get(3)? }))
+        })?;
+        let mut duplicates: std::collections::HashMap<i64, Vec<ItemDuplicate>> = std::collections::HashMap::new();
+        for row in rows {
+            let (item_id, duplicate) = row?;
+            duplicates.entry(item_id).or_default().push(duplicate);
+        }
+        Ok(duplicates)
     }
 
-    /// Upserts an item (inserts if new, updates if exists based on source_id + external_id).
-    /// Preserves item state and created_at timestamp on updates.
-    pub fn upsert_item(
-        &self,
-        source_id: i64,
-        external_id: &str,
-        title: &str,
-        summary: Option<&str>,
-        url: &str,
-        item_type: &str,
-        image_url: Option<&str>,
-        content_html: Option<&str>,
-        author: Option<&str>,
-        category: Option<&str>,
-        comments: Option<&str>,
-        thread_id: Option<&str>,
-        content_completeness: Option<&str>,
-    ) -> Result<i64> {
+    /// Comma-separated group names for each of the given sources that has groups.
+    fn group_names_by_source(conn: &Connection, source_ids: impl Iterator<Item = i64>) -> Result<std::collections::HashMap<i64, String>> {
+        let ids: std::collections::BTreeSet<i64> = source_ids.collect();
+        if ids.is_empty() {
+            return Ok(std::collections::HashMap::new());
+        }
+        let placeholders: Vec<&str> = ids.iter().map(|_| "?").collect();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT sg.source_id, GROUP_CONCAT(g.name, ', ') FROM source_groups sg INNER JOIN groups g ON sg.group_id = g.id WHERE sg.source_id IN ({}) GROUP BY sg.source_id",
+            placeholders.join(", ")
+        ))?;
+        let groups = stmt.query_map(rusqlite::params_from_iter(ids.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(groups)
+    }
+
+    /// One page of items for a list, without HTML bodies, in the filter's order (newest
+    /// first by default) or the view's. Pass the returned `next_cursor` to get the following page.
+    pub fn get_item_page(&self, filter: &ItemFilter, item_query: Option<&ItemQuery>, cursor: Option<&ItemCursor>, limit: i64) -> Result<ItemPage> {
+        let (conditions, mut params, sort) = match filter.view_id {
+            Some(view_id) => {
+                let view = self.get_custom_view(view_id)?;
+                let (conditions, params) = Self::view_conditions(&view, filter.state_filter.as_deref())?;
+                (conditions, params, view.sort_order)
+            }
+            None => {
+                let (mut conditions, params) = Self::item_filter_conditions(
+                    filter.state_filter.as_deref(),
+                    filter.group_filter.as_deref(),
+                    filter.source_ids.as_deref(),
+                    filter.group_names.as_deref(),
+                    item_query,
+                );
+                if filter.source_ids.as_ref().is_none_or(|ids| ids.is_empty()) {
+                    conditions = Self::first_matching_copies(conditions);
+                }
+                (conditions, params, filter.sort.unwrap_or_default())
+            }
+        };
         let conn = self.conn.lock().unwrap();
-        let now = Utc::now().timestamp();
         
-        // Try to find existing item
-        let existing: Result<i64, _> = conn.query_row(
-            "SELECT id FROM items WHERE source_id = ?1 AND external_id = ?2",
-            params![source_id, external_id],
+        let mut conditions = conditions;
+        if filter.group_similar {
+            conditions = Self::cluster_representatives(conditions);
+        }
+        let where_clause = |conditions: &[String]| if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM items i INNER JOIN sources s ON i.source_id = s.id{}", where_clause(&conditions)),
+            rusqlite::params_from_iter(params.iter()),
             |row| row.get(0),
+        )?;
+        
+        if let Some(cursor) = cursor {
+            let (after, after_params) = sort.after(cursor);
+            conditions.push(after);
+            params.extend(after_params);
+        }
+        // One extra row tells us whether there is another page
+        params.push(Value::Integer(limit + 1));
+        let query = format!(
+            "SELECT i.id, i.source_id, i.title, i.summary, i.url, i.item_type, i.state, i.created_at, i.updated_at, i.image_url, i.author, i.category, i.comment_count, s.name, i.content_status, i.content_completeness, EXISTS (SELECT 1 FROM attachments a WHERE a.item_id = i.id), i.published_at, i.source_updated_at FROM items i INNER JOIN sources s ON i.source_id = s.id{} ORDER BY {} LIMIT ?",
+            where_clause(&conditions),
+            sort.order_by(),
         );
-
-        match existing {
-            Ok(id) => {
-                // Update existing item - preserve content_status if already set
-                conn.execute(
-                    "UPDATE items SET title = ?1, summary = ?2, url = ?3, item_type = ?4, image_url = ?5, content_html = ?6, author = ?7, category = ?8, comments = ?9, thread_id = ?10, content_completeness = COALESCE(?11, content_completeness), updated_at = ?12 WHERE id = ?13",
-                    params![title, summary, url, item_type, image_url, content_html, author, category, comments, thread_id, content_completeness, now, id],
-                )?;
-                Ok(id)
-            }
-            Err(_) => {
-                // Insert new item
-                conn.execute(
-                    "INSERT INTO items (source_id, external_id, title, summary, url, item_type, image_url, content_html, author, category, comments, thread_id, content_completeness, state, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, 'unread', ?14, ?14)",
-                    params![source_id, external_id, title, summary, url, item_type, image_url, content_html, author, category, comments, thread_id, content_completeness, now],
-                )?;
-                Ok(conn.last_insert_rowid())
-            }
+        let mut stmt = conn.prepare(&query)?;
+        let mut items: Vec<ItemSummary> = stmt.query_map(rusqlite::params_from_iter(params.iter()), ItemSummary::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let next_cursor = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(|item| sort.cursor_after(item))
+        } else {
+            None
+        };
+        let groups = Self::group_names_by_source(&conn, items.iter().map(|item| item.source_id))?;
+        let mut duplicates = Self::duplicates_by_item(&conn, items.iter().map(|item| item.id))?;
+        let mut members = if filter.group_similar {
+            Self::cluster_members_by_item(&conn, items.iter().map(|item| item.id))?
+        } else {
+            std::collections::HashMap::new()
+        };
+        for item in items.iter_mut() {
+            item.source_group = groups.get(&item.source_id).cloned();
+            item.duplicates = duplicates.remove(&item.id).unwrap_or_default();
+            item.cluster_members = members.remove(&item.id).unwrap_or_default();
         }
+        Ok(ItemPage { items, total, next_cursor })
     }
 
-    /// Retrieves items with optional filtering by state, groups, or source IDs.
-    /// Supports both legacy single group filter and new multi-group filter via group_names.
-    pub fn get_items(&self, state_filter: Option<&str>, group_filter: Option<&str>, source_ids: Option<&[i64]>, group_names: Option<&[String]>) -> Result<Vec<Item>> {
-        let conn = self.conn.lock().unwrap();
-        
-        // JOIN with sources and groups tables to get group names
-        // Use LEFT JOIN for groups since a source might not have any groups
-        let mut query = "SELECT i.id, i.source_id, i.external_id, i.title, i.summary, i.url, i.item_type, i.state, i.created_at, i.updated_at, i.image_url, i.content_html, i.author, i.category, i.comments, s.name as source_name, GROUP_CONCAT(g.name, ', ') as source_group, i.content_status, i.extracted_content_html, i.content_completeness, i.extraction_attempted_at, i.extraction_failed_reason FROM items i INNER JOIN sources s ON i.source_id = s.id LEFT JOIN source_groups sg ON s.id = sg.source_id LEFT JOIN groups g ON sg.group_id = g.id".to_string();
+    /// WHERE conditions (over `items i` joined with `sources s`) and their parameters for
+    /// the inbox and custom view filters. Deleted items are excluded unless asked for.
+    fn item_filter_conditions(
+        state_filter: Option<&str>,
+        group_filter: Option<&str>,
+        source_ids: Option<&[i64]>,
+        group_names: Option<&[String]>,
+        query: Option<&ItemQuery>,
+    ) -> (Vec<String>, Vec<Value>) {
         let mut conditions: Vec<String> = Vec::new();
-        
-        // Build params in order - need to store owned values for string parameters
-        let mut string_params: Vec<String> = Vec::new();
-        let mut int_params: Vec<i64> = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
         
         // Always exclude deleted items unless explicitly requested
-        if state_filter != Some("deleted") {
+        if state_filter != Some("deleted") && !query.is_some_and(|q| q.includes_deleted()) {
             conditions.push("i.state != 'deleted'".to_string());
         }
         
         // State filter
         if let Some(state) = state_filter {
             conditions.push("i.state = ?".to_string());
-            string_params.push(state.to_string());
+            params.push(Value::Text(state.to_string()));
         }
         
-        // Source IDs filter (from custom views)
-        if let Some(ids) = source_ids {
-            if !ids.is_empty() {
-                let placeholders: Vec<String> = (1..=ids.len()).map(|_| "?".to_string()).collect();
-                conditions.push(format!("i.source_id IN ({})", placeholders.join(", ")));
-                int_params.extend_from_slice(ids);
-            }
+        // Source IDs filter (from custom views). A source's own list shows all of its items;
+        // elsewhere callers list an article carried by several sources once (see first_matching_copies).
+        if let Some(ids) = source_ids.filter(|ids| !ids.is_empty()) {
+            let placeholders: Vec<&str> = ids.iter().map(|_| "?").collect();
+            conditions.push(format!("i.source_id IN ({})", placeholders.join(", ")));
+            params.extend(ids.iter().map(|id| Value::Integer(*id)));
         }
         
         // Group names filter (from custom views) - takes precedence over legacy group_filter
         if let Some(groups) = group_names {
             if !groups.is_empty() {
                 // Filter by group names using EXISTS subquery
-                let placeholders: Vec<String> = (1..=groups.len()).map(|_| "?".to_string()).collect();
+                let placeholders: Vec<&str> = groups.iter().map(|_| "?").collect();
                 conditions.push(format!(
                     "EXISTS (SELECT 1 FROM source_groups sg2 INNER JOIN groups g2 ON sg2.group_id = g2.id WHERE sg2.source_id = s.id AND g2.name IN ({}))",
                     placeholders.join(", ")
                 ));
-                for group in groups {
-                    string_params.push(group.clone());
-                }
+                params.extend(groups.iter().map(|g| Value::Text(g.clone())));
             }
         } else if let Some(group) = group_filter {
             // Legacy single group filter support - now uses groups table
             conditions.push("EXISTS (SELECT 1 FROM source_groups sg2 INNER JOIN groups g2 ON sg2.group_id = g2.id WHERE sg2.source_id = s.id AND g2.name = ?)".to_string());
-            string_params.push(group.to_string());
+            params.push(Value::Text(group.to_string()));
         }
         
-        if !conditions.is_empty() {
-            query.push_str(" WHERE ");
-            query.push_str(&conditions.join(" AND "));
+        // Inbox query (is:unread author:alice ...)
+        if let Some(query) = query {
+            let (query_conditions, query_params) = query.to_sql();
+            conditions.extend(query_conditions);
+            params.extend(query_params);
         }
         
-        // Group by item fields to handle GROUP_CONCAT properly
-        query.push_str(" GROUP BY i.id, i.source_id, i.external_id, i.title, i.summary, i.url, i.item_type, i.state, i.created_at, i.updated_at, i.image_url, i.content_html, i.author, i.category, i.comments, s.name, i.content_status, i.extracted_content_html, i.content_completeness, i.extraction_attempted_at, i.extraction_failed_reason");
-        // Order by created_at DESC (newest first), then by id DESC for consistent ordering when timestamps are identical
-        query.push_str(" ORDER BY i.created_at DESC, i.id DESC");
+        (conditions, params)
+    }
+
+    /// Full-text search over items, best matches first, with the same filters as `get_items`.
+    /// Results carry the BM25 rank (lower is better), the title with matches highlighted and
+    /// a snippet of the best matching column, both as escaped HTML with `<mark>` tags.
+    pub fn search_items(
+        &self,
+        query: &str,
+        state_filter: Option<&str>,
+        group_filter: Option<&str>,
+        source_ids: Option<&[i64]>,
+        group_names: Option<&[String]>,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>> {
+        let fts_query = match search::fts_query(query) {
+            Some(q) => q,
+            None => return Ok(Vec::new()),
+        };
+        let conn = self.conn.lock().unwrap();
+        
+        let (mut conditions, mut filter_params) = Self::item_filter_conditions(state_filter, group_filter, source_ids, group_names, None);
+        // Copies are collapsed among the matching ones, so the match is part of the filter
+        if source_ids.is_none_or(|ids| ids.is_empty()) {
+            conditions.push("i.id IN (SELECT rowid FROM items_fts WHERE items_fts MATCH ?)".to_string());
+            filter_params.push(Value::Text(fts_query.clone()));
+            conditions = Self::first_matching_copies(conditions);
+        }
+        conditions.insert(0, "items_fts MATCH ?".to_string());
+        let mut params = vec![Value::Text(fts_query)];
+        params.extend(filter_params);
+        params.push(Value::Integer(limit));
+        
+        // Title matches weigh most, then summary, author and categories, then body text
+        let query = format!(
+            "SELECT i.id, i.source_id, i.external_id, i.title, i.summary, i.url, i.item_type, i.state, i.created_at, i.updated_at, i.image_url, i.content_html, i.author, i.category, i.comments, s.name, \
+             (SELECT GROUP_CONCAT(g.name, ', ') FROM source_groups sg INNER JOIN groups g ON sg.group_id = g.id WHERE sg.source_id = s.id), \
+             i.content_status, i.extracted_content_html, i.content_completeness, i.extraction_attempted_at, i.extraction_failed_reason, i.comment_count, \
+             i.published_at, i.source_updated_at, \
+             bm25(items_fts, 10.0, 4.0, 3.0, 3.0, 1.0, 1.0) AS rank, \
+             highlight(items_fts, 0, '{start}', '{end}'), \
+             snippet(items_fts, -1, '{start}', '{end}', '…', 24) \
+             FROM items_fts INNER JOIN items i ON i.id = items_fts.rowid INNER JOIN sources s ON i.source_id = s.id \
+             WHERE {} ORDER BY rank, i.created_at DESC LIMIT ?",
+            conditions.join(" AND "),
+            start = search::MARK_START,
+            end = search::MARK_END,
+        );
         
-        // Build params vector with proper references
         let mut stmt = conn.prepare(&query)?;
-        let items: Vec<Item> = if !string_params.is_empty() || !int_params.is_empty() {
-            let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
-            let mut str_idx = 0;
-            let mut int_idx = 0;
-            
-            // Add state if present
-            if state_filter.is_some() {
-                params.push(&string_params[str_idx]);
-                str_idx += 1;
-            }
-            
-            // Add source IDs if present
-            if source_ids.is_some() && !int_params.is_empty() {
-                for _ in 0..int_params.len() {
-                    params.push(&int_params[int_idx]);
-                    int_idx += 1;
-                }
-            }
-            
-            // Add group parameters if present
-            if let Some(groups) = group_names {
-                if !groups.is_empty() {
-                    for _group in groups {
-                        params.push(&string_params[str_idx]);
-                        str_idx += 1;
-                    }
-                }
-            } else if group_filter.is_some() {
-                params.push(&string_params[str_idx]);
-            }
-            
-            stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| Item::from_row_with_source(row))?
-                .collect::<Result<Vec<_>, _>>()?
-        } else {
-            stmt.query_map([], |row| Item::from_row_with_source(row))?
-                .collect::<Result<Vec<_>, _>>()?
-        };
-        Ok(items)
+        let mut results = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            Ok(SearchResult {
+                item: Item::from_row_with_source(row)?,
+                rank: row.get(25)?,
+                title_highlight: search::render_highlights(&row.get::<_, String>(26)?),
+                snippet: search::render_highlights(&row.get::<_, String>(27)?),
+            })
+        })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut duplicates = Self::duplicates_by_item(&conn, results.iter().map(|result| result.item.id))?;
+        for result in results.iter_mut() {
+            result.item.duplicates = duplicates.remove(&result.item.id).unwrap_or_default();
+        }
+        Ok(results)
     }
 
     pub fn get_item(&self, id: i64) -> Result<Item> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, source_id, external_id, title, summary, url, item_type, state, created_at, updated_at, image_url, content_html, author, category, comments, content_status, extracted_content_html, content_completeness, extraction_attempted_at, extraction_failed_reason FROM items WHERE id = ?1"
+            "SELECT id, source_id, external_id, title, summary, url, item_type, state, created_at, updated_at, image_url, content_html, author, category, comments, content_status, extracted_content_html, content_completeness, extraction_attempted_at, extraction_failed_reason, comment_count, published_at, source_updated_at FROM items WHERE id = ?1"
+        )?;
+        let mut item = stmt.query_row(params![id], |row| Item::from_row(row))?;
+        item.attachments = Self::get_attachments_for_items(&conn, &[id])?;
+        item.duplicates = Self::duplicates_by_item(&conn, std::iter::once(id))?.remove(&id).unwrap_or_default();
+        Ok(item)
+    }
+
+    /// Loads attachments for the given items, ordered by item and insertion order.
+    fn get_attachments_for_items(conn: &Connection, item_ids: &[i64]) -> Result<Vec<Attachment>> {
+        let mut attachments = Vec::new();
+        // Stay well below SQLite's bound parameter limit
+        for chunk in item_ids.chunks(500) {
+            let placeholders: Vec<&str> = chunk.iter().map(|_| "?").collect();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, item_id, url, mime_type, length, duration_seconds, episode, season, explicit, image_url, created_at FROM attachments WHERE item_id IN ({}) ORDER BY item_id, id",
+                placeholders.join(", ")
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk.iter()), Attachment::from_row)?;
+            for attachment in rows {
+                attachments.push(attachment?);
+            }
+        }
+        Ok(attachments)
+    }
+
+    /// Fills `attachments` on each item with a single query per chunk of items.
+    fn load_attachments(conn: &Connection, items: &mut [Item]) -> Result<()> {
+        let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+        let mut by_item: std::collections::HashMap<i64, Vec<Attachment>> = std::collections::HashMap::new();
+        for attachment in Self::get_attachments_for_items(conn, &ids)? {
+            by_item.entry(attachment.item_id).or_default().push(attachment);
+        }
+        for item in items.iter_mut() {
+            if let Some(attachments) = by_item.remove(&item.id) {
+                item.attachments = attachments;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces an item's attachments with the ones from the latest poll.
+    fn write_attachments(conn: &Connection, item_id: i64, attachments: &[IngestedAttachment], now: i64) -> Result<()> {
+        conn.prepare_cached("DELETE FROM attachments WHERE item_id = ?1")?
+            .execute(params![item_id])?;
+        let mut insert = conn.prepare_cached(
+            "INSERT OR IGNORE INTO attachments (item_id, url, mime_type, length, duration_seconds, episode, season, explicit, image_url, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+        )?;
+        for a in attachments {
+            insert.execute(params![item_id, a.url, a.mime_type, a.length, a.duration_seconds, a.episode, a.season, a.explicit, a.image_url, now])?;
+        }
+        Ok(())
+    }
+
+    /// Stored attachments of the given items, by item id, each in stored order.
+    fn stored_attachments(conn: &Connection, item_ids: &[i64]) -> Result<std::collections::HashMap<i64, Vec<IngestedAttachment>>> {
+        let mut by_item: std::collections::HashMap<i64, Vec<IngestedAttachment>> = std::collections::HashMap::new();
+        if item_ids.is_empty() {
+            return Ok(by_item);
+        }
+        let ids = serde_json::to_string(item_ids)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT item_id, url, mime_type, length, duration_seconds, episode, season, explicit, image_url FROM attachments \
+             WHERE item_id IN (SELECT value FROM json_each(?1)) ORDER BY item_id, id"
         )?;
-        stmt.query_row(params![id], |row| Item::from_row(row))
+        let rows = stmt.query_map(params![ids], |row| {
+            Ok((row.get::<_, i64>(0)?, IngestedAttachment {
+                url: row.get(1)?,
+                mime_type: row.get(2)?,
+                length: row.get(3)?,
+                duration_seconds: row.get(4)?,
+                episode: row.get(5)?,
+                season: row.get(6)?,
+                explicit: row.get(7)?,
+                image_url: row.get(8)?,
+            }))
+        })?;
+        for row in rows {
+            let (item_id, attachment) = row?;
+            by_item.entry(item_id).or_default().push(attachment);
+        }
+        Ok(by_item)
+    }
+
+    /// Whether stored attachments are exactly these, in order (repeated URLs are stored once).
+    fn attachments_match(stored: &[IngestedAttachment], attachments: &[IngestedAttachment]) -> bool {
+        let mut urls = std::collections::HashSet::new();
+        let expected: Vec<&IngestedAttachment> = attachments.iter().filter(|a| urls.insert(a.url.as_str())).collect();
+        stored.iter().eq(expected)
     }
 
-    /// Deletes items older than specified days, preserving archived items.
-    pub fn cleanup_old_items(&self, days: i64) -> Result<usize> {
+    /// Deletes items older than specified days, preserving archived items. Age is measured
+    /// from ingestion, or from the source's publication date when `by_published` is set.
+    pub fn cleanup_old_items(&self, days: i64, by_published: bool) -> Result<usize> {
         let conn = self.conn.lock().unwrap();
         let cutoff_timestamp = Utc::now().timestamp() - (days * 24 * 60 * 60);
+        let age_column = if by_published { "COALESCE(published_at, created_at)" } else { "created_at" };
         
         // Delete items older than cutoff, but preserve archived items
         let deleted = conn.execute(
-            "DELETE FROM items WHERE state != 'archived' AND created_at < ?1",
+            &format!("DELETE FROM items WHERE state != 'archived' AND {} < ?1", age_column),
             params![cutoff_timestamp],
         )?;
         
+        // Foreign keys aren't enforced, so drop attachments of deleted items explicitly
+        conn.execute(
+            "DELETE FROM attachments WHERE item_id NOT IN (SELECT id FROM items)",
+            [],
+        )?;
+        Self::link_duplicates(&conn, &[])?;
+        
         Ok(deleted)
     }
 
@@ -535,6 +1681,7 @@ impl Database {
                     "DELETE FROM items WHERE source_id = ?1",
                     params![id],
                 )?;
+                Self::link_duplicates(&conn, &[])?;
                 Ok(deleted)
             }
             Err(_) => {
@@ -544,19 +1691,23 @@ impl Database {
         }
     }
 
-    pub fn update_item_state(&self, id: i64, state: &str) -> Result<()> {
+    /// Sets an item's state, along with every other copy of it (same canonical URL) and, with
+    /// `whole_cluster` (the list groups similar stories), every item in its story cluster.
+    pub fn update_item_state(&self, id: i64, state: &str, whole_cluster: bool) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         let now = Utc::now().timestamp();
         conn.execute(
-            "UPDATE items SET state = ?1, updated_at = ?2 WHERE id = ?3",
-            params![state, now, id],
+            "UPDATE items SET state = ?1, updated_at = ?2 WHERE id = ?3 OR canonical_url = (SELECT canonical_url FROM items WHERE id = ?3) \
+             OR (?4 AND cluster_id = (SELECT cluster_id FROM items WHERE id = ?3))",
+            params![state, now, id, whole_cluster],
         )?;
         Ok(())
     }
 
-    /// Updates multiple items' state in a single transaction
-    pub fn bulk_update_item_state(&self, ids: &[i64], state: &str) -> Result<usize> {
-        let conn = self.conn.lock().unwrap();
+    /// Updates multiple items' state, and their copies from other sources (and story clusters,
+    /// with `whole_cluster`), in a single transaction. Returns how many of the given items were updated.
+    pub fn bulk_update_item_state(&self, ids: &[i64], state: &str, whole_cluster: bool) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
         let now = Utc::now().timestamp();
         
         if ids.is_empty() {
@@ -569,6 +1720,11 @@ impl Database {
             "UPDATE items SET state = ?, updated_at = ? WHERE id IN ({})",
             placeholders.join(", ")
         );
+        // The given items already have the new state by the time their copies are updated
+        let copies_query = "UPDATE items SET state = ?1, updated_at = ?2 WHERE state != ?1 AND (canonical_url IN (SELECT canonical_url FROM items WHERE id IN (SELECT value FROM json_each(?3))) \
+             OR (?4 AND cluster_id IN (SELECT cluster_id FROM items WHERE id IN (SELECT value FROM json_each(?3)))))";
+        let ids_json = serde_json::to_string(ids)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
         
         // Build params: state, updated_at, then all IDs
         let mut params: Vec<&dyn rusqlite::ToSql> = vec![&state, &now];
@@ -576,11 +1732,13 @@ impl Database {
             params.push(id);
         }
         
-        let updated = conn.execute(&query, rusqlite::params_from_iter(params.iter()))?;
+        let tx = conn.transaction()?;
+        let updated = tx.execute(&query, rusqlite::params_from_iter(params.iter()))?;
+        tx.execute(copies_query, params![state, now, ids_json, whole_cluster])?;
+        tx.commit()?;
         Ok(updated)
     }
 
-
     /// Updates item timestamps to simulate "leaving soon" state (for testing/debugging).
     pub fn make_items_leaving_soon(&self, count: i64) -> Result<usize> {
         let conn = self.conn.lock().unwrap();
@@ -614,42 +1772,50 @@ impl Database {
     }
 
     // Custom View operations
-    pub fn create_custom_view(&self, name: &str, source_ids: Option<&str>, group_names: Option<&str>) -> Result<i64> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now().timestamp();
-        conn.execute(
-            "INSERT INTO custom_views (name, source_ids, group_names, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
-            params![name, source_ids, group_names, now],
-        )?;
-        Ok(conn.last_insert_rowid())
+    pub fn create_custom_view(&self, name: &str, rules: Option<&ViewRule>, sort_order: ViewSort, query: Option<&str>) -> Result<i64> {
+        let rules_json = Self::rules_json(rules)?;
+        let id = {
+            let conn = self.conn.lock().unwrap();
+            let now = Utc::now().timestamp();
+            conn.execute(
+                "INSERT INTO custom_views (name, rules, sort_order, query, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                params![name, rules_json, sort_order.as_str(), query, now],
+            )?;
+            conn.last_insert_rowid()
+        };
+        self.refresh_view_unread_count(id)?;
+        Ok(id)
     }
 
-    pub fn get_all_custom_views(&self) -> Result<Vec<super::models::CustomView>> {
+    pub fn get_all_custom_views(&self) -> Result<Vec<CustomView>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, source_ids, group_names, created_at, updated_at FROM custom_views ORDER BY name"
+            "SELECT id, name, rules, sort_order, unread_count, query, created_at, updated_at FROM custom_views ORDER BY name"
         )?;
-        let views = stmt.query_map([], |row| super::models::CustomView::from_row(row))?
+        let views = stmt.query_map([], CustomView::from_row)?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(views)
     }
 
-    pub fn get_custom_view(&self, id: i64) -> Result<super::models::CustomView> {
+    pub fn get_custom_view(&self, id: i64) -> Result<CustomView> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, source_ids, group_names, created_at, updated_at FROM custom_views WHERE id = ?1"
+            "SELECT id, name, rules, sort_order, unread_count, query, created_at, updated_at FROM custom_views WHERE id = ?1"
         )?;
-        stmt.query_row(params![id], |row| super::models::CustomView::from_row(row))
+        stmt.query_row(params![id], CustomView::from_row)
     }
 
-    pub fn update_custom_view(&self, id: i64, name: &str, source_ids: Option<&str>, group_names: Option<&str>) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now().timestamp();
-        conn.execute(
-            "UPDATE custom_views SET name = ?1, source_ids = ?2, group_names = ?3, updated_at = ?4 WHERE id = ?5",
-            params![name, source_ids, group_names, now, id],
-        )?;
-        Ok(())
+    pub fn update_custom_view(&self, id: i64, name: &str, rules: Option<&ViewRule>, sort_order: ViewSort, query: Option<&str>) -> Result<()> {
+        let rules_json = Self::rules_json(rules)?;
+        {
+            let conn = self.conn.lock().unwrap();
+            let now = Utc::now().timestamp();
+            conn.execute(
+                "UPDATE custom_views SET name = ?1, rules = ?2, sort_order = ?3, query = ?4, updated_at = ?5 WHERE id = ?6",
+                params![name, rules_json, sort_order.as_str(), query, now, id],
+            )?;
+        }
+        self.refresh_view_unread_count(id)
     }
 
     pub fn delete_custom_view(&self, id: i64) -> Result<()> {
@@ -658,15 +1824,71 @@ impl Database {
         Ok(())
     }
 
+    fn rules_json(rules: Option<&ViewRule>) -> Result<Option<String>> {
+        rules.map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+    }
+
+    /// Recounts one view's unread items and stores the count on the view.
+    pub fn refresh_view_unread_count(&self, id: i64) -> Result<()> {
+        let view = self.get_custom_view(id)?;
+        let (conditions, params) = Self::view_conditions(&view, Some("unread"))?;
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM items i INNER JOIN sources s ON i.source_id = s.id WHERE {}", conditions.join(" AND ")),
+            rusqlite::params_from_iter(params.iter()),
+            |row| row.get(0),
+        )?;
+        conn.execute("UPDATE custom_views SET unread_count = ?1 WHERE id = ?2", params![count, id])?;
+        Ok(())
+    }
+
+    /// Recounts every view's unread items, after syncs and state changes.
+    pub fn refresh_view_unread_counts(&self) -> Result<()> {
+        let ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id FROM custom_views")?;
+            let ids = stmt.query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            ids
+        };
+        for id in ids {
+            self.refresh_view_unread_count(id)?;
+        }
+        Ok(())
+    }
+
     // Event operations
-    pub fn create_event(&self, item_id: i64, event_type: &str, payload_json: Option<&str>) -> Result<i64> {
+    /// An item's recorded changes, newest first. Legacy per-poll `update` rows are left out.
+    pub fn get_item_history(&self, item_id: i64) -> Result<Vec<ItemEvent>> {
         let conn = self.conn.lock().unwrap();
-        let now = Utc::now().timestamp();
-        conn.execute(
-            "INSERT INTO events (item_id, event_type, payload_json, occurred_at) VALUES (?1, ?2, ?3, ?4)",
-            params![item_id, event_type, payload_json, now],
+        let mut stmt = conn.prepare(
+            "SELECT id, item_id, event_type, payload_json, occurred_at FROM events WHERE item_id = ?1 AND event_type != 'update' ORDER BY occurred_at DESC, id DESC"
         )?;
-        Ok(conn.last_insert_rowid())
+        let events = stmt.query_map(params![item_id], ItemEvent::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    /// Deletes the `update` events older builds wrote on every poll. The dates they carried are
+    /// first copied to items still missing `published_at` / `source_updated_at`, so nothing is
+    /// lost even if the events outlived the migration that backfilled those columns.
+    /// Returns how many rows were removed.
+    pub fn compact_item_history(&self) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE items SET
+                published_at = COALESCE(published_at, (SELECT MIN(json_extract(e.payload_json, '$.occurred_at')) FROM events e WHERE e.item_id = items.id AND e.event_type = 'update')),
+                source_updated_at = COALESCE(source_updated_at, (SELECT MAX(json_extract(e.payload_json, '$.occurred_at')) FROM events e WHERE e.item_id = items.id AND e.event_type = 'update'))
+             WHERE (published_at IS NULL OR source_updated_at IS NULL)
+                AND id IN (SELECT item_id FROM events WHERE event_type = 'update')",
+            [],
+        )?;
+        let removed = tx.execute("DELETE FROM events WHERE event_type = 'update'", [])?;
+        tx.commit()?;
+        Ok(removed)
     }
 
     // Group operations
@@ -1054,6 +2276,21 @@ impl Database {
             "UPDATE items SET content_status = ?1, extracted_content_html = ?2, content_completeness = ?3, extraction_attempted_at = ?4, extraction_failed_reason = ?5, updated_at = ?6 WHERE id = ?7",
             params![status, extracted_content, completeness, now, failed_reason, now, item_id],
         )?;
+        Self::index_items(&conn, &[item_id])?;
+        Ok(())
+    }
+
+    /// Records the canonical URL an extracted page declares for itself. If that makes the item
+    /// a copy of one already read or archived, it takes that item's state.
+    pub fn set_item_canonical_url(&self, item_id: i64, canonical_url: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE items SET canonical_url = ?2, state = COALESCE((SELECT CASE WHEN d.state IN ('read', 'archived') THEN d.state END FROM items d \
+             WHERE d.canonical_url = ?2 AND d.id != ?1 AND d.state != 'deleted' ORDER BY d.id LIMIT 1), state) \
+             WHERE id = ?1 AND canonical_url IS NOT ?2",
+            params![item_id, canonical_url],
+        )?;
+        Self::link_duplicates(&conn, &[canonical_url.to_string()])?;
         Ok(())
     }
 
@@ -1119,6 +2356,519 @@ mod tests {
         assert!(tables.contains(&"sources".to_string()));
         assert!(tables.contains(&"items".to_string()));
         assert!(tables.contains(&"events".to_string()));
+        assert!(tables.contains(&"attachments".to_string()));
+    }
+
+    #[test]
+    fn test_sync_runs() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("feed", "Infra", "{}", None, None).unwrap();
+
+        let run_id = db.start_sync_run(source_id, "background").unwrap();
+        let in_progress = db.get_sync_runs(Some(source_id), 10).unwrap();
+        assert_eq!(in_progress[0].finished_at, None);
+
+        let stats = SyncRunStats { duration_ms: 120, http_requests: 1, items_new: 2, items_updated: 1, items_unchanged: 7 };
+        db.finish_sync_run(run_id, &stats, None).unwrap();
+        let runs = db.get_sync_runs(Some(source_id), 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].source_name.as_deref(), Some("Infra"));
+        assert_eq!(runs[0].trigger, "background");
+        assert_eq!((runs[0].items_new, runs[0].items_updated, runs[0].items_unchanged), (2, 1, 7));
+        assert!(runs[0].finished_at.is_some());
+
+        assert_eq!(db.prune_sync_runs(Utc::now().timestamp() + 1).unwrap(), 1);
+        assert!(db.get_sync_runs(None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_items() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("feed", "Infra", "{}", None, None).unwrap();
+        let (k8s_id, _) = db.upsert_item(
+            source_id, "1", "Scaling <Kubernetes> clusters", None, "https://example.org/1", "post",
+            None, Some("<p>Operators &amp; controllers</p>"), Some("alice"), None, None, None, None, None,
+        ).unwrap();
+        db.upsert_item(
+            source_id, "2", "Release notes", Some("Kubernetes is mentioned in passing"), "https://example.org/2", "post",
+            None, None, Some("bob"), None, None, None, None, None,
+        ).unwrap();
+
+        let results = db.search_items("kubernet", None, None, None, None, 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].item.id, k8s_id); // Title match ranks first
+        assert_eq!(results[0].title_highlight, "Scaling &lt;<mark>Kubernetes</mark>&gt; clusters");
+
+        // Indexed as text, kept in sync with extracted content
+        assert_eq!(db.search_items("controllers", None, None, None, None, 10).unwrap().len(), 1);
+        assert!(db.search_items("sidecar", None, None, None, None, 10).unwrap().is_empty());
+        db.update_item_content_status(k8s_id, "extracted", Some("<article>Sidecar containers</article>"), None, None).unwrap();
+        let results = db.search_items("sidecar", None, None, None, None, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].snippet.contains("<mark>Sidecar</mark>"));
+
+        db.update_item_state(k8s_id, "archived", false).unwrap();
+        assert_eq!(db.search_items("kubernetes", Some("unread"), None, None, None, 10).unwrap().len(), 1);
+        assert!(db.search_items("\"-\"", None, None, None, None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_index_follows_writes() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("feed", "Infra", "{}", None, None).unwrap();
+        let found = |query: &str| db.search_items(query, None, None, None, None, 10).unwrap()
+            .into_iter().map(|result| result.item.id).collect::<Vec<_>>();
+
+        // A feed update replaces the indexed text
+        let (id, _) = db.upsert_item(
+            source_id, "1", "Draft", Some("<p>Terraform modules</p>"), "https://example.org/1", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        assert_eq!(found("terraform"), vec![id]);
+        db.upsert_item(
+            source_id, "1", "Draft", Some("<p>Pulumi stacks</p>"), "https://example.org/1", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        assert!(found("terraform").is_empty());
+        assert_eq!(found("pulumi"), vec![id]);
+
+        // So does extracting the article again
+        db.update_item_content_status(id, "extracted", Some("<article>Ansible roles</article>"), Some("full"), None).unwrap();
+        assert_eq!(found("ansible"), vec![id]);
+        db.update_item_content_status(id, "extracted", Some("<article>Chef cookbooks</article>"), Some("full"), None).unwrap();
+        assert!(found("ansible").is_empty());
+        assert_eq!(found("cookbooks"), vec![id]);
+
+        // Imported articles are searchable by their text and categories
+        let (imported, _) = db.import_item(source_id, &ImportedArticle {
+            external_id: "https://example.org/2".to_string(),
+            title: "Saved for later".to_string(),
+            url: "https://example.org/2".to_string(),
+            content_html: Some("<div>Nomad &amp; Consul</div>".to_string()),
+            categories: vec!["hashicorp".to_string()],
+            state: "archived".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(found("consul"), vec![imported]);
+        assert_eq!(found("hashicorp"), vec![imported]);
+    }
+
+    #[test]
+    fn test_items_writable_without_app_functions() {
+        let path = std::env::temp_dir().join(format!("umbra-relay-fts-{}.db", std::process::id()));
+        let db = Database::new(path.to_str().unwrap()).unwrap();
+        let source_id = db.create_source("feed", "Infra", "{}", None, None).unwrap();
+        drop(db);
+
+        // e.g. the sqlite3 shell, which doesn't have strip_html()
+        let conn = Connection::open(&path).unwrap();
+        conn.execute(
+            "INSERT INTO items (source_id, external_id, title, url, item_type, state, created_at, updated_at) VALUES (?1, '1', 'Title', 'https://example.org/1', 'post', 'unread', 0, 0)",
+            params![source_id],
+        ).unwrap();
+        conn.execute("UPDATE items SET summary = '<p>Edited</p>'", []).unwrap();
+        conn.execute("DELETE FROM items", []).unwrap();
+        drop(conn);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_item_pages() {
+        let db = Database::new(":memory:").unwrap();
+        let group_id = db.create_group("Work").unwrap();
+        let source_id = db.create_source("feed", "Infra", "{}", Some(&[group_id]), None).unwrap();
+        for n in 0..5 {
+            db.upsert_item(
+                source_id, &n.to_string(), &format!("Item {}", n), None, &format!("https://example.org/{}", n), "post",
+                None, Some("<p>Body</p>"), None, None, None, None, None, None,
+            ).unwrap();
+        }
+
+        // Items share a timestamp, so pages rely on the id tie-breaker
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = db.get_item_page(&ItemFilter::default(), None, cursor.as_ref(), 2).unwrap();
+            assert_eq!(page.total, 5);
+            assert!(page.items.iter().all(|item| item.source_group.as_deref() == Some("Work")));
+            seen.extend(page.items.iter().map(|item| item.title.clone()));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(seen, vec!["Item 4", "Item 3", "Item 2", "Item 1", "Item 0"]);
+
+        let filter = ItemFilter { query: Some("is:read".to_string()), ..Default::default() };
+        let query = ItemQuery::parse("is:read").unwrap();
+        let page = db.get_item_page(&filter, Some(&query), None, 10).unwrap();
+        assert_eq!(page.total, 0);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_published_dates() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("feed", "Blog", "{}", None, None).unwrap();
+        let post = |id: &str, published_at: Option<i64>, occurred_at: Option<i64>| IngestedItem {
+            external_id: id.to_string(),
+            title: format!("Post {}", id),
+            summary: None,
+            url: format!("https://example.org/{}", id),
+            item_type: "post".to_string(),
+            occurred_at,
+            published_at,
+            image_url: None,
+            content_html: None,
+            author: None,
+            category: None,
+            comments: None,
+            comment_count: None,
+            thread_id: None,
+            attachments: vec![],
+        };
+        let items = [post("old", Some(1_000), Some(5_000)), post("new", None, Some(3_000)), post("undated", None, None)];
+        let upserts: Vec<ItemUpsert> = items.iter()
+            .map(|item| ItemUpsert { item, category: None, content_completeness: None, fingerprint: None })
+            .collect();
+        let ids: Vec<i64> = db.upsert_items(source_id, &upserts).unwrap().into_iter().map(|(id, _)| id).collect();
+
+        let old = db.get_item(ids[0]).unwrap();
+        assert_eq!((old.published_at, old.source_updated_at), (Some(1_000), Some(5_000)));
+        // Without a publication date the first source date stands in, and later edits keep it
+        let mut edited = post("new", None, Some(4_000));
+        edited.title = "Post new (edited)".to_string();
+        db.upsert_items(source_id, &[ItemUpsert { item: &edited, category: None, content_completeness: None, fingerprint: None }]).unwrap();
+        let new = db.get_item(ids[1]).unwrap();
+        assert_eq!((new.published_at, new.source_updated_at), (Some(3_000), Some(4_000)));
+
+        // Undated items fall back to ingestion time, so they sort first here
+        let filter = ItemFilter { sort: Some(ViewSort::Published), ..Default::default() };
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = db.get_item_page(&filter, None, cursor.as_ref(), 1).unwrap();
+            seen.extend(page.items.iter().map(|item| item.id));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(seen, vec![ids[2], ids[1], ids[0]]);
+        // Adaptive polling only learns from real publication dates
+        assert_eq!(db.get_recent_published_timestamps(source_id, 10).unwrap(), vec![3_000, 1_000]);
+
+        assert_eq!(db.cleanup_old_items(1, false).unwrap(), 0);
+        assert_eq!(db.cleanup_old_items(1, true).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_cross_source_duplicates() {
+        let db = Database::new(":memory:").unwrap();
+        let blog = db.create_source("feed", "Blog", "{}", None, None).unwrap();
+        let aggregator = db.create_source("feed", "Aggregator", "{}", None, None).unwrap();
+        let planet = db.create_source("feed", "Planet", "{}", None, None).unwrap();
+        let (original, _) = db.upsert_item(
+            blog, "post-1", "Release 1.0", None, "https://blog.example.org/release/", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        let (copy, _) = db.upsert_item(
+            aggregator, "agg-9", "Release 1.0", None, "http://www.blog.example.org/release?utm_source=agg", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+
+        // One inbox entry, listing the other copy
+        let items = db.get_items(None, None, None, None, None, false).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, original);
+        assert_eq!(items[0].duplicates, vec![ItemDuplicate { item_id: copy, source_id: aggregator, source_name: "Aggregator".to_string() }]);
+        assert_eq!(db.get_item_page(&ItemFilter::default(), None, None, 10).unwrap().total, 1);
+        // A source's own list still shows its copy
+        let items = db.get_items(None, None, Some(&[aggregator]), None, None, false).unwrap();
+        assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![copy]);
+
+        // Reading one copy reads them all, including copies that arrive later
+        db.update_item_state(copy, "read", false).unwrap();
+        assert_eq!(db.get_item(original).unwrap().state, "read");
+        let (late, _) = db.upsert_item(
+            planet, "p-3", "Release 1.0", None, "https://blog.example.org/release#top", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        assert_eq!(db.get_item(late).unwrap().state, "read");
+        assert!(db.get_items(Some("unread"), None, None, None, None, false).unwrap().is_empty());
+
+        assert_eq!(db.bulk_update_item_state(&[late], "unread", false).unwrap(), 1);
+        assert_eq!(db.get_items(Some("unread"), None, None, None, None, false).unwrap().len(), 1);
+        assert_eq!(db.get_item(original).unwrap().duplicates.len(), 2);
+
+        // Search lists the article once too
+        let results = db.search_items("release", None, None, None, None, 10).unwrap();
+        assert_eq!(results.iter().map(|result| result.item.id).collect::<Vec<_>>(), vec![original]);
+        assert_eq!(results[0].item.duplicates.len(), 2);
+
+        // Once the first copy is gone, the next one takes its place
+        db.delete_items_by_source_name("Blog").unwrap();
+        let items = db.get_items(None, None, None, None, None, false).unwrap();
+        assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![copy]);
+        assert_eq!(items[0].duplicates.len(), 1);
+
+        // A deleted article isn't deleted again when another source brings it back
+        db.update_item_state(copy, "deleted", false).unwrap();
+        let digest = db.create_source("feed", "Digest", "{}", None, None).unwrap();
+        let (returned, _) = db.upsert_item(
+            digest, "d-1", "Release 1.0", None, "https://blog.example.org/release", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        assert_eq!(db.get_item(returned).unwrap().state, "unread");
+    }
+
+    #[test]
+    fn test_duplicates_across_groups() {
+        let db = Database::new(":memory:").unwrap();
+        let work = db.create_group("Work").unwrap();
+        let news = db.create_group("News").unwrap();
+        let blog = db.create_source("feed", "Blog", "{}", Some(&[work]), None).unwrap();
+        let aggregator = db.create_source("feed", "Aggregator", "{}", Some(&[news]), None).unwrap();
+        let (original, _) = db.upsert_item(
+            blog, "post-1", "Release 1.0", None, "https://blog.example.org/release/", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+        let (copy, _) = db.upsert_item(
+            aggregator, "agg-9", "Release 1.0", None, "https://blog.example.org/release", "post",
+            None, None, None, None, None, None, None, None,
+        ).unwrap();
+
+        // Each group lists the article under its own copy
+        let ids = |group: &str| db.get_items(None, Some(group), None, None, None, false).unwrap()
+            .into_iter().map(|item| item.id).collect::<Vec<_>>();
+        assert_eq!(ids("Work"), vec![original]);
+        assert_eq!(ids("News"), vec![copy]);
+        let filter = ItemFilter { group_filter: Some("News".to_string()), ..Default::default() };
+        assert_eq!(db.get_item_page(&filter, None, None, 10).unwrap().total, 1);
+        let results = db.search_items("release", None, Some("News"), None, None, 10).unwrap();
+        assert_eq!(results.iter().map(|result| result.item.id).collect::<Vec<_>>(), vec![copy]);
+
+        // So does a rule view on the second group
+        let rules = ViewRule::Group { names: vec!["News".to_string()] };
+        let id = db.create_custom_view("News", Some(&rules), ViewSort::Newest, None).unwrap();
+        let view = db.get_custom_view(id).unwrap();
+        assert_eq!(view.unread_count, 1);
+        assert_eq!(db.get_view_items(&view, None).unwrap().iter().map(|item| item.id).collect::<Vec<_>>(), vec![copy]);
+
+        // The inbox still lists it once
+        assert_eq!(db.get_items(None, None, None, None, None, false).unwrap().iter().map(|item| item.id).collect::<Vec<_>>(), vec![original]);
+    }
+
+    #[test]
+    fn test_story_clusters() {
+        let db = Database::new(":memory:").unwrap();
+        let wire = db.create_source("feed", "Wire", "{}", None, None).unwrap();
+        let daily = db.create_source("feed", "Daily", "{}", None, None).unwrap();
+        let story = "The project shipped version 2.0 today with a new plugin system, faster startup, \
+            a redesigned settings page and dozens of bug fixes contributed by the community over the last year.";
+        let item = |id: &str, title: &str, summary: String| IngestedItem {
+            external_id: id.to_string(),
+            title: title.to_string(),
+            summary: Some(summary),
+            url: format!("https://example.org/{}", id),
+            item_type: "post".to_string(),
+            occurred_at: None,
+            published_at: None,
+            image_url: None,
+            content_html: None,
+            author: None,
+            category: None,
+            comments: None,
+            comment_count: None,
+            thread_id: None,
+            attachments: vec![],
+        };
+        let store = |source_id: i64, item: IngestedItem| {
+            let fingerprint = fingerprint(&item.title, item.summary.as_deref(), None);
+            let (id, _) = db.upsert_items(source_id, &[ItemUpsert { item: &item, category: None, content_completeness: None, fingerprint }]).unwrap()[0];
+            db.cluster_items(&[id]).unwrap();
+            id
+        };
+        let first = store(wire, item("w1", "Project 2.0 released", story.to_string()));
+        let copy = store(daily, item("d1", "Project 2.0 released", format!("{} Read the full changelog.", story)));
+        let other = store(daily, item("d2", "Quarterly earnings", "Shares rose after the company reported revenue growth across all regions.".to_string()));
+
+        let items = db.get_items(None, None, None, None, None, true).unwrap();
+        assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![other, first]);
+        assert_eq!(items[1].cluster_members.iter().map(|m| (m.item_id, m.source_name.as_str())).collect::<Vec<_>>(), vec![(copy, "Daily")]);
+        assert!(items[0].cluster_members.is_empty());
+
+        // Ungrouped lists are unchanged
+        assert_eq!(db.get_items(None, None, None, None, None, false).unwrap().len(), 3);
+
+        // Reading the story from a grouped list reads the whole cluster
+        let unread = ItemFilter { state_filter: Some("unread".to_string()), group_similar: true, ..Default::default() };
+        assert_eq!(db.get_item_page(&unread, None, None, 10).unwrap().total, 2);
+        db.update_item_state(first, "read", true).unwrap();
+        assert_eq!(db.get_item(copy).unwrap().state, "read");
+        let page = db.get_item_page(&unread, None, None, 10).unwrap();
+        assert_eq!(page.items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![other]);
+
+        // With only its first item read, the story stays in the unread list through the other one
+        db.update_item_state(copy, "unread", false).unwrap();
+        let page = db.get_item_page(&unread, None, None, 10).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![other, copy]);
+        assert_eq!(page.items[1].cluster_members.iter().map(|m| m.item_id).collect::<Vec<_>>(), vec![first]);
+        let items = db.get_items(Some("unread"), None, None, None, None, true).unwrap();
+        assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![other, copy]);
+    }
+
+    #[test]
+    fn test_rule_based_views() {
+        let db = Database::new(":memory:").unwrap();
+        let infra = db.create_source("feed", "Infra", "{}", None, None).unwrap();
+        let news = db.create_source("feed", "News", "{}", None, None).unwrap();
+        let (first, _) = db.upsert_item(
+            infra, "1", "Alpha", None, "https://example.org/1", "post",
+            None, None, Some("alice"), None, None, None, None, None,
+        ).unwrap();
+        db.upsert_item(
+            infra, "2", "Beta", None, "https://example.org/2", "post",
+            None, None, Some("dependabot"), None, None, None, None, None,
+        ).unwrap();
+        db.upsert_item(
+            news, "3", "Gamma", None, "https://example.org/3", "post",
+            None, None, Some("carol"), None, None, None, None, None,
+        ).unwrap();
+
+        // Views from before rules existed get the equivalent rule on startup
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO custom_views (name, source_ids, created_at, updated_at) VALUES ('Legacy', ?1, 0, 0)",
+                params![format!("[{}]", infra)],
+            ).unwrap();
+            Database::migrate_legacy_view_rules(&conn).unwrap();
+        }
+        let legacy = db.get_all_custom_views().unwrap().pop().unwrap();
+        assert_eq!(legacy.rules, Some(ViewRule::Source { ids: vec![infra] }));
+        assert_eq!(db.get_view_items(&legacy, None).unwrap().len(), 2);
+
+        let rules = ViewRule::Or { rules: vec![
+            ViewRule::Not { rule: Box::new(ViewRule::Author { value: "bot".to_string() }) },
+            ViewRule::Group { names: vec!["Nothing".to_string()] },
+        ] };
+        let id = db.create_custom_view("Humans", Some(&rules), ViewSort::Title, None).unwrap();
+        let view = db.get_custom_view(id).unwrap();
+        assert_eq!(view.unread_count, 2);
+        let titles: Vec<String> = db.get_view_items(&view, None).unwrap().into_iter().map(|i| i.title).collect();
+        assert_eq!(titles, vec!["Alpha", "Gamma"]);
+
+        db.update_item_state(first, "read", false).unwrap();
+        db.refresh_view_unread_counts().unwrap();
+        assert_eq!(db.get_custom_view(id).unwrap().unread_count, 1);
+
+        // Items without a completeness are unknown, so negating the rule doesn't drop them
+        db.update_item_content_status(first, "extracted", Some("<p>Alpha</p>"), Some("full"), None).unwrap();
+        let unknown = ViewRule::Completeness { value: "unknown".to_string() };
+        let titles = |rules: ViewRule| {
+            let id = db.create_custom_view("Completeness", Some(&rules), ViewSort::Title, None).unwrap();
+            let view = db.get_custom_view(id).unwrap();
+            db.get_view_items(&view, None).unwrap().into_iter().map(|i| i.title).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(unknown.clone()), vec!["Beta", "Gamma"]);
+        assert_eq!(titles(ViewRule::Not { rule: Box::new(unknown) }), vec!["Alpha"]);
+        assert_eq!(titles(ViewRule::Not { rule: Box::new(ViewRule::Completeness { value: "full".to_string() }) }), vec!["Beta", "Gamma"]);
+    }
+
+    #[test]
+    fn test_item_attachments_round_trip() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("feed", "Podcast", r#"{"url":"https://example.org/feed"}"#, None, None).unwrap();
+        let audio = IngestedAttachment {
+            url: "https://cdn.example.org/1.mp3".to_string(),
+            mime_type: Some("audio/mpeg".to_string()),
+            length: Some(1024),
+            duration_seconds: Some(60),
+            explicit: Some(false),
+            ..Default::default()
+        };
+        let mut episode = IngestedItem {
+            external_id: "ep-1".to_string(),
+            title: "Episode 1".to_string(),
+            summary: None,
+            url: "https://example.org/1".to_string(),
+            item_type: "podcast_episode".to_string(),
+            occurred_at: Some(1714557600),
+            published_at: None,
+            image_url: None,
+            content_html: None,
+            author: None,
+            category: None,
+            comments: None,
+            comment_count: None,
+            thread_id: None,
+            attachments: vec![audio.clone(), audio],
+        };
+        let upsert = |item: &IngestedItem| {
+            db.upsert_items(source_id, &[ItemUpsert { item, category: None, content_completeness: None, fingerprint: None }]).unwrap()[0]
+        };
+        let events = || -> i64 {
+            db.conn.lock().unwrap().query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0)).unwrap()
+        };
+
+        let (item_id, outcome) = upsert(&episode);
+        assert_eq!(outcome, UpsertOutcome::Inserted);
+        assert_eq!(upsert(&episode), (item_id, UpsertOutcome::Unchanged));
+        assert_eq!(events(), 0); // Neither new nor unchanged items add history
+
+        let item = db.get_item(item_id).unwrap();
+        assert_eq!(item.attachments.len(), 1);
+        assert_eq!(item.attachments[0].duration_seconds, Some(60));
+        assert_eq!(item.attachments[0].explicit, Some(false));
+
+        let items = db.get_items(None, None, None, None, None, false).unwrap();
+        assert_eq!(items[0].attachments.len(), 1);
+
+        // Attachments follow the feed even when the item itself is unchanged
+        episode.attachments.clear();
+        assert_eq!(upsert(&episode), (item_id, UpsertOutcome::Unchanged));
+        assert!(db.get_item(item_id).unwrap().attachments.is_empty());
+
+        episode.title = "Episode 1 (remastered)".to_string();
+        assert_eq!(upsert(&episode), (item_id, UpsertOutcome::Updated));
+        assert_eq!(events(), 1);
+    }
+
+    #[test]
+    fn test_item_history() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("github", "CI", "{}", None, None).unwrap();
+        let run = |title: &str, summary: &str| db.upsert_item(
+            source_id, "repo/actions/runs/1", title, Some(summary), "https://github.com/repo/actions/runs/1", "action",
+            None, None, None, None, None, None, None, None,
+        ).unwrap().0;
+        let item_id = run("Build - in_progress (unknown)", "Status: in_progress, Conclusion: unknown");
+        run("Build - in_progress (unknown)", "Status: in_progress, Conclusion: unknown");
+        run("Build - completed (success)", "Status: completed, Conclusion: success");
+        db.update_item_state(item_id, "read", false).unwrap();
+        db.update_item_state(item_id, "read", false).unwrap();
+
+        let mut kinds: Vec<String> = db.get_item_history(item_id).unwrap().into_iter().map(|e| e.event_type).collect();
+        kinds.sort();
+        assert_eq!(kinds, vec!["state_changed", "status_changed", "title_changed"]);
+        let status = db.get_item_history(item_id).unwrap().into_iter().find(|e| e.event_type == "status_changed").unwrap();
+        assert_eq!(status.payload["new"], "Status: completed, Conclusion: success");
+
+        // Legacy per-poll rows are dropped by compaction
+        for occurred_at in [300, 100, 200] {
+            db.conn.lock().unwrap().execute(
+                "INSERT INTO events (item_id, event_type, payload_json, occurred_at) VALUES (?1, 'update', ?2, 0)",
+                params![item_id, format!(r#"{{"occurred_at":{}}}"#, occurred_at)],
+            ).unwrap();
+        }
+        assert_eq!(db.get_item_history(item_id).unwrap().len(), 3);
+        assert_eq!(db.compact_item_history().unwrap(), 3);
+        assert_eq!(db.get_item_history(item_id).unwrap().len(), 3);
+        // ...after handing their dates to the item
+        let item = db.get_item(item_id).unwrap();
+        assert_eq!((item.published_at, item.source_updated_at), (Some(100), Some(300)));
     }
 }
 
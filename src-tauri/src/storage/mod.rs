@@ -1,5 +1,8 @@
 pub mod db;
 pub mod models;
+pub mod query;
+pub mod rules;
+pub mod search;
 
 pub use db::*;
 // Models are accessed via models:: prefix, not re-exported
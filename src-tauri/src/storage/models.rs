@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use rusqlite::Row;
+use super::rules::{ViewRule, ViewSort};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Source {
@@ -11,6 +12,113 @@ pub struct Source {
     pub last_synced_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
+    // Feed-level metadata, refreshed on every poll of feed sources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_image_url: Option<String>,
+    // Favicon cached in the app data dir (see get_source_favicons)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon_fetched_at: Option<i64>,
+    // Health, updated after every sync attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error_kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error_at: Option<i64>,
+    #[serde(default)]
+    pub consecutive_failures: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_sync_duration_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff_until: Option<i64>, // Background polling skips the source until then
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>, // Set when the source was disabled automatically
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_interval_secs: Option<i64>, // Learned poll interval, used when adaptive polling is on
+}
+
+/// A failed sync attempt, kept in the source's error history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceError {
+    pub id: i64,
+    pub source_id: i64,
+    pub kind: String,
+    pub message: String,
+    pub occurred_at: i64,
+}
+
+/// One sync attempt of a source and what it picked up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRun {
+    pub id: i64,
+    pub source_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
+    pub trigger: String, // "startup", "background" or "manual"
+    pub started_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<i64>, // None while the run is in progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+    pub http_requests: i64,
+    pub items_new: i64,
+    pub items_updated: i64,
+    pub items_unchanged: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Counters collected while a sync runs, stored by `finish_sync_run`.
+#[derive(Debug, Clone, Default)]
+pub struct SyncRunStats {
+    pub duration_ms: i64,
+    pub http_requests: i64,
+    pub items_new: i64,
+    pub items_updated: i64,
+    pub items_unchanged: i64,
+}
+
+impl SyncRun {
+    pub fn from_row(row: &Row) -> rusqlite::Result<SyncRun> {
+        Ok(SyncRun {
+            id: row.get(0)?,
+            source_id: row.get(1)?,
+            source_name: row.get(2)?,
+            trigger: row.get(3)?,
+            started_at: row.get(4)?,
+            finished_at: row.get(5)?,
+            duration_ms: row.get(6)?,
+            http_requests: row.get(7)?,
+            items_new: row.get(8)?,
+            items_updated: row.get(9)?,
+            items_unchanged: row.get(10)?,
+            error: row.get(11)?,
+        })
+    }
+}
+
+impl SourceError {
+    pub fn from_row(row: &Row) -> rusqlite::Result<SourceError> {
+        Ok(SourceError {
+            id: row.get(0)?,
+            source_id: row.get(1)?,
+            kind: row.get(2)?,
+            message: row.get(3)?,
+            occurred_at: row.get(4)?,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,9 +131,13 @@ pub struct Item {
     pub url: String,
     pub item_type: String,
     pub state: String,
-    pub created_at: i64,
+    pub created_at: i64, // When we first stored it
     pub updated_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<i64>, // The source's publication date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_updated_at: Option<i64>, // When the source last changed it
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub image_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content_html: Option<String>,
@@ -36,6 +148,8 @@ pub struct Item {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comments: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub source_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_group: Option<String>,
@@ -50,6 +164,75 @@ pub struct Item {
     pub extraction_attempted_at: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extraction_failed_reason: Option<String>,
+    // Enclosures (podcast audio, video, files), loaded separately from the attachments table
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    // Copies of this item from other sources (same canonical URL), listed in the inbox entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<ItemDuplicate>,
+    // Other items telling the same story, filled in when a list groups similar items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cluster_members: Vec<ClusterMember>,
+}
+
+/// An item clustered with another as the same story (near-identical title and text),
+/// listed under the item that represents the cluster.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClusterMember {
+    pub item_id: i64,
+    pub source_id: i64,
+    pub source_name: String,
+    pub title: String,
+    pub state: String,
+}
+
+/// Another stored copy of an item: the same article under the same canonical URL,
+/// usually from a different source.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemDuplicate {
+    pub item_id: i64,
+    pub source_id: i64,
+    pub source_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub item_id: i64,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<i64>, // bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explicit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    pub created_at: i64,
+}
+
+impl Attachment {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Attachment> {
+        Ok(Attachment {
+            id: row.get(0)?,
+            item_id: row.get(1)?,
+            url: row.get(2)?,
+            mime_type: row.get(3)?,
+            length: row.get(4)?,
+            duration_seconds: row.get(5)?,
+            episode: row.get(6)?,
+            season: row.get(7)?,
+            explicit: row.get(8)?,
+            image_url: row.get(9)?,
+            created_at: row.get(10)?,
+        })
+    }
 }
 
 impl Source {
@@ -63,6 +246,22 @@ impl Source {
             last_synced_at: row.get(5)?,
             created_at: row.get(6)?,
             updated_at: row.get(7)?,
+            feed_title: row.get(8)?,
+            feed_description: row.get(9)?,
+            site_url: row.get(10)?,
+            feed_language: row.get(11)?,
+            feed_image_url: row.get(12)?,
+            favicon_path: row.get(13)?,
+            favicon_fetched_at: row.get(14)?,
+            last_error: row.get(15)?,
+            last_error_kind: row.get(16)?,
+            last_error_at: row.get(17)?,
+            consecutive_failures: row.get(18)?,
+            last_success_at: row.get(19)?,
+            avg_sync_duration_ms: row.get(20)?,
+            backoff_until: row.get(21)?,
+            disabled_reason: row.get(22)?,
+            adaptive_interval_secs: row.get(23)?,
         })
     }
 }
@@ -85,6 +284,9 @@ impl Item {
             author: row.get(12).ok(),
             category: row.get(13).ok(),
             comments: row.get(14).ok(),
+            comment_count: row.get(20).ok(),
+            published_at: row.get(21)?,
+            source_updated_at: row.get(22)?,
             source_name: None,
             source_group: None,
             content_status: row.get(15).ok(),
@@ -92,6 +294,9 @@ impl Item {
             content_completeness: row.get(17).ok(),
             extraction_attempted_at: row.get(18).ok(),
             extraction_failed_reason: row.get(19).ok(),
+            attachments: Vec::new(),
+            duplicates: Vec::new(),
+            cluster_members: Vec::new(),
         })
     }
     
@@ -112,6 +317,9 @@ impl Item {
             author: row.get(12).ok(),
             category: row.get(13).ok(),
             comments: row.get(14).ok(),
+            comment_count: row.get(22).ok(),
+            published_at: row.get(23)?,
+            source_updated_at: row.get(24)?,
             source_name: row.get(15).ok(),
             source_group: row.get(16).ok(),
             content_status: row.get(17).ok(),
@@ -119,10 +327,147 @@ impl Item {
             content_completeness: row.get(19).ok(),
             extraction_attempted_at: row.get(20).ok(),
             extraction_failed_reason: row.get(21).ok(),
+            attachments: Vec::new(),
+            duplicates: Vec::new(),
+            cluster_members: Vec::new(),
+        })
+    }
+}
+
+/// An item matched by `search_items`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub item: Item,
+    pub rank: f64, // BM25, lower is a better match
+    pub title_highlight: String, // Escaped HTML with <mark> around matches
+    pub snippet: String, // Best matching passage, escaped HTML with <mark> around matches
+}
+
+/// A recorded change to an item: `title_changed`, `summary_changed`, `status_changed` and
+/// `state_changed` carry `{"old", "new"}`; `content_changed` carries `{"old_length", "new_length"}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemEvent {
+    pub id: i64,
+    pub item_id: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub occurred_at: i64,
+}
+
+impl ItemEvent {
+    pub fn from_row(row: &Row) -> rusqlite::Result<ItemEvent> {
+        let payload: Option<String> = row.get(3)?;
+        Ok(ItemEvent {
+            id: row.get(0)?,
+            item_id: row.get(1)?,
+            event_type: row.get(2)?,
+            payload: payload.and_then(|p| serde_json::from_str(&p).ok()).unwrap_or(serde_json::Value::Null),
+            occurred_at: row.get(4)?,
         })
     }
 }
 
+/// An item as shown in a list: no HTML bodies, which `get_item_with_content` loads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemSummary {
+    pub id: i64,
+    pub source_id: i64,
+    pub title: String,
+    pub summary: Option<String>,
+    pub url: String,
+    pub item_type: String,
+    pub state: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_updated_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>, // JSON array string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<i64>,
+    pub source_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_group: Option<String>, // Comma-separated group names
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_completeness: Option<String>,
+    pub has_attachments: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<ItemDuplicate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cluster_members: Vec<ClusterMember>,
+}
+
+impl ItemSummary {
+    pub fn from_row(row: &Row) -> rusqlite::Result<ItemSummary> {
+        Ok(ItemSummary {
+            id: row.get(0)?,
+            source_id: row.get(1)?,
+            title: row.get(2)?,
+            summary: row.get(3)?,
+            url: row.get(4)?,
+            item_type: row.get(5)?,
+            state: row.get(6)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+            image_url: row.get(9)?,
+            author: row.get(10)?,
+            category: row.get(11)?,
+            comment_count: row.get(12)?,
+            source_name: row.get(13)?,
+            source_group: None,
+            content_status: row.get(14)?,
+            content_completeness: row.get(15)?,
+            has_attachments: row.get(16)?,
+            published_at: row.get(17)?,
+            source_updated_at: row.get(18)?,
+            duplicates: Vec::new(),
+            cluster_members: Vec::new(),
+        })
+    }
+}
+
+/// Which items a list covers: the inbox filters, or a custom view when `view_id` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ItemFilter {
+    pub state_filter: Option<String>,
+    pub group_filter: Option<String>,
+    pub source_ids: Option<Vec<i64>>,
+    pub group_names: Option<Vec<String>>,
+    pub query: Option<String>, // Inbox query
+    pub view_id: Option<i64>,
+    #[serde(default)]
+    pub sort: Option<ViewSort>, // Inbox order, newest first by default; views use their own
+    #[serde(default)]
+    pub group_similar: bool, // One entry per cluster of similar stories, listing the rest
+}
+
+/// Where the next page starts: the sort key of the last item on this one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemCursor {
+    pub created_at: i64,
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>, // Title or source name when sorting by those
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<i64>, // Publication date (or created_at) when sorting by it
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemPage {
+    pub items: Vec<ItemSummary>,
+    pub total: i64, // Items matching the filter, across all pages
+    pub next_cursor: Option<ItemCursor>, // None on the last page
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Group {
     pub id: i64,
@@ -147,22 +492,28 @@ pub struct CustomView {
     pub id: i64,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub source_ids: Option<String>, // JSON array of source IDs
+    pub rules: Option<ViewRule>, // None shows every item
+    pub sort_order: ViewSort,
+    pub unread_count: i64, // Refreshed after syncs and state changes
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_names: Option<String>, // JSON array of group names
+    pub query: Option<String>, // Inbox query, e.g. "is:unread -label:bot"
     pub created_at: i64,
     pub updated_at: i64,
 }
 
 impl CustomView {
     pub fn from_row(row: &Row) -> rusqlite::Result<CustomView> {
+        let rules: Option<String> = row.get(2)?;
+        let sort_order: String = row.get(3)?;
         Ok(CustomView {
             id: row.get(0)?,
             name: row.get(1)?,
-            source_ids: row.get(2)?,
-            group_names: row.get(3)?,
-            created_at: row.get(4)?,
-            updated_at: row.get(5)?,
+            rules: rules.and_then(|r| serde_json::from_str(&r).ok()),
+            sort_order: ViewSort::parse(&sort_order).unwrap_or_default(),
+            unread_count: row.get(4)?,
+            query: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
         })
     }
 }
@@ -0,0 +1,403 @@
+use super::search::quote_term;
+use chrono::{NaiveDate, TimeZone, Utc};
+use rusqlite::types::Value;
+use serde::Serialize;
+
+/// Fields accepted before a colon, for error messages and suggestions.
+const FIELDS: &[&str] = &["is", "source", "group", "author", "type", "label", "category", "after", "before", "has"];
+const STATES: &[&str] = &["unread", "read", "archived", "deleted"];
+const HAS_VALUES: &[&str] = &["attachment", "comments", "extracted"];
+
+/// Why a query could not be parsed, with the character position it refers to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at character {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    State(String),
+    Source(String),
+    Group(String),
+    Author(String),
+    ItemType(String),
+    Label(String),
+    After(i64),
+    Before(i64),
+    Has(String),
+    Text { text: String, phrase: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    filter: Filter,
+    negated: bool,
+}
+
+/// A parsed inbox query such as
+/// `is:unread source:"Rust Blog" author:alice type:pr after:2024-05-01 -label:bot kubernetes`.
+///
+/// All terms must match; `-` negates a term. Free text (words and "quoted phrases")
+/// goes to the full-text index, with words matched as prefixes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemQuery {
+    terms: Vec<Term>,
+}
+
+/// One term as typed: `-key:"value"`, `word` or `"phrase"`.
+struct Token {
+    position: usize,
+    negated: bool,
+    key: Option<String>,
+    value: String,
+    quoted: bool,
+}
+
+/// Splits the input into tokens.
+struct Lexer<'a> {
+    input: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Lexer { input, chars: input.char_indices().collect(), pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|(_, c)| *c)
+    }
+
+    fn byte_offset(&self) -> usize {
+        self.chars.get(self.pos).map(|(i, _)| *i).unwrap_or(self.input.len())
+    }
+
+    fn error(&self, message: String, position: usize) -> QueryError {
+        QueryError { message, position }
+    }
+
+    /// Reads a quoted string; the opening quote is at the current position.
+    fn quoted(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        self.pos += 1;
+        let from = self.byte_offset();
+        while let Some(c) = self.peek() {
+            if c == '"' {
+                let value = self.input[from..self.byte_offset()].to_string();
+                self.pos += 1;
+                return Ok(value);
+            }
+            self.pos += 1;
+        }
+        Err(self.error("Unterminated quote; add a closing \"".to_string(), start))
+    }
+
+    /// Reads up to the next whitespace, stopping after a `key:` if `stop_at_colon`.
+    fn word(&mut self, stop_at_colon: bool) -> String {
+        let from = self.byte_offset();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            self.pos += 1;
+            if c == ':' && stop_at_colon {
+                break;
+            }
+        }
+        self.input[from..self.byte_offset()].to_string()
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, QueryError> {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        let negated = match self.peek() {
+            None => return Ok(None),
+            Some('-') => {
+                self.pos += 1;
+                true
+            }
+            Some(_) => false,
+        };
+
+        match self.peek() {
+            None => Err(self.error("Nothing to exclude after -".to_string(), start)),
+            Some(c) if c.is_whitespace() => Err(self.error("Nothing to exclude after -".to_string(), start)),
+            Some('"') => {
+                let value = self.quoted()?;
+                Ok(Some(Token { position: start, negated, key: None, value, quoted: true }))
+            }
+            Some(_) => {
+                let word = self.word(true);
+                let key = word.strip_suffix(':')
+                    .filter(|key| !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+                match key {
+                    // A URL like https://example.org is text, not a field
+                    Some(key) if !matches!(self.peek(), Some('/')) => {
+                        let key = key.to_lowercase();
+                        let (value, quoted) = match self.peek() {
+                            Some('"') => (self.quoted()?, true),
+                            Some(c) if !c.is_whitespace() => (self.word(false), false),
+                            _ => return Err(self.error(format!("Missing value after {}:", key), start)),
+                        };
+                        Ok(Some(Token { position: start, negated, key: Some(key), value, quoted }))
+                    }
+                    _ => {
+                        let value = word + &self.word(false);
+                        Ok(Some(Token { position: start, negated, key: None, value, quoted: false }))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Closest known value within two edits, for "did you mean" hints.
+fn suggest<'a>(value: &str, options: &[&'a str]) -> Option<&'a str> {
+    fn distance(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut previous = row[0];
+            row[0] = i + 1;
+            for (j, cb) in b.iter().enumerate() {
+                let current = row[j + 1];
+                row[j + 1] = if ca == *cb { previous } else { 1 + previous.min(row[j]).min(row[j + 1]) };
+                previous = current;
+            }
+        }
+        row[b.len()]
+    }
+    options.iter()
+        .map(|option| (distance(value, option), *option))
+        .filter(|(d, _)| *d <= 2)
+        .min()
+        .map(|(_, option)| option)
+}
+
+fn one_of(field: &str, value: &str, options: &[&str], position: usize) -> Result<String, QueryError> {
+    let value = value.to_lowercase();
+    if options.contains(&value.as_str()) {
+        return Ok(value);
+    }
+    let hint = suggest(&value, options).map(|s| format!(" Did you mean {}:{}?", field, s)).unwrap_or_default();
+    Err(QueryError {
+        message: format!("Unknown value for {}: \"{}\". Expected one of: {}.{}", field, value, options.join(", "), hint),
+        position,
+    })
+}
+
+/// Parses `YYYY-MM-DD` (UTC midnight) or a relative age like `7d`, `12h` or `2w` (ago).
+fn parse_date(field: &str, value: &str, position: usize) -> Result<i64, QueryError> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        return Ok(Utc.from_utc_datetime(&midnight).timestamp());
+    }
+    let unit_secs = match value.chars().last() {
+        Some('h') => Some(3600),
+        Some('d') => Some(86400),
+        Some('w') => Some(7 * 86400),
+        _ => None,
+    };
+    let amount = value.get(..value.len().saturating_sub(1)).and_then(|n| n.parse::<i64>().ok());
+    match (amount, unit_secs) {
+        (Some(amount), Some(unit_secs)) => amount.checked_mul(unit_secs)
+            .and_then(|age| Utc::now().timestamp().checked_sub(age))
+            .ok_or_else(|| QueryError {
+                message: format!("Date for {} is out of range: \"{}\"", field, value),
+                position,
+            }),
+        _ => Err(QueryError {
+            message: format!("Invalid date for {}: \"{}\". Use YYYY-MM-DD or an age like 7d, 12h or 2w", field, value),
+            position,
+        }),
+    }
+}
+
+impl ItemQuery {
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let mut lexer = Lexer::new(input);
+        let mut terms = Vec::new();
+
+        while let Some(Token { position, negated, key, value, quoted }) = lexer.next_token()? {
+            let filter = match key.as_deref() {
+                None => {
+                    // Lone punctuation has nothing to match in the full-text index
+                    if !value.chars().any(char::is_alphanumeric) {
+                        continue;
+                    }
+                    Filter::Text { text: value, phrase: quoted }
+                }
+                Some(_) if value.trim().is_empty() => {
+                    return Err(QueryError { message: format!("Missing value after {}:", key.unwrap_or_default()), position });
+                }
+                Some("is") => Filter::State(one_of("is", &value, STATES, position)?),
+                Some("has") => Filter::Has(one_of("has", &value, HAS_VALUES, position)?),
+                Some("source") => Filter::Source(value),
+                Some("group") => Filter::Group(value),
+                Some("author") => Filter::Author(value),
+                Some("type") => Filter::ItemType(match value.to_lowercase().as_str() {
+                    "pull_request" | "pullrequest" => "pr".to_string(),
+                    "article" | "feed" => "rss".to_string(),
+                    other => other.to_string(),
+                }),
+                Some("label") | Some("category") => Filter::Label(value),
+                Some("after") => Filter::After(parse_date("after", &value, position)?),
+                Some("before") => Filter::Before(parse_date("before", &value, position)?),
+                Some(key) => {
+                    let hint = suggest(key, FIELDS).map(|s| format!(" Did you mean {}:?", s)).unwrap_or_default();
+                    return Err(QueryError {
+                        message: format!("Unknown filter \"{}:\". Known filters: {}.{}", key, FIELDS.join(", "), hint),
+                        position,
+                    });
+                }
+            };
+            terms.push(Term { filter, negated });
+        }
+
+        Ok(ItemQuery { terms })
+    }
+
+    /// Whether the query asks for deleted items, which are hidden otherwise.
+    pub fn includes_deleted(&self) -> bool {
+        self.terms.iter().any(|t| !t.negated && t.filter == Filter::State("deleted".to_string()))
+    }
+
+    /// WHERE conditions over `items i` joined with `sources s`, and their parameters.
+    pub fn to_sql(&self) -> (Vec<String>, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        let text = |s: &str| Value::Text(s.to_string());
+        let contains = |s: &str| Value::Text(format!("%{}%", s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+
+        // Positive free text becomes a single full-text match; excluded words one each
+        let matched: Vec<String> = self.terms.iter()
+            .filter(|t| !t.negated)
+            .filter_map(|t| match &t.filter {
+                Filter::Text { text, phrase } => Some(quote_term(text, !phrase)),
+                _ => None,
+            })
+            .collect();
+        if !matched.is_empty() {
+            conditions.push("i.id IN (SELECT rowid FROM items_fts WHERE items_fts MATCH ?)".to_string());
+            params.push(Value::Text(matched.join(" ")));
+        }
+
+        for term in &self.terms {
+            let condition = match &term.filter {
+                Filter::Text { text: t, phrase } => {
+                    if !term.negated {
+                        continue;
+                    }
+                    params.push(Value::Text(quote_term(t, !phrase)));
+                    "i.id IN (SELECT rowid FROM items_fts WHERE items_fts MATCH ?)"
+                }
+                Filter::State(state) => {
+                    params.push(text(state));
+                    "i.state = ?"
+                }
+                Filter::Source(name) => match name.parse::<i64>() {
+                    Ok(id) => {
+                        params.push(Value::Integer(id));
+                        "s.id = ?"
+                    }
+                    Err(_) => {
+                        params.push(text(name));
+                        "s.name = ? COLLATE NOCASE"
+                    }
+                },
+                Filter::Group(name) => {
+                    params.push(text(name));
+                    "EXISTS (SELECT 1 FROM source_groups qsg INNER JOIN groups qg ON qsg.group_id = qg.id WHERE qsg.source_id = i.source_id AND qg.name = ? COLLATE NOCASE)"
+                }
+                Filter::Author(author) => {
+                    params.push(contains(author));
+                    "COALESCE(i.author, '') LIKE ? ESCAPE '\\'"
+                }
+                Filter::ItemType(item_type) => {
+                    params.push(text(item_type));
+                    "i.item_type = ?"
+                }
+                Filter::Label(label) => {
+                    params.push(text(label));
+                    "EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid(i.category) THEN i.category ELSE '[]' END) WHERE value = ? COLLATE NOCASE)"
+                }
+                Filter::After(timestamp) => {
+                    params.push(Value::Integer(*timestamp));
+                    "COALESCE(i.published_at, i.created_at) >= ?"
+                }
+                Filter::Before(timestamp) => {
+                    params.push(Value::Integer(*timestamp));
+                    "COALESCE(i.published_at, i.created_at) < ?"
+                }
+                Filter::Has(what) => match what.as_str() {
+                    "attachment" => "EXISTS (SELECT 1 FROM attachments qa WHERE qa.item_id = i.id)",
+                    "comments" => "COALESCE(i.comment_count, 0) > 0",
+                    _ => "i.content_status = 'extracted'",
+                },
+            };
+            conditions.push(if term.negated {
+                format!("NOT ({})", condition)
+            } else {
+                condition.to_string()
+            });
+        }
+
+        (conditions, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_query() {
+        let query = ItemQuery::parse(r#"is:unread source:"Rust Blog" author:alice type:pr after:2024-05-01 -label:bot kubernetes"#).unwrap();
+        let (conditions, params) = query.to_sql();
+        assert_eq!(conditions.len(), 7);
+        assert_eq!(conditions[0], "i.id IN (SELECT rowid FROM items_fts WHERE items_fts MATCH ?)");
+        assert_eq!(params[0], Value::Text("\"kubernetes\"*".to_string()));
+        assert_eq!(params[2], Value::Text("Rust Blog".to_string()));
+        assert_eq!(params[5], Value::Integer(1714521600));
+        assert!(conditions[6].starts_with("NOT (EXISTS"));
+        assert!(!query.includes_deleted());
+    }
+
+    #[test]
+    fn test_text_terms() {
+        let query = ItemQuery::parse(r#""release notes" -draft https://example.org/a"#).unwrap();
+        let (conditions, params) = query.to_sql();
+        assert_eq!(params[0], Value::Text("\"release notes\" \"https://example.org/a\"*".to_string()));
+        assert_eq!(conditions[1], "NOT (i.id IN (SELECT rowid FROM items_fts WHERE items_fts MATCH ?))");
+        assert_eq!(ItemQuery::parse("  - ").unwrap_err().position, 2);
+        assert!(ItemQuery::parse("  ").unwrap().to_sql().0.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = ItemQuery::parse("is:unread autor:alice").unwrap_err();
+        assert_eq!(error.position, 10);
+        assert!(error.message.contains("Did you mean author:?"));
+
+        let error = ItemQuery::parse("is:unred").unwrap_err();
+        assert!(error.message.contains("Did you mean is:unread?"));
+
+        assert!(ItemQuery::parse("source:\"Rust Blog").unwrap_err().message.starts_with("Unterminated quote"));
+        assert!(ItemQuery::parse("author: alice").unwrap_err().message.starts_with("Missing value after author:"));
+        assert!(ItemQuery::parse("after:yesterday").unwrap_err().message.starts_with("Invalid date"));
+        assert!(ItemQuery::parse("after:999999999999999999w").unwrap_err().message.starts_with("Date for after is out of range"));
+        assert!(ItemQuery::parse("rust - go").unwrap_err().message.starts_with("Nothing to exclude"));
+    }
+}
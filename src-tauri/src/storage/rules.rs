@@ -0,0 +1,294 @@
+use super::models::{ItemCursor, ItemSummary};
+use super::search;
+use chrono::Utc;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+const STATES: &[&str] = &["unread", "read", "archived"];
+const COMPLETENESS: &[&str] = &["full", "partial", "unknown"];
+/// Deep enough for any rule built in the UI, shallow enough to keep the SQL small.
+const MAX_DEPTH: usize = 16;
+
+/// A custom view's filter: conditions combined with AND, OR and NOT, evaluated in SQL.
+///
+/// Stored as JSON, e.g.
+/// `{"type":"and","rules":[{"type":"state","value":"unread"},{"type":"not","rule":{"type":"author","value":"dependabot"}}]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ViewRule {
+    And { rules: Vec<ViewRule> },
+    Or { rules: Vec<ViewRule> },
+    Not { rule: Box<ViewRule> },
+    State { value: String },
+    ItemType { value: String },
+    /// Author contains the value, ignoring case
+    Author { value: String },
+    /// Item has this category or label
+    Category { value: String },
+    /// Full-text match, like the search box
+    Keyword { value: String },
+    /// Created within the last `max_days` days
+    Age { max_days: i64 },
+    Completeness { value: String },
+    Source { ids: Vec<i64> },
+    Group { names: Vec<String> },
+}
+
+impl ViewRule {
+    /// The rule equivalent to a view's old `source_ids` / `group_names` lists, which
+    /// applied both when both were set. `None` if neither was set.
+    pub fn from_legacy(source_ids: Option<&str>, group_names: Option<&str>) -> Option<ViewRule> {
+        let source_ids: Vec<i64> = source_ids.and_then(|s| serde_json::from_str(s).ok()).unwrap_or_default();
+        let group_names: Vec<String> = group_names.and_then(|s| serde_json::from_str(s).ok()).unwrap_or_default();
+
+        let mut rules = Vec::new();
+        if !source_ids.is_empty() {
+            rules.push(ViewRule::Source { ids: source_ids });
+        }
+        if !group_names.is_empty() {
+            rules.push(ViewRule::Group { names: group_names });
+        }
+        match rules.len() {
+            0 => None,
+            1 => rules.pop(),
+            _ => Some(ViewRule::And { rules }),
+        }
+    }
+
+    /// Checks values the UI can't constrain, like states and ages.
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_at(0)
+    }
+
+    fn validate_at(&self, depth: usize) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("Rules are nested more than {} levels deep", MAX_DEPTH));
+        }
+        match self {
+            ViewRule::And { rules } | ViewRule::Or { rules } => {
+                rules.iter().try_for_each(|rule| rule.validate_at(depth + 1))
+            }
+            ViewRule::Not { rule } => rule.validate_at(depth + 1),
+            ViewRule::State { value } if !STATES.contains(&value.as_str()) => {
+                Err(format!("Unknown state \"{}\". Expected one of: {}", value, STATES.join(", ")))
+            }
+            ViewRule::Completeness { value } if !COMPLETENESS.contains(&value.as_str()) => {
+                Err(format!("Unknown completeness \"{}\". Expected one of: {}", value, COMPLETENESS.join(", ")))
+            }
+            ViewRule::Age { max_days } if *max_days <= 0 => {
+                Err("Age must be at least one day".to_string())
+            }
+            ViewRule::ItemType { value } | ViewRule::Author { value } | ViewRule::Category { value } | ViewRule::Keyword { value }
+                if value.trim().is_empty() =>
+            {
+                Err("Rule value can't be empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// A WHERE condition over `items i` joined with `sources s`, and its parameters.
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut params = Vec::new();
+        let sql = self.write_sql(&mut params, Utc::now().timestamp());
+        (sql, params)
+    }
+
+    fn write_sql(&self, params: &mut Vec<Value>, now: i64) -> String {
+        let placeholders = |n: usize| vec!["?"; n].join(", ");
+        match self {
+            // An empty AND matches everything, an empty OR nothing
+            ViewRule::And { rules } if rules.is_empty() => "1".to_string(),
+            ViewRule::Or { rules } if rules.is_empty() => "0".to_string(),
+            ViewRule::And { rules } | ViewRule::Or { rules } => {
+                let joiner = if matches!(self, ViewRule::And { .. }) { " AND " } else { " OR " };
+                let parts: Vec<String> = rules.iter().map(|rule| rule.write_sql(params, now)).collect();
+                format!("({})", parts.join(joiner))
+            }
+            ViewRule::Not { rule } => format!("NOT {}", rule.write_sql(params, now)),
+            ViewRule::State { value } => {
+                params.push(Value::Text(value.clone()));
+                "(i.state = ?)".to_string()
+            }
+            ViewRule::ItemType { value } => {
+                params.push(Value::Text(value.clone()));
+                "(i.item_type = ?)".to_string()
+            }
+            ViewRule::Author { value } => {
+                let escaped = value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+                params.push(Value::Text(format!("%{}%", escaped)));
+                "(COALESCE(i.author, '') LIKE ? ESCAPE '\\')".to_string()
+            }
+            ViewRule::Category { value } => {
+                params.push(Value::Text(value.clone()));
+                "EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid(i.category) THEN i.category ELSE '[]' END) WHERE value = ? COLLATE NOCASE)".to_string()
+            }
+            ViewRule::Keyword { value } => match search::fts_query(value) {
+                Some(fts_query) => {
+                    params.push(Value::Text(fts_query));
+                    "(i.id IN (SELECT rowid FROM items_fts WHERE items_fts MATCH ?))".to_string()
+                }
+                None => "1".to_string(),
+            },
+            ViewRule::Age { max_days } => {
+                params.push(Value::Integer(now - max_days * 86400));
+                "(COALESCE(i.published_at, i.created_at) >= ?)".to_string()
+            }
+            // Items never assessed have no completeness and count as unknown
+            ViewRule::Completeness { value } => {
+                params.push(Value::Text(value.clone()));
+                "(IFNULL(i.content_completeness, 'unknown') = ?)".to_string()
+            }
+            ViewRule::Source { ids } if ids.is_empty() => "0".to_string(),
+            ViewRule::Source { ids } => {
+                params.extend(ids.iter().map(|id| Value::Integer(*id)));
+                format!("(i.source_id IN ({}))", placeholders(ids.len()))
+            }
+            ViewRule::Group { names } if names.is_empty() => "0".to_string(),
+            ViewRule::Group { names } => {
+                params.extend(names.iter().map(|name| Value::Text(name.clone())));
+                format!(
+                    "EXISTS (SELECT 1 FROM source_groups rsg INNER JOIN groups rg ON rsg.group_id = rg.id WHERE rsg.source_id = i.source_id AND rg.name IN ({}))",
+                    placeholders(names.len())
+                )
+            }
+        }
+    }
+}
+
+/// How a view orders its items. Newest and oldest go by when items arrived here;
+/// published by the source's publication date, falling back to arrival.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewSort {
+    #[default]
+    Newest,
+    Oldest,
+    Published,
+    Title,
+    Source,
+}
+
+impl ViewSort {
+    pub fn parse(value: &str) -> Option<ViewSort> {
+        match value {
+            "newest" => Some(ViewSort::Newest),
+            "oldest" => Some(ViewSort::Oldest),
+            "published" => Some(ViewSort::Published),
+            "title" => Some(ViewSort::Title),
+            "source" => Some(ViewSort::Source),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewSort::Newest => "newest",
+            ViewSort::Oldest => "oldest",
+            ViewSort::Published => "published",
+            ViewSort::Title => "title",
+            ViewSort::Source => "source",
+        }
+    }
+
+    /// ORDER BY clause; ties fall back to newest first so paging stays stable.
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            ViewSort::Newest => "i.created_at DESC, i.id DESC",
+            ViewSort::Oldest => "i.created_at ASC, i.id ASC",
+            ViewSort::Published => "COALESCE(i.published_at, i.created_at) DESC, i.id DESC",
+            ViewSort::Title => "i.title COLLATE NOCASE ASC, i.created_at DESC, i.id DESC",
+            ViewSort::Source => "s.name COLLATE NOCASE ASC, i.created_at DESC, i.id DESC",
+        }
+    }
+
+    /// Keyset condition for items after `cursor` in this order, so pages don't need OFFSET.
+    pub fn after(&self, cursor: &ItemCursor) -> (String, Vec<Value>) {
+        let newest_after = "(i.created_at < ? OR (i.created_at = ? AND i.id < ?))";
+        let mut params = vec![Value::Integer(cursor.created_at), Value::Integer(cursor.created_at), Value::Integer(cursor.id)];
+        let column = match self {
+            ViewSort::Newest => return (newest_after.to_string(), params),
+            ViewSort::Oldest => {
+                return ("(i.created_at > ? OR (i.created_at = ? AND i.id > ?))".to_string(), params);
+            }
+            ViewSort::Published => {
+                let published_at = cursor.published_at.unwrap_or(cursor.created_at);
+                return (
+                    "(COALESCE(i.published_at, i.created_at) < ? OR (COALESCE(i.published_at, i.created_at) = ? AND i.id < ?))".to_string(),
+                    vec![Value::Integer(published_at), Value::Integer(published_at), Value::Integer(cursor.id)],
+                );
+            }
+            ViewSort::Title => "i.title",
+            ViewSort::Source => "s.name",
+        };
+        let key = Value::Text(cursor.key.clone().unwrap_or_default());
+        params.splice(0..0, [key.clone(), key]);
+        (
+            format!("({0} COLLATE NOCASE > ? OR ({0} = ? COLLATE NOCASE AND {1}))", column, newest_after),
+            params,
+        )
+    }
+
+    /// The cursor that continues after `item`.
+    pub fn cursor_after(&self, item: &ItemSummary) -> ItemCursor {
+        let key = match self {
+            ViewSort::Title => Some(item.title.clone()),
+            ViewSort::Source => Some(item.source_name.clone()),
+            ViewSort::Newest | ViewSort::Oldest | ViewSort::Published => None,
+        };
+        let published_at = match self {
+            ViewSort::Published => Some(item.published_at.unwrap_or(item.created_at)),
+            _ => None,
+        };
+        ItemCursor { created_at: item.created_at, id: item.id, key, published_at }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_sql() {
+        let rule: ViewRule = serde_json::from_str(r#"{"type":"and","rules":[
+            {"type":"state","value":"unread"},
+            {"type":"or","rules":[{"type":"source","ids":[1,2]},{"type":"group","names":["Work"]}]},
+            {"type":"not","rule":{"type":"author","value":"bot_"}}
+        ]}"#).unwrap();
+        assert!(rule.validate().is_ok());
+
+        let (sql, params) = rule.to_sql();
+        assert!(sql.starts_with("((i.state = ?) AND ((i.source_id IN (?, ?)) OR EXISTS"));
+        assert!(sql.ends_with("AND NOT (COALESCE(i.author, '') LIKE ? ESCAPE '\\'))"));
+        assert_eq!(params.len(), 5);
+        assert_eq!(params[4], Value::Text("%bot\\_%".to_string()));
+
+        assert_eq!(ViewRule::Or { rules: vec![] }.to_sql().0, "0");
+        assert!(ViewRule::State { value: "deleted".to_string() }.validate().is_err());
+        assert!(ViewRule::Age { max_days: 0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_keyset_after() {
+        let cursor = ItemCursor { created_at: 100, id: 7, key: Some("Rust".to_string()), published_at: None };
+        let (sql, params) = ViewSort::Title.after(&cursor);
+        assert!(sql.starts_with("(i.title COLLATE NOCASE > ? OR (i.title = ? COLLATE NOCASE AND (i.created_at < ?"));
+        assert_eq!(params.len(), 5);
+        assert_eq!(params[0], Value::Text("Rust".to_string()));
+        assert_eq!(params[4], Value::Integer(7));
+        assert_eq!(ViewSort::Oldest.after(&cursor).1.len(), 3);
+    }
+
+    #[test]
+    fn test_from_legacy() {
+        assert_eq!(ViewRule::from_legacy(None, Some("[]")), None);
+        assert_eq!(ViewRule::from_legacy(Some("[3]"), None), Some(ViewRule::Source { ids: vec![3] }));
+        assert_eq!(
+            ViewRule::from_legacy(Some("[3]"), Some(r#"["News"]"#)),
+            Some(ViewRule::And { rules: vec![
+                ViewRule::Source { ids: vec![3] },
+                ViewRule::Group { names: vec!["News".to_string()] },
+            ] })
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, Result};
+
+// Highlight markers used in snippet() and highlight(); replaced by <mark> after escaping
+pub const MARK_START: &str = "\u{2}";
+pub const MARK_END: &str = "\u{3}";
+
+static SCRIPT_OR_STYLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<(script|style)\b.*?</(script|style)>").unwrap());
+static TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+
+/// Plain text of an HTML fragment, for the search index.
+pub fn html_to_text(html: &str) -> String {
+    let text = SCRIPT_OR_STYLE.replace_all(html, " ");
+    let text = TAG.replace_all(&text, " ");
+    let text = text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    WHITESPACE.replace_all(&text, " ").trim().to_string()
+}
+
+/// Registers `strip_html(text)`, used by the migration that builds `items_fts` from
+/// existing items. The index is maintained from Rust afterwards, so other connections
+/// to the database don't need it.
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "strip_html",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let html: Option<String> = ctx.get(0)?;
+            Ok(html.map(|h| html_to_text(&h)))
+        },
+    )
+}
+
+/// Turns what the user typed into an FTS5 query: words and "quoted phrases" must all
+/// match, the last word also as a prefix. Everything is quoted, so FTS5 operators and
+/// stray punctuation can't cause syntax errors. `None` if there is nothing to search for.
+pub fn fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    let mut rest = input.trim();
+
+    while !rest.is_empty() {
+        let (term, is_phrase, remaining) = if let Some(stripped) = rest.strip_prefix('"') {
+            match stripped.find('"') {
+                Some(end) => (&stripped[..end], true, &stripped[end + 1..]),
+                None => (stripped, true, ""),
+            }
+        } else {
+            match rest.find(char::is_whitespace) {
+                Some(end) => (&rest[..end], false, &rest[end..]),
+                None => (rest, false, ""),
+            }
+        };
+        rest = remaining.trim_start();
+
+        let term = term.trim();
+        if term.chars().any(char::is_alphanumeric) {
+            terms.push((term, is_phrase));
+        }
+    }
+
+    let last = terms.len().checked_sub(1)?;
+    let terms: Vec<String> = terms.iter().enumerate()
+        .map(|(i, (term, is_phrase))| quote_term(term, i == last && !is_phrase))
+        .collect();
+    Some(terms.join(" "))
+}
+
+/// Quotes a word or phrase as an FTS5 string, optionally matching it as a prefix.
+pub fn quote_term(term: &str, prefix: bool) -> String {
+    format!("\"{}\"{}", term.replace('"', "\"\""), if prefix { "*" } else { "" })
+}
+
+/// HTML-escapes a snippet and turns its highlight markers into `<mark>` tags,
+/// so the UI can render it as HTML safely.
+pub fn render_highlights(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(MARK_START, "<mark>")
+        .replace(MARK_END, "</mark>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<p>Hello&nbsp;<b>world</b></p><script>track()</script><style>p{}</style>\n<p>Fish &amp; chips</p>";
+        assert_eq!(html_to_text(html), "Hello world Fish & chips");
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("rust async").as_deref(), Some("\"rust\" \"async\"*"));
+        assert_eq!(fts_query("\"release notes\" tok").as_deref(), Some("\"release notes\" \"tok\"*"));
+        assert_eq!(fts_query("\"release notes\"").as_deref(), Some("\"release notes\""));
+        assert_eq!(fts_query("NOT - * a\"b").as_deref(), Some("\"NOT\" \"a\"\"b\"*"));
+        assert_eq!(fts_query("  - ( ) ").as_deref(), None);
+    }
+
+    #[test]
+    fn test_render_highlights() {
+        let raw = format!("a <script> {}match{} here", MARK_START, MARK_END);
+        assert_eq!(render_highlights(&raw), "a &lt;script&gt; <mark>match</mark> here");
+    }
+}
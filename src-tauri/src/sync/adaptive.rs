@@ -0,0 +1,167 @@
+use super::scheduler::parse_duration;
+use crate::ingestion::traits::IngestedItem;
+use crate::storage::models::Source;
+use crate::storage::Database;
+use anyhow::Result;
+
+const DEFAULT_MIN_INTERVAL: &str = "5m";
+const DEFAULT_MAX_INTERVAL: &str = "24h";
+/// Most recent publication gaps considered when estimating the cadence.
+const CADENCE_SAMPLE: usize = 20;
+
+/// Adaptive polling settings from a source's config:
+/// `{"adaptive_polling": true, "min_poll_interval": "15m", "max_poll_interval": "12h"}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptivePolling {
+    pub min_secs: i64,
+    pub max_secs: i64,
+}
+
+impl AdaptivePolling {
+    /// Returns the settings if adaptive polling is turned on for the source.
+    pub fn from_config(config: &serde_json::Value) -> Option<Self> {
+        if !config.get("adaptive_polling").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return None;
+        }
+        let bound = |key: &str, default: &str| {
+            parse_duration(config.get(key).and_then(|v| v.as_str()).unwrap_or(default)) as i64
+        };
+        let min_secs = bound("min_poll_interval", DEFAULT_MIN_INTERVAL).max(60);
+        let max_secs = bound("max_poll_interval", DEFAULT_MAX_INTERVAL).max(min_secs);
+        Some(AdaptivePolling { min_secs, max_secs })
+    }
+
+    pub fn for_source(source: &Source) -> Option<Self> {
+        let config: serde_json::Value = serde_json::from_str(&source.config_json).unwrap_or_default();
+        Self::from_config(&config)
+    }
+
+    /// Poll interval for a source publishing at the given item timestamps: half the median
+    /// gap between recent items, widened when the source has gone quiet for longer than
+    /// usual, within the bounds. `None` until there are at least two distinct timestamps.
+    pub fn interval_for(&self, timestamps: &[i64], now: i64) -> Option<i64> {
+        let mut timestamps: Vec<i64> = timestamps.iter().copied().filter(|t| *t <= now).collect();
+        timestamps.sort_unstable_by(|a, b| b.cmp(a));
+        timestamps.dedup();
+        timestamps.truncate(CADENCE_SAMPLE + 1);
+        if timestamps.len() < 2 {
+            return None;
+        }
+
+        let mut gaps: Vec<i64> = timestamps.windows(2).map(|w| w[0] - w[1]).collect();
+        gaps.sort_unstable();
+        let median_gap = gaps[gaps.len() / 2];
+
+        let since_latest = now - timestamps[0];
+        let interval = (median_gap / 2).max(since_latest / 4);
+        Some(interval.clamp(self.min_secs, self.max_secs))
+    }
+}
+
+/// Publication times of fetched items. An update date says when the source last edited an
+/// item, not that it published one, so items dated only that way are left out.
+pub fn publication_times(items: &[IngestedItem]) -> Vec<i64> {
+    items.iter().filter_map(|item| item.published_at).collect()
+}
+
+/// Re-learns the poll interval of an adaptive source after a sync, from the publication
+/// times of the fetched items, or of its stored items if too few fetched ones are dated.
+/// Undated feeds keep their current interval: when items were first seen says more about
+/// how often we polled than about how often the source publishes.
+pub fn update_learned_interval(db: &Database, source: &Source, published: &[i64]) -> Result<()> {
+    let adaptive = match AdaptivePolling::for_source(source) {
+        Some(adaptive) => adaptive,
+        None => return Ok(()),
+    };
+    let now = chrono::Utc::now().timestamp();
+
+    let interval = match adaptive.interval_for(published, now) {
+        Some(interval) => Some(interval),
+        None => {
+            let stored = db.get_recent_published_timestamps(source.id, CADENCE_SAMPLE as i64 + 1)?;
+            adaptive.interval_for(&stored, now)
+        }
+    };
+    if interval.is_some() && interval != source.adaptive_interval_secs {
+        db.set_source_adaptive_interval(source.id, interval)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_config() {
+        assert_eq!(AdaptivePolling::from_config(&json!({"poll_interval": "10m"})), None);
+        let adaptive = AdaptivePolling::from_config(&json!({"adaptive_polling": true})).unwrap();
+        assert_eq!(adaptive, AdaptivePolling { min_secs: 300, max_secs: 86400 });
+        let adaptive = AdaptivePolling::from_config(&json!({
+            "adaptive_polling": true,
+            "min_poll_interval": "2h",
+            "max_poll_interval": "30m",
+        })).unwrap();
+        assert_eq!(adaptive, AdaptivePolling { min_secs: 7200, max_secs: 7200 });
+    }
+
+    #[test]
+    fn test_interval_follows_cadence() {
+        let adaptive = AdaptivePolling { min_secs: 300, max_secs: 86400 };
+        let now = 10_000_000;
+
+        // Hourly feed, last item 10 minutes ago: poll every half hour
+        let hourly: Vec<i64> = (0..10).map(|i| now - 600 - i * 3600).collect();
+        assert_eq!(adaptive.interval_for(&hourly, now), Some(1800));
+
+        // Twice a year: capped at the max
+        let rare = vec![now - 30 * 86400, now - 210 * 86400, now - 390 * 86400];
+        assert_eq!(adaptive.interval_for(&rare, now), Some(86400));
+
+        // Bursty feed with many items per minute: floored at the min
+        let bursty: Vec<i64> = (0..10).map(|i| now - i * 30).collect();
+        assert_eq!(adaptive.interval_for(&bursty, now), Some(300));
+
+        // Hourly feed that went quiet two days ago backs off
+        let quiet: Vec<i64> = (0..10).map(|i| now - 2 * 86400 - i * 3600).collect();
+        assert_eq!(adaptive.interval_for(&quiet, now), Some(43200));
+
+        assert_eq!(adaptive.interval_for(&[now - 60], now), None);
+    }
+
+    #[test]
+    fn test_updates_are_not_publications() {
+        let db = Database::new(":memory:").unwrap();
+        let id = db.create_source("feed", "Wiki", r#"{"adaptive_polling": true}"#, None, None).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let item = |n: i64, occurred_at: Option<i64>, published_at: Option<i64>| IngestedItem {
+            external_id: n.to_string(),
+            title: format!("Page {}", n),
+            summary: None,
+            url: format!("https://wiki.example.org/{}", n),
+            item_type: "post".to_string(),
+            occurred_at,
+            published_at,
+            image_url: None,
+            content_html: None,
+            author: None,
+            category: None,
+            comments: None,
+            comment_count: None,
+            thread_id: None,
+            attachments: vec![],
+        };
+
+        // Pages edited every few minutes, dated only by their last update
+        let edited: Vec<IngestedItem> = (1..=5).map(|n| item(n, Some(now - n * 120), None)).collect();
+        assert!(publication_times(&edited).is_empty());
+        update_learned_interval(&db, &db.get_source(id).unwrap(), &publication_times(&edited)).unwrap();
+        assert_eq!(db.get_source(id).unwrap().adaptive_interval_secs, None);
+
+        // Hourly posts that were also edited just now
+        let posts: Vec<IngestedItem> = (1..=5).map(|n| item(n, Some(now - 60), Some(now - 600 - n * 3600))).collect();
+        update_learned_interval(&db, &db.get_source(id).unwrap(), &publication_times(&posts)).unwrap();
+        assert_eq!(db.get_source(id).unwrap().adaptive_interval_secs, Some(1800));
+    }
+}
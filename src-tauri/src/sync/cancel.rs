@@ -0,0 +1,64 @@
+use crate::http::RequestContext;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Work that can be cancelled while in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Task {
+    Sync(i64),       // Source ID
+    Extraction(i64), // Item ID
+}
+
+/// Contexts of the running syncs and extractions, so commands can cancel them.
+#[derive(Default)]
+pub struct Cancellations {
+    tasks: Mutex<HashMap<Task, RequestContext>>,
+}
+
+/// Unregisters its task when dropped, however the task ends.
+pub struct Registration<'a> {
+    cancellations: &'a Cancellations,
+    task: Task,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.cancellations.tasks.lock().unwrap().remove(&self.task);
+    }
+}
+
+impl Cancellations {
+    pub fn register(&self, task: Task, context: &RequestContext) -> Registration<'_> {
+        self.tasks.lock().unwrap().insert(task, context.clone());
+        Registration { cancellations: self, task }
+    }
+
+    /// Cancels the task if it is running. Returns whether it was.
+    pub fn cancel(&self, task: Task) -> bool {
+        match self.tasks.lock().unwrap().get(&task) {
+            Some(context) => {
+                context.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_registered_task() {
+        let cancellations = Cancellations::default();
+        let context = RequestContext::new();
+        {
+            let _registration = cancellations.register(Task::Sync(7), &context);
+            assert!(!cancellations.cancel(Task::Extraction(7)));
+            assert!(cancellations.cancel(Task::Sync(7)));
+            assert!(context.is_cancelled());
+        }
+        assert!(!cancellations.cancel(Task::Sync(7)));
+    }
+}
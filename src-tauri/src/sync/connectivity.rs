@@ -0,0 +1,55 @@
+use crate::http::HttpSettings;
+use crate::storage::Database;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// User preference key for the reachability check target ("host:port").
+pub const REACHABILITY_HOST_PREFERENCE: &str = "sync_reachability_host";
+const DEFAULT_REACHABILITY_HOST: &str = "one.one.one.one:443";
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Where the reachability check connects to: the configured host, else the proxy
+/// (which is all the app needs to reach when one is set), else a public resolver.
+pub fn reachability_target(db: &Database) -> String {
+    if let Some(host) = db.get_user_preference(REACHABILITY_HOST_PREFERENCE).ok().flatten() {
+        if !host.trim().is_empty() {
+            return host.trim().to_string();
+        }
+    }
+
+    HttpSettings::from_preferences(db).proxy
+        .and_then(|proxy| reqwest::Url::parse(&proxy).ok())
+        .and_then(|url| Some(format!("{}:{}", url.host_str()?, url.port_or_known_default().unwrap_or(1080))))
+        .unwrap_or_else(|| DEFAULT_REACHABILITY_HOST.to_string())
+}
+
+/// Cheap connectivity check: resolves the target and opens a TCP connection to it.
+/// Blocking; call from `spawn_blocking`.
+pub fn is_reachable(target: &str) -> bool {
+    let addrs = match target.to_socket_addrs() {
+        Ok(addrs) => addrs,
+        Err(_) => return false,
+    };
+    addrs.take(2).any(|addr| TcpStream::connect_timeout(&addr, REACHABILITY_TIMEOUT).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachability_target() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(reachability_target(&db), DEFAULT_REACHABILITY_HOST);
+        db.set_user_preference(crate::http::PROXY_PREFERENCE, "http://proxy.corp.example:3128").unwrap();
+        assert_eq!(reachability_target(&db), "proxy.corp.example:3128");
+        db.set_user_preference(REACHABILITY_HOST_PREFERENCE, " intranet.example:80 ").unwrap();
+        assert_eq!(reachability_target(&db), "intranet.example:80");
+    }
+
+    #[test]
+    fn test_unresolvable_target_is_unreachable() {
+        assert!(!is_reachable("no-such-host.invalid:443"));
+        assert!(!is_reachable("not a target"));
+    }
+}
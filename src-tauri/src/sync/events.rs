@@ -0,0 +1,53 @@
+use serde::Serialize;
+use tauri::Emitter;
+
+/// Sync lifecycle events, emitted to the frontend as `sync:<stage>` (e.g. `sync:started`)
+/// with the event as payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum SyncEvent {
+    /// A sync was requested; it starts once running syncs of the same source are done.
+    Queued { source_id: i64, trigger: &'static str },
+    Started { source_id: i64, run_id: i64, trigger: &'static str },
+    /// Another page (HTTP request) of the source was fetched.
+    Progress { source_id: i64, run_id: i64, pages: u32 },
+    ItemsStored { source_id: i64, new: i64, updated: i64, unchanged: i64 },
+    Finished { source_id: i64, run_id: i64, duration_ms: i64 },
+    /// `kind` is an error kind (see `health::classify_error`), "cancelled" or "deferred".
+    Failed { source_id: i64, run_id: i64, kind: &'static str, error: String },
+}
+
+impl SyncEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncEvent::Queued { .. } => "sync:queued",
+            SyncEvent::Started { .. } => "sync:started",
+            SyncEvent::Progress { .. } => "sync:progress",
+            SyncEvent::ItemsStored { .. } => "sync:items_stored",
+            SyncEvent::Finished { .. } => "sync:finished",
+            SyncEvent::Failed { .. } => "sync:failed",
+        }
+    }
+}
+
+/// Emits a sync event to all windows. Failures are logged, never fatal to the sync.
+pub fn emit(app: &tauri::AppHandle, event: SyncEvent) {
+    if app.emit(event.name(), event.clone()).is_err() {
+        eprintln!("Failed to emit {} event", event.name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_payload() {
+        let event = SyncEvent::ItemsStored { source_id: 3, new: 2, updated: 1, unchanged: 7 };
+        assert_eq!(event.name(), "sync:items_stored");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({"stage": "items_stored", "source_id": 3, "new": 2, "updated": 1, "unchanged": 7})
+        );
+    }
+}
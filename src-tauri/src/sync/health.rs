@@ -0,0 +1,229 @@
+use crate::storage::Database;
+use anyhow::Result;
+use std::time::Duration;
+
+/// Background polling starts backing off after this many consecutive failures.
+pub const BACKOFF_AFTER_FAILURES: i64 = 3;
+const BASE_BACKOFF_SECS: i64 = 5 * 60;
+const MAX_BACKOFF_SECS: i64 = 24 * 60 * 60;
+
+// User preference keys for auto-disable ("true"/"false" and a failure count)
+pub const AUTO_DISABLE_PREFERENCE: &str = "auto_disable_failing_sources";
+pub const AUTO_DISABLE_THRESHOLD_PREFERENCE: &str = "auto_disable_after_failures";
+const DEFAULT_AUTO_DISABLE_THRESHOLD: i64 = 10;
+
+/// Sorts a sync error into a coarse kind the UI can show and filter on:
+/// auth, not_found, rate_limited, server, timeout, network, parse, config or other.
+/// HTTP errors are sorted by their status or transport failure; the message is only
+/// looked at for errors that don't carry one.
+pub fn classify_error(error: &anyhow::Error) -> &'static str {
+    error.chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .and_then(classify_http_error)
+        .unwrap_or_else(|| classify_message(&format!("{:#}", error).to_lowercase()))
+}
+
+fn classify_http_error(error: &reqwest::Error) -> Option<&'static str> {
+    if let Some(status) = error.status() {
+        return Some(match status.as_u16() {
+            401 | 403 => "auth",
+            404 | 410 => "not_found",
+            429 => "rate_limited",
+            408 | 504 => "timeout",
+            500..=599 => "server",
+            _ => "other",
+        });
+    }
+    // A host that can't be reached, even by timing out on connect, is a network failure
+    if error.is_connect() {
+        Some("network")
+    } else if error.is_timeout() {
+        Some("timeout")
+    } else if error.is_request() || error.is_body() {
+        Some("network")
+    } else if error.is_decode() {
+        Some("parse")
+    } else {
+        None
+    }
+}
+
+fn classify_message(message: &str) -> &'static str {
+    let has = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+
+    if has(&["(401)", "(403)", "401 unauthorized", "403 forbidden", "re-authorize", "token may be"]) {
+        "auth"
+    } else if has(&["(404)", "(410)", "404 not found", "410 gone", "not found"]) {
+        "not_found"
+    } else if has(&["(429)", "429 too many requests", "rate limit"]) {
+        "rate_limited"
+    } else if has(&["(504)", "timed out", "timeout"]) {
+        "timeout"
+    } else if has(&["http error 5", "api error: 5"]) {
+        "server"
+    } else if has(&["failed to fetch", "error sending request", "connection", "dns", "certificate", "proxy"]) {
+        "network"
+    } else if has(&["parse", "not an rss", "invalid xml", "is empty"]) {
+        "parse"
+    } else if has(&["missing", "needs a", "no secret configured", "configure a secret", "unknown source type"]) {
+        "config"
+    } else {
+        "other"
+    }
+}
+
+/// How long background polling waits after `consecutive_failures` failed syncs:
+/// nothing before the threshold, then 5 minutes doubling up to a day.
+pub fn backoff_secs(consecutive_failures: i64) -> Option<i64> {
+    if consecutive_failures < BACKOFF_AFTER_FAILURES {
+        return None;
+    }
+    let exponent = (consecutive_failures - BACKOFF_AFTER_FAILURES).min(16) as u32;
+    Some((BASE_BACKOFF_SECS * 2i64.pow(exponent)).min(MAX_BACKOFF_SECS))
+}
+
+/// Failure count at which sources are disabled, if the user turned auto-disable on.
+fn auto_disable_threshold(db: &Database) -> Option<i64> {
+    let enabled = db.get_user_preference(AUTO_DISABLE_PREFERENCE)
+        .ok()
+        .flatten()
+        .map(|v| v == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let threshold = db.get_user_preference(AUTO_DISABLE_THRESHOLD_PREFERENCE)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|t| *t > 0)
+        .unwrap_or(DEFAULT_AUTO_DISABLE_THRESHOLD);
+    Some(threshold)
+}
+
+/// Updates the source's health after a sync attempt: resets it on success; on failure
+/// records the error, backs off exponentially and auto-disables past the threshold.
+pub fn record_sync_outcome(db: &Database, source_id: i64, result: &Result<()>, duration: Duration) -> Result<()> {
+    let duration_ms = duration.as_millis() as i64;
+
+    let error = match result {
+        Ok(()) => {
+            db.record_sync_success(source_id, duration_ms)?;
+            return Ok(());
+        }
+        Err(e) => e,
+    };
+
+    let kind = classify_error(error);
+    let message = format!("{:#}", error);
+    let failures = db.record_sync_failure(source_id, kind, &message, duration_ms)?;
+
+    if let Some(threshold) = auto_disable_threshold(db) {
+        if failures >= threshold {
+            let reason = format!("Disabled after {} consecutive failures. Last error: {}", failures, message);
+            eprintln!("Auto-disabling source {}: {}", source_id, reason);
+            db.auto_disable_source(source_id, &reason)?;
+            return Ok(());
+        }
+    }
+
+    let backoff_until = backoff_secs(failures).map(|secs| chrono::Utc::now().timestamp() + secs);
+    db.set_source_backoff(source_id, backoff_until)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingestion::FeedIngester;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_classify_error() {
+        let error = anyhow::anyhow!("Authentication failed (401) when fetching feed from: https://example.org/feed");
+        assert_eq!(classify_error(&error), "auth");
+        let error = anyhow::anyhow!("Content from https://example.org/ is not an RSS, RDF or ATOM feed");
+        assert_eq!(classify_error(&error), "parse");
+        let error = anyhow::anyhow!("operation timed out").context("Failed to fetch feed from: https://example.org/feed");
+        assert_eq!(classify_error(&error), "timeout");
+        let error = anyhow::anyhow!("Feed uses basic authentication but has no secret configured");
+        assert_eq!(classify_error(&error), "config");
+
+        // Words that only look like a kind
+        let error = anyhow::anyhow!("Unexpected token '<' at line 1");
+        assert_eq!(classify_error(&error), "other");
+        let error = anyhow::anyhow!("Read 500 bytes from https://example.org/500/feed");
+        assert_eq!(classify_error(&error), "other");
+    }
+
+    /// Serves one canned HTTP response on a local port and returns its URL.
+    fn serve_once(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/feed", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(response.as_bytes());
+        });
+        url
+    }
+
+    #[test]
+    fn test_classify_http_errors() {
+        let url = serve_once("HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let error = FeedIngester::new(url, None).unwrap().fetch().unwrap_err();
+        assert_eq!(classify_error(&error), "server");
+
+        let url = serve_once("HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let error = FeedIngester::new(url, None).unwrap().fetch().unwrap_err();
+        assert_eq!(classify_error(&error), "rate_limited");
+
+        // Nothing listening on the port
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let error = FeedIngester::new(format!("http://{}/feed", closed), None).unwrap().fetch().unwrap_err();
+        assert_eq!(classify_error(&error), "network");
+    }
+
+    #[test]
+    fn test_backoff_secs() {
+        assert_eq!(backoff_secs(2), None);
+        assert_eq!(backoff_secs(3), Some(300));
+        assert_eq!(backoff_secs(4), Some(600));
+        assert_eq!(backoff_secs(40), Some(MAX_BACKOFF_SECS));
+    }
+
+    #[test]
+    fn test_record_sync_outcome() {
+        let db = Database::new(":memory:").unwrap();
+        let source_id = db.create_source("feed", "Flaky", "{}", None, None).unwrap();
+        db.set_user_preference(AUTO_DISABLE_PREFERENCE, "true").unwrap();
+        db.set_user_preference(AUTO_DISABLE_THRESHOLD_PREFERENCE, "4").unwrap();
+
+        for _ in 0..3 {
+            let result = Err(anyhow::anyhow!("HTTP error 500 when fetching feed"));
+            record_sync_outcome(&db, source_id, &result, Duration::from_millis(100)).unwrap();
+        }
+        let source = db.get_source(source_id).unwrap();
+        assert_eq!(source.consecutive_failures, 3);
+        assert_eq!(source.last_error_kind.as_deref(), Some("server"));
+        assert!(source.backoff_until.is_some());
+        assert!(source.enabled);
+
+        let result = Err(anyhow::anyhow!("HTTP error 500 when fetching feed"));
+        record_sync_outcome(&db, source_id, &result, Duration::from_millis(100)).unwrap();
+        let source = db.get_source(source_id).unwrap();
+        assert!(!source.enabled);
+        assert!(source.disabled_reason.unwrap().starts_with("Disabled after 4 consecutive failures"));
+        assert_eq!(db.get_source_errors(source_id).unwrap().len(), 4);
+
+        db.update_source(source_id, None, None, Some(true), None, None).unwrap();
+        record_sync_outcome(&db, source_id, &Ok(()), Duration::from_millis(300)).unwrap();
+        let source = db.get_source(source_id).unwrap();
+        assert_eq!(source.consecutive_failures, 0);
+        assert_eq!(source.disabled_reason, None);
+        assert_eq!(source.avg_sync_duration_ms, Some(140));
+        assert!(source.last_success_at.is_some());
+    }
+}
@@ -0,0 +1,25 @@
+pub mod adaptive;
+pub mod cancel;
+pub mod connectivity;
+pub mod events;
+pub mod health;
+pub mod quiet_hours;
+pub mod scheduler;
+
+/// What started a sync, recorded on its sync run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncTrigger {
+    Startup,
+    Background,
+    Manual,
+}
+
+impl SyncTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncTrigger::Startup => "startup",
+            SyncTrigger::Background => "background",
+            SyncTrigger::Manual => "manual",
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::storage::Database;
+use anyhow::{Result, Context};
+use chrono::{Datelike, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+// User preference keys: "true" pauses background syncs; quiet hours are a JSON list of windows
+pub const PAUSED_PREFERENCE: &str = "sync_paused";
+pub const QUIET_HOURS_PREFERENCE: &str = "sync_quiet_hours";
+
+/// A window in local time during which sources aren't polled in the background, e.g.
+/// `{"days": ["sat", "sun"], "start": "00:00", "end": "23:59"}` or `{"start": "22:00", "end": "07:00"}`.
+/// Windows ending before they start run past midnight; `days` are the days they start on
+/// (every day when omitted).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietWindow {
+    #[serde(default)]
+    pub days: Vec<String>,
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedWindow {
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl ParsedWindow {
+    fn parse(window: &QuietWindow) -> Result<Self> {
+        let time = |value: &str| NaiveTime::parse_from_str(value.trim(), "%H:%M")
+            .with_context(|| format!("Invalid time {:?}, expected HH:MM", value));
+        let days = window.days.iter()
+            .map(|day| day.trim().parse::<Weekday>().map_err(|_| anyhow::anyhow!("Invalid weekday {:?}", day)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ParsedWindow {
+            days,
+            start: time(&window.start)?,
+            end: time(&window.end)?,
+        })
+    }
+
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn contains(&self, day: Weekday, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.starts_on(day) && time >= self.start && time < self.end
+        } else {
+            (self.starts_on(day) && time >= self.start) || (self.starts_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// When background polling may run: not while paused or inside a quiet window.
+/// Manual syncs are never held back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PollingSchedule {
+    pub paused: bool,
+    windows: Vec<ParsedWindow>,
+}
+
+impl PollingSchedule {
+    /// Parses the quiet hours preference value (a JSON list of `QuietWindow`s; blank for none).
+    pub fn parse_quiet_hours(value: &str) -> Result<Vec<QuietWindow>> {
+        if value.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let windows: Vec<QuietWindow> = serde_json::from_str(value)
+            .context("Quiet hours must be a JSON list of {days, start, end} windows")?;
+        for window in &windows {
+            ParsedWindow::parse(window)?;
+        }
+        Ok(windows)
+    }
+
+    pub fn new(paused: bool, windows: &[QuietWindow]) -> Result<Self> {
+        Ok(PollingSchedule {
+            paused,
+            windows: windows.iter().map(ParsedWindow::parse).collect::<Result<_>>()?,
+        })
+    }
+
+    /// Reads the schedule from preferences. Invalid quiet hours are ignored (they are
+    /// validated when set).
+    pub fn from_preferences(db: &Database) -> Self {
+        let get = |key: &str| db.get_user_preference(key).ok().flatten().unwrap_or_default();
+        let paused = get(PAUSED_PREFERENCE) == "true";
+        let windows = Self::parse_quiet_hours(&get(QUIET_HOURS_PREFERENCE)).unwrap_or_else(|e| {
+            eprintln!("Ignoring invalid quiet hours: {:#}", e);
+            Vec::new()
+        });
+        Self::new(paused, &windows).unwrap_or(PollingSchedule { paused, windows: Vec::new() })
+    }
+
+    pub fn is_quiet<T: Datelike + chrono::Timelike>(&self, now: &T) -> bool {
+        let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), now.second()).unwrap_or_default();
+        self.windows.iter().any(|w| w.contains(now.weekday(), time))
+    }
+
+    /// Whether background syncs are held back right now.
+    pub fn is_blocked(&self) -> bool {
+        self.paused || self.is_quiet(&chrono::Local::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_quiet_windows() {
+        let windows = PollingSchedule::parse_quiet_hours(r#"[
+            {"start": "22:00", "end": "07:00"},
+            {"days": ["sat", "sun"], "start": "09:00", "end": "18:00"}
+        ]"#).unwrap();
+        let schedule = PollingSchedule::new(false, &windows).unwrap();
+        let at = |day: u32, h: u32, m: u32| NaiveDate::from_ymd_opt(2024, 6, day).unwrap().and_hms_opt(h, m, 0).unwrap();
+
+        // 2024-06-07 is a Friday
+        assert!(!schedule.is_quiet(&at(7, 21, 59)));
+        assert!(schedule.is_quiet(&at(7, 22, 0)));
+        assert!(schedule.is_quiet(&at(8, 6, 30)));
+        assert!(!schedule.is_quiet(&at(7, 12, 0)));
+        assert!(schedule.is_quiet(&at(8, 12, 0)));
+        assert!(!schedule.is_quiet(&at(10, 12, 0)));
+    }
+
+    #[test]
+    fn test_parse_quiet_hours_rejects_invalid() {
+        assert!(PollingSchedule::parse_quiet_hours("").unwrap().is_empty());
+        assert!(PollingSchedule::parse_quiet_hours(r#"[{"start": "25:00", "end": "07:00"}]"#).is_err());
+        assert!(PollingSchedule::parse_quiet_hours(r#"[{"days": ["someday"], "start": "22:00", "end": "07:00"}]"#).is_err());
+    }
+}
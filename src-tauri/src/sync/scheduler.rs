@@ -0,0 +1,629 @@
+use super::adaptive::AdaptivePolling;
+use super::connectivity::{is_reachable, reachability_target};
+use super::quiet_hours::PollingSchedule;
+use super::SyncTrigger;
+use crate::storage::models::Source;
+use crate::storage::Database;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tokio::sync::{oneshot, Notify};
+
+// User preference keys for the scheduler limits
+pub const MAX_CONCURRENT_PREFERENCE: &str = "sync_max_concurrent";
+pub const MAX_PER_HOST_PREFERENCE: &str = "sync_max_per_host";
+const DEFAULT_MAX_CONCURRENT: usize = 4;
+const DEFAULT_MAX_PER_HOST: usize = 2;
+
+/// Sources are re-read at least this often, to pick up new, edited and removed ones.
+const REFRESH_SECS: i64 = 60;
+/// Startup syncs are spread over this many seconds instead of all firing at once.
+const STARTUP_SPREAD_SECS: i64 = 10;
+/// While offline, connectivity is re-checked this often.
+const OFFLINE_CHECK_SECS: u64 = 30;
+/// Jitter added to each next-due time: up to 10% of the interval, capped at 5 minutes.
+const MAX_JITTER_SECS: i64 = 300;
+
+/// Parses duration strings (e.g., "5m", "10m", "1h") into seconds.
+pub fn parse_duration(duration_str: &str) -> u64 {
+    let duration_str = duration_str.trim();
+
+    if duration_str.is_empty() {
+        return 600; // Default 10 minutes
+    }
+
+    let (num_str, unit) = if let Some(num) = duration_str.strip_suffix('m') {
+        (num, "m")
+    } else if let Some(num) = duration_str.strip_suffix('h') {
+        (num, "h")
+    } else if let Some(num) = duration_str.strip_suffix('s') {
+        (num, "s")
+    } else {
+        return 600; // Default on parse error
+    };
+
+    let num: u64 = num_str.parse().unwrap_or(10);
+
+    match unit {
+        "s" => num,
+        "m" => num * 60,
+        "h" => num * 3600,
+        _ => 600, // Default on invalid unit
+    }
+}
+
+/// Poll interval of a source in seconds: the learned interval when adaptive polling is on,
+/// otherwise `poll_interval` from its config for every source type, defaulting to 5m for
+/// GitHub sources and 10m for everything else.
+pub fn poll_interval_secs(source: &Source) -> i64 {
+    let config: serde_json::Value = serde_json::from_str(&source.config_json).unwrap_or_default();
+    let adaptive = AdaptivePolling::from_config(&config);
+    if let (Some(adaptive), Some(learned)) = (adaptive, source.adaptive_interval_secs) {
+        return learned.clamp(adaptive.min_secs, adaptive.max_secs);
+    }
+    let default = match source.source_type.as_str() {
+        "github" | "github_notifications" => "5m",
+        _ => "10m",
+    };
+    let interval = config.get("poll_interval")
+        .and_then(|v| v.as_str())
+        .unwrap_or(default);
+    (parse_duration(interval) as i64).max(60)
+}
+
+/// Host a source talks to, for per-host concurrency limits.
+pub fn source_host(source: &Source) -> String {
+    match source.source_type.as_str() {
+        "github" | "github_notifications" => "api.github.com".to_string(),
+        _ => {
+            let config: serde_json::Value = serde_json::from_str(&source.config_json).unwrap_or_default();
+            config.get("url")
+                .and_then(|v| v.as_str())
+                .and_then(|url| reqwest::Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(|h| h.to_lowercase()))
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Random number in `0..=max`, seeded from the standard library's per-map random keys.
+fn random_up_to(max: i64) -> i64 {
+    if max <= 0 {
+        return 0;
+    }
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_i64(chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default());
+    (hasher.finish() % (max as u64 + 1)) as i64
+}
+
+fn jitter_secs(interval: i64) -> i64 {
+    random_up_to((interval / 10).min(MAX_JITTER_SECS))
+}
+
+/// Error of a sync that failed because the machine is offline. The source is not marked
+/// as failing; it is queued and synced once connectivity is back.
+#[derive(Debug)]
+pub struct SyncDeferred;
+
+impl std::fmt::Display for SyncDeferred {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No network connection, sync deferred until back online")
+    }
+}
+
+impl std::error::Error for SyncDeferred {}
+
+/// Whether background syncs are currently held back, and how many are waiting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub paused: bool,
+    pub quiet: bool, // Inside a quiet hours window
+    pub offline: bool,
+    pub deferred: usize, // Sources due but held back
+}
+
+/// Concurrency limits, read from preferences on every scheduling pass.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    max_concurrent: usize,
+    max_per_host: usize,
+}
+
+impl Limits {
+    fn from_preferences(db: &Database) -> Self {
+        let get = |key: &str, default: usize| {
+            db.get_user_preference(key)
+                .ok()
+                .flatten()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        Limits {
+            max_concurrent: get(MAX_CONCURRENT_PREFERENCE, DEFAULT_MAX_CONCURRENT),
+            max_per_host: get(MAX_PER_HOST_PREFERENCE, DEFAULT_MAX_PER_HOST),
+        }
+    }
+}
+
+/// Waiters for a sync in flight; they receive its result instead of starting another run.
+type Waiters = Vec<oneshot::Sender<Result<(), String>>>;
+
+#[derive(Default)]
+struct State {
+    queue: BinaryHeap<Reverse<(i64, i64)>>, // (due, source_id), stale entries are skipped
+    next_due: HashMap<i64, i64>,
+    startup: HashSet<i64>, // Sources whose next run is the startup sync
+    in_flight: HashMap<i64, Waiters>,
+    manual: HashSet<i64>, // Sources queued by `sync_now` and not started yet
+    queued: HashMap<i64, Waiters>, // Waiters for those, answered when their sync finishes
+    running: HashMap<i64, String>, // Scheduled runs and their host
+    offline: bool, // Set when a sync failed and the reachability check failed too
+}
+
+impl State {
+    fn schedule(&mut self, source_id: i64, due: i64) {
+        self.next_due.insert(source_id, due);
+        self.queue.push(Reverse((due, source_id)));
+    }
+
+    /// Queues a manual sync of the source, due now, and returns a receiver for its result.
+    /// A sync of the source already in flight answers it instead.
+    fn queue_now(&mut self, source_id: i64, now: i64) -> oneshot::Receiver<Result<(), String>> {
+        let (tx, rx) = oneshot::channel();
+        self.queued.entry(source_id).or_default().push(tx);
+        if !self.in_flight.contains_key(&source_id) {
+            self.manual.insert(source_id);
+            self.schedule(source_id, now);
+        }
+        rx
+    }
+
+    /// Adds enabled sources that aren't scheduled yet and forgets removed or disabled ones.
+    /// On the first pass every enabled source is due right away, as the startup sync.
+    fn refresh(&mut self, sources: &[Source], now: i64, first_pass: bool) {
+        let enabled: HashSet<i64> = sources.iter().filter(|s| s.enabled).map(|s| s.id).collect();
+        self.next_due.retain(|id, _| enabled.contains(id));
+        self.startup.retain(|id| enabled.contains(id));
+        self.manual.retain(|id| enabled.contains(id));
+        self.queued.retain(|id, _| enabled.contains(id)); // Their waiters see the sync interrupted
+
+        for source in sources.iter().filter(|s| s.enabled) {
+            let interval = poll_interval_secs(source);
+            let latest = source.last_synced_at.unwrap_or(now) + interval + MAX_JITTER_SECS;
+            let latest = latest.max(source.backoff_until.unwrap_or(0));
+            match self.next_due.get(&source.id).copied() {
+                None if first_pass => {
+                    self.startup.insert(source.id);
+                    self.schedule(source.id, now + random_up_to(STARTUP_SPREAD_SECS));
+                }
+                None => {
+                    let due = match source.last_synced_at {
+                        Some(last) => last + interval + jitter_secs(interval),
+                        None => now,
+                    };
+                    self.schedule(source.id, due);
+                }
+                // A shortened poll_interval takes effect without waiting for the old due time
+                Some(due) if due > latest => self.schedule(source.id, latest),
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Pops the sources that are due and fit within the concurrency limits, marking them running.
+    /// Sources in backoff are pushed back; sources over their host's limit wait for the next pass.
+    /// With `manual_only` (while paused or in quiet hours) only syncs queued by `sync_now` start.
+    fn take_due(&mut self, sources: &HashMap<i64, Source>, now: i64, limits: Limits, manual_only: bool) -> Vec<(Source, SyncTrigger)> {
+        let mut due = Vec::new();
+        let mut deferred = Vec::new();
+
+        while let Some(Reverse((at, source_id))) = self.queue.peek().copied() {
+            if at > now || self.running.len() >= limits.max_concurrent {
+                break;
+            }
+            self.queue.pop();
+            if self.next_due.get(&source_id) != Some(&at) {
+                continue; // Superseded by a later schedule() call
+            }
+            let source = match sources.get(&source_id) {
+                Some(source) if source.enabled => source,
+                _ => {
+                    self.next_due.remove(&source_id);
+                    self.manual.remove(&source_id);
+                    self.queued.remove(&source_id);
+                    continue;
+                }
+            };
+            let manual = self.manual.contains(&source_id);
+            if manual_only && !manual {
+                deferred.push((at, source_id));
+                continue;
+            }
+            // Asking for a sync overrides the backoff of a failing source
+            if let Some(until) = source.backoff_until.filter(|until| *until > now && !manual) {
+                self.schedule(source_id, until);
+                continue;
+            }
+            if self.in_flight.contains_key(&source_id) {
+                // A manual sync is running; it reschedules the source when done
+                continue;
+            }
+
+            let host = source_host(source);
+            let host_running = self.running.values().filter(|h| **h == host).count();
+            if !host.is_empty() && host_running >= limits.max_per_host {
+                deferred.push((at, source_id));
+                continue;
+            }
+
+            self.running.insert(source_id, host);
+            let startup = self.startup.remove(&source_id);
+            let trigger = if self.manual.remove(&source_id) {
+                SyncTrigger::Manual
+            } else if startup {
+                SyncTrigger::Startup
+            } else {
+                SyncTrigger::Background
+            };
+            due.push((source.clone(), trigger));
+        }
+
+        for (at, source_id) in deferred {
+            self.queue.push(Reverse((at, source_id)));
+        }
+        due
+    }
+
+    /// Seconds until the next source is due, bounded by the refresh interval.
+    fn next_wake_in(&self, now: i64) -> i64 {
+        let next = self.queue.peek().map(|Reverse((at, _))| *at - now).unwrap_or(REFRESH_SECS);
+        next.clamp(1, REFRESH_SECS)
+    }
+}
+
+/// Decides when each source syncs. Every sync, whatever triggered it, goes through
+/// `coalesce`, so a source never syncs twice at the same time.
+pub struct Scheduler {
+    state: Mutex<State>,
+    wake: Notify,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler {
+            state: Mutex::new(State::default()),
+            wake: Notify::new(),
+        }
+    }
+
+    /// Runs the scheduling loop now, e.g. after its preferences changed.
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.state.lock().unwrap().offline
+    }
+
+    /// Marks the machine offline (background syncs stop until a reachability check
+    /// passes) or back online (syncs deferred meanwhile run right away).
+    pub fn set_offline(&self, offline: bool) {
+        let was_offline = std::mem::replace(&mut self.state.lock().unwrap().offline, offline);
+        if was_offline != offline {
+            eprintln!("{}", if offline { "Offline, deferring background syncs" } else { "Back online, running deferred syncs" });
+        }
+        if !offline {
+            self.wake.notify_one();
+        }
+    }
+
+    pub fn status(&self, schedule: &PollingSchedule) -> SyncStatus {
+        let state = self.state.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let quiet = schedule.is_quiet(&chrono::Local::now());
+        let held = schedule.paused || quiet || state.offline;
+        SyncStatus {
+            paused: schedule.paused,
+            quiet,
+            offline: state.offline,
+            deferred: if held { state.next_due.values().filter(|due| **due <= now).count() } else { 0 },
+        }
+    }
+
+    /// When each scheduled source is next due for a background sync.
+    pub fn next_poll_times(&self) -> HashMap<i64, i64> {
+        self.state.lock().unwrap().next_due.clone()
+    }
+
+    /// Queues the sources for a manual sync right away and waits for all of them. They run
+    /// like background syncs, within the concurrency and per-host limits, but also while
+    /// paused, in quiet hours or in backoff. While offline they stay queued for when the
+    /// connection is back, and are reported as deferred.
+    pub async fn sync_now(&self, sources: &[Source]) -> Vec<(i64, Result<(), String>)> {
+        let now = chrono::Utc::now().timestamp();
+        let receivers = {
+            let mut state = self.state.lock().unwrap();
+            if state.offline {
+                for source in sources {
+                    state.schedule(source.id, now);
+                }
+                return sources.iter().map(|source| (source.id, Err(SyncDeferred.to_string()))).collect();
+            }
+            sources.iter().map(|source| (source.id, state.queue_now(source.id, now))).collect::<Vec<_>>()
+        };
+        self.wake.notify_one();
+
+        let mut results = Vec::new();
+        for (source_id, rx) in receivers {
+            let result = rx.await
+                .unwrap_or_else(|_| Err(format!("Sync of source {} was interrupted", source_id)));
+            results.push((source_id, result));
+        }
+        results
+    }
+
+    /// Runs `sync` for the source unless a sync of it is already in flight, in which
+    /// case it waits for that one and returns its result. Afterwards the source's next
+    /// background sync is scheduled one poll interval (plus jitter) from now, or right
+    /// away if it was deferred because the machine is offline.
+    pub async fn coalesce<F>(&self, source: &Source, sync: F) -> anyhow::Result<()>
+    where
+        F: Future<Output = anyhow::Result<()>>,
+    {
+        let waiter = {
+            let mut state = self.state.lock().unwrap();
+            match state.in_flight.get_mut(&source.id) {
+                Some(waiters) => {
+                    let (tx, rx) = oneshot::channel();
+                    waiters.push(tx);
+                    Some(rx)
+                }
+                None => {
+                    state.in_flight.insert(source.id, Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(rx) = waiter {
+            return match rx.await {
+                Ok(result) => result.map_err(|e| anyhow::anyhow!(e)),
+                Err(_) => Err(anyhow::anyhow!("Sync of source {} was interrupted", source.id)),
+            };
+        }
+
+        let result = sync.await;
+
+        let waiters = {
+            let mut state = self.state.lock().unwrap();
+            let interval = poll_interval_secs(source);
+            let now = chrono::Utc::now().timestamp();
+            state.running.remove(&source.id);
+            let deferred = matches!(&result, Err(e) if e.is::<SyncDeferred>());
+            let due = if deferred { now } else { now + interval + jitter_secs(interval) };
+            state.schedule(source.id, due);
+            // Syncs queued meanwhile are covered by this one
+            state.manual.remove(&source.id);
+            let mut waiters = state.in_flight.remove(&source.id).unwrap_or_default();
+            waiters.extend(state.queued.remove(&source.id).unwrap_or_default());
+            waiters
+        };
+        for waiter in waiters {
+            let _ = waiter.send(result.as_ref().map(|_| ()).map_err(|e| format!("{:#}", e)));
+        }
+        self.wake.notify_one();
+
+        result
+    }
+}
+
+/// Background service that syncs sources when they are due, up to `sync_max_concurrent`
+/// at once and `sync_max_per_host` per host. The first pass is the startup sync.
+/// Nothing runs while paused, during quiet hours, or while offline.
+pub async fn run(app: tauri::AppHandle) {
+    let mut first_pass = true;
+
+    loop {
+        let loaded = {
+            let db_state: tauri::State<'_, Mutex<Database>> = app.state();
+            let loaded = match db_state.lock() {
+                Ok(db) => db.get_all_sources()
+                    .map(|sources| (sources, Limits::from_preferences(&db), PollingSchedule::from_preferences(&db), reachability_target(&db)))
+                    .map_err(|e| e.to_string()),
+                Err(_) => Err("Failed to lock database".to_string()),
+            };
+            loaded
+        };
+        let (sources, limits, schedule, target) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("Failed to get sources: {}", e);
+                tokio::time::sleep(Duration::from_secs(REFRESH_SECS as u64)).await;
+                continue;
+            }
+        };
+
+        let scheduler = app.state::<Scheduler>();
+        if scheduler.is_offline() {
+            let reachable = tokio::task::spawn_blocking(move || is_reachable(&target))
+                .await
+                .unwrap_or(false);
+            if !reachable {
+                tokio::time::sleep(Duration::from_secs(OFFLINE_CHECK_SECS)).await;
+                continue;
+            }
+            scheduler.set_offline(false);
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let blocked = schedule.is_blocked();
+        let (due, wake_in) = {
+            let mut state = scheduler.state.lock().unwrap();
+            state.refresh(&sources, now, first_pass);
+            let by_id: HashMap<i64, Source> = sources.into_iter().map(|s| (s.id, s)).collect();
+            let due = state.take_due(&by_id, now, limits, blocked);
+            if blocked && state.manual.is_empty() {
+                (due, REFRESH_SECS)
+            } else {
+                (due, state.next_wake_in(now))
+            }
+        };
+        first_pass = false;
+
+        for (source, trigger) in due {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let source_id = source.id;
+                if let Err(e) = crate::sync_source_internal(&app, source, trigger).await {
+                    eprintln!("Failed to sync source {}: {}", source_id, e);
+                }
+            });
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(wake_in as u64)) => {}
+            _ = scheduler.wake.notified() => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(id: i64, source_type: &str, config_json: &str) -> Source {
+        Source {
+            id,
+            source_type: source_type.to_string(),
+            name: format!("Source {}", id),
+            config_json: config_json.to_string(),
+            enabled: true,
+            last_synced_at: None,
+            created_at: 0,
+            updated_at: 0,
+            feed_title: None,
+            feed_description: None,
+            site_url: None,
+            feed_language: None,
+            feed_image_url: None,
+            favicon_path: None,
+            favicon_fetched_at: None,
+            last_error: None,
+            last_error_kind: None,
+            last_error_at: None,
+            consecutive_failures: 0,
+            last_success_at: None,
+            avg_sync_duration_ms: None,
+            backoff_until: None,
+            disabled_reason: None,
+            adaptive_interval_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_poll_interval_for_all_types() {
+        assert_eq!(poll_interval_secs(&source(1, "github", r#"{"poll_interval":"15m"}"#)), 900);
+        assert_eq!(poll_interval_secs(&source(2, "github_notifications", "{}")), 300);
+        assert_eq!(poll_interval_secs(&source(3, "feed", r#"{"url":"https://example.org/feed"}"#)), 600);
+        assert_eq!(poll_interval_secs(&source(4, "feed", r#"{"poll_interval":"10s"}"#)), 60);
+
+        let mut adaptive = source(5, "feed", r#"{"poll_interval":"10m","adaptive_polling":true,"max_poll_interval":"6h"}"#);
+        assert_eq!(poll_interval_secs(&adaptive), 600);
+        adaptive.adaptive_interval_secs = Some(86400);
+        assert_eq!(poll_interval_secs(&adaptive), 21600);
+    }
+
+    #[test]
+    fn test_take_due_respects_host_and_concurrency_limits() {
+        let sources = vec![
+            source(1, "feed", r#"{"url":"https://a.example.org/1"}"#),
+            source(2, "feed", r#"{"url":"https://a.example.org/2"}"#),
+            source(3, "feed", r#"{"url":"https://b.example.org/feed"}"#),
+            source(4, "github", "{}"),
+        ];
+        let by_id: HashMap<i64, Source> = sources.iter().cloned().map(|s| (s.id, s)).collect();
+        let mut state = State::default();
+        let now = 1_000_000;
+        state.refresh(&sources, now, true);
+
+        let limits = Limits { max_concurrent: 3, max_per_host: 1 };
+        let due = state.take_due(&by_id, now + STARTUP_SPREAD_SECS, limits, false);
+        assert_eq!(due.len(), 3);
+        assert!(due.iter().all(|(_, trigger)| *trigger == SyncTrigger::Startup));
+        let hosts: HashSet<String> = due.iter().map(|(s, _)| source_host(s)).collect();
+        assert_eq!(hosts.len(), 3);
+
+        // Nothing else starts until a running sync finishes
+        assert!(state.take_due(&by_id, now + STARTUP_SPREAD_SECS, limits, false).is_empty());
+        let finished = due.iter().find(|(s, _)| source_host(s) == "a.example.org").unwrap().0.id;
+        state.running.remove(&finished);
+        let due = state.take_due(&by_id, now + STARTUP_SPREAD_SECS, limits, false);
+        assert_eq!(due.len(), 1);
+        assert_eq!(source_host(&due[0].0), "a.example.org");
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_runs_once_for_concurrent_requests() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let scheduler = Scheduler::new();
+        let feed = source(1, "feed", "{}");
+        let runs = AtomicU32::new(0);
+        let sync = || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(())
+        };
+
+        let (a, b) = tokio::join!(scheduler.coalesce(&feed, sync()), scheduler.coalesce(&feed, sync()));
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(scheduler.state.lock().unwrap().next_due.contains_key(&1));
+    }
+
+    #[tokio::test]
+    async fn test_sync_now_goes_through_the_queue() {
+        let mut sources = vec![
+            source(1, "feed", r#"{"url":"https://a.example.org/1"}"#),
+            source(2, "feed", r#"{"url":"https://a.example.org/2"}"#),
+            source(3, "feed", r#"{"url":"https://b.example.org/feed"}"#),
+        ];
+        let now = 1_000_000;
+        for source in &mut sources {
+            source.last_synced_at = Some(now);
+        }
+        sources[2].backoff_until = Some(now + 3600);
+        let by_id: HashMap<i64, Source> = sources.iter().cloned().map(|s| (s.id, s)).collect();
+        let mut state = State::default();
+        state.refresh(&sources, now, false);
+        let limits = Limits { max_concurrent: 4, max_per_host: 1 };
+        assert!(state.take_due(&by_id, now, limits, false).is_empty());
+
+        // Queued syncs run even while paused and in backoff, but within the host limit
+        let _receivers: Vec<_> = [1, 2, 3].iter().map(|id| state.queue_now(*id, now)).collect();
+        let due = state.take_due(&by_id, now, limits, true);
+        assert_eq!(due.iter().map(|(s, _)| s.id).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(due.iter().all(|(_, trigger)| *trigger == SyncTrigger::Manual));
+        assert!(state.take_due(&by_id, now, limits, true).is_empty());
+
+        // Through the scheduler, the waiter gets the result of the run it queued
+        let scheduler = Scheduler::new();
+        let rx = scheduler.state.lock().unwrap().queue_now(1, now);
+        scheduler.state.lock().unwrap().take_due(&by_id, now, limits, false);
+        scheduler.coalesce(&sources[0], async { Err(anyhow::anyhow!("HTTP error 500")) }).await.unwrap_err();
+        assert_eq!(rx.await.unwrap(), Err("HTTP error 500".to_string()));
+        assert!(scheduler.state.lock().unwrap().manual.is_empty());
+    }
+}
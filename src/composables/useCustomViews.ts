@@ -1,11 +1,39 @@
 import { ref } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
-import type { CustomView, CustomViewInput } from '../types';
+import type { CustomView, CustomViewInput, ViewRule } from '../types';
 
 const customViews = ref<CustomView[]>([]);
 const loading = ref(false);
 const error = ref<string | null>(null);
 
+// Rule matching items from any of the sources that are also in any of the groups
+export function rulesFromSelection(sourceIds?: number[] | null, groupNames?: string[] | null): ViewRule | null {
+  const rules: ViewRule[] = [];
+  if (sourceIds && sourceIds.length > 0) rules.push({ type: 'source', ids: sourceIds });
+  if (groupNames && groupNames.length > 0) rules.push({ type: 'group', names: groupNames });
+  if (rules.length === 0) return null;
+  return rules.length === 1 ? rules[0] : { type: 'and', rules };
+}
+
+// Inverse of rulesFromSelection; null if the rules are more than a source/group selection
+export function selectionFromRules(rules?: ViewRule | null): { sourceIds: number[]; groupNames: string[] } | null {
+  const selection = { sourceIds: [] as number[], groupNames: [] as string[] };
+  const parts = !rules ? [] : rules.type === 'and' ? rules.rules : [rules];
+  for (const rule of parts) {
+    if (rule.type === 'source') selection.sourceIds = rule.ids;
+    else if (rule.type === 'group') selection.groupNames = rule.names;
+    else return null;
+  }
+  return selection;
+}
+
+const toArgs = (input: CustomViewInput) => ({
+  name: input.name,
+  rules: input.rules ?? rulesFromSelection(input.sourceIds, input.groupNames),
+  sortOrder: input.sortOrder,
+  query: input.query,
+});
+
 export function useCustomViews() {
   const fetchCustomViews = async () => {
     loading.value = true;
@@ -24,18 +52,7 @@ export function useCustomViews() {
     loading.value = true;
     error.value = null;
     try {
-      const sourceIdsJson = input.sourceIds && input.sourceIds.length > 0
-        ? JSON.stringify(input.sourceIds)
-        : null;
-      const groupNamesJson = input.groupNames && input.groupNames.length > 0
-        ? JSON.stringify(input.groupNames)
-        : null;
-      
-      const id = await invoke<number>('add_custom_view', {
-        name: input.name,
-        sourceIds: sourceIdsJson,
-        groupNames: groupNamesJson,
-      });
+      const id = await invoke<number>('add_custom_view', toArgs(input));
       
       await fetchCustomViews();
       return id;
@@ -52,19 +69,7 @@ export function useCustomViews() {
     loading.value = true;
     error.value = null;
     try {
-      const sourceIdsJson = input.sourceIds && input.sourceIds.length > 0
-        ? JSON.stringify(input.sourceIds)
-        : null;
-      const groupNamesJson = input.groupNames && input.groupNames.length > 0
-        ? JSON.stringify(input.groupNames)
-        : null;
-      
-      await invoke('update_custom_view', {
-        id,
-        name: input.name,
-        sourceIds: sourceIdsJson,
-        groupNames: groupNamesJson,
-      });
+      await invoke('update_custom_view', { id, ...toArgs(input) });
       
       await fetchCustomViews();
     } catch (e) {
@@ -1,13 +1,13 @@
 import { ref, computed } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
-import type { Item } from '../types';
+import type { Item, ItemCursor, ItemFilter, ItemPage } from '../types';
 
 const items = ref<Item[]>([]);
 const loading = ref(false);
 const error = ref<string | null>(null);
 
 export function useItems() {
-  const fetchItems = async (stateFilter?: string, groupFilter?: string, sourceIds?: number[], groupNames?: string[]) => {
+  const fetchItems = async (stateFilter?: string, groupFilter?: string, sourceIds?: number[], groupNames?: string[], groupSimilar?: boolean) => {
     loading.value = true;
     error.value = null;
     try {
@@ -16,6 +16,7 @@ export function useItems() {
         groupFilter,
         sourceIds: sourceIds && sourceIds.length > 0 ? sourceIds : undefined,
         groupNames: groupNames && groupNames.length > 0 ? groupNames : undefined,
+        groupSimilar: groupSimilar || undefined,
       });
     } catch (e) {
       error.value = e as string;
@@ -25,6 +26,31 @@ export function useItems() {
     }
   };
 
+  // Items in a custom view, filtered and sorted by the view's rules in the backend
+  const fetchViewItems = async (viewId: number, stateFilter?: string) => {
+    loading.value = true;
+    error.value = null;
+    try {
+      items.value = await invoke<Item[]>('get_view_items', { viewId, stateFilter });
+    } catch (e) {
+      error.value = e as string;
+      console.error('Failed to fetch view items:', e);
+    } finally {
+      loading.value = false;
+    }
+  };
+
+  // One page of slim list rows; pass the previous page's next_cursor to continue
+  const fetchItemPage = async (filter: ItemFilter, cursor?: ItemCursor | null, limit?: number): Promise<ItemPage | null> => {
+    try {
+      return await invoke<ItemPage>('get_item_page', { filter, cursor: cursor ?? undefined, limit });
+    } catch (e) {
+      error.value = e as string;
+      console.error('Failed to fetch item page:', e);
+      return null;
+    }
+  };
+
   const fetchItem = async (id: number): Promise<Item | null> => {
     try {
       return await invoke<Item>('get_item', { id });
@@ -35,9 +61,9 @@ export function useItems() {
     }
   };
 
-  const updateItemState = async (id: number, state: 'unread' | 'read' | 'archived' | 'deleted') => {
+  const updateItemState = async (id: number, state: 'unread' | 'read' | 'archived' | 'deleted', groupSimilar = false) => {
     try {
-      await invoke('update_item_state', { id, state });
+      await invoke('update_item_state', { id, state, groupSimilar });
       // Update local state
       const item = items.value.find(i => i.id === id);
       if (item) {
@@ -53,11 +79,11 @@ export function useItems() {
     }
   };
 
-  const bulkUpdateItemState = async (ids: number[], state: 'unread' | 'read' | 'archived' | 'deleted') => {
+  const bulkUpdateItemState = async (ids: number[], state: 'unread' | 'read' | 'archived' | 'deleted', groupSimilar = false) => {
     if (ids.length === 0) return;
     
     try {
-      await invoke('bulk_update_item_state', { ids, state });
+      await invoke('bulk_update_item_state', { ids, state, groupSimilar });
       // Update local state
       if (state === 'deleted') {
         // Remove deleted items from local state immediately (unless we're viewing trash)
@@ -105,6 +131,8 @@ export function useItems() {
     loading,
     error,
     fetchItems,
+    fetchViewItems,
+    fetchItemPage,
     fetchItem,
     updateItemState,
     bulkUpdateItemState,
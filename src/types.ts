@@ -5,15 +5,18 @@ export interface Item {
   title: string;
   summary: string | null;
   url: string;
-  item_type: 'rss' | 'atom' | 'issue' | 'pr' | 'notification' | 'event' | 'commit';
+  item_type: 'rss' | 'atom' | 'issue' | 'pr' | 'notification' | 'event' | 'user_event' | 'commit' | 'podcast_episode';
   state: 'unread' | 'read' | 'archived' | 'deleted';
-  created_at: number;
+  created_at: number; // When the item was first ingested
   updated_at: number;
+  published_at?: number | null; // Publication date from the source, else its first known date
+  source_updated_at?: number | null; // Latest modification date reported by the source
   image_url?: string | null;
   content_html?: string | null;
   author?: string | null;
   category?: string | null; // JSON array string
   comments?: string | null;
+  comment_count?: number | null; // slash:comments
   source_name?: string | null;
   source_group?: string | null;
   // Content extraction fields
@@ -22,21 +25,72 @@ export interface Item {
   content_completeness?: string | null; // 'full', 'partial', 'unknown'
   extraction_attempted_at?: number | null;
   extraction_failed_reason?: string | null;
+  attachments?: Attachment[]; // Enclosures (podcast audio, video, files)
+  duplicates?: ItemDuplicate[]; // Copies from other sources with the same canonical URL
+  cluster_members?: ClusterMember[]; // Similar stories, when the list groups them
+}
+
+// Another stored copy of an item; lists show one entry per article
+export interface ItemDuplicate {
+  item_id: number;
+  source_id: number;
+  source_name: string;
+}
+
+// Another item telling the same story, listed under the cluster's representative
+export interface ClusterMember {
+  item_id: number;
+  source_id: number;
+  source_name: string;
+  title: string;
+  state: 'unread' | 'read' | 'archived' | 'deleted';
 }
 
+export interface Attachment {
+  id: number;
+  item_id: number;
+  url: string;
+  mime_type?: string | null;
+  length?: number | null; // bytes
+  duration_seconds?: number | null;
+  episode?: number | null;
+  season?: number | null;
+  explicit?: boolean | null;
+  image_url?: string | null;
+  created_at: number;
+}
+
+// Custom view filter, evaluated in SQL by the backend
+export type ViewRule =
+  | { type: 'and' | 'or'; rules: ViewRule[] } // Empty AND matches everything, empty OR nothing
+  | { type: 'not'; rule: ViewRule }
+  | { type: 'state'; value: 'unread' | 'read' | 'archived' }
+  | { type: 'item_type' | 'author' | 'category' | 'keyword'; value: string } // author contains, keyword is full-text
+  | { type: 'age'; max_days: number } // Created within the last max_days days
+  | { type: 'completeness'; value: 'full' | 'partial' | 'unknown' }
+  | { type: 'source'; ids: number[] }
+  | { type: 'group'; names: string[] };
+
+export type ViewSort = 'newest' | 'oldest' | 'published' | 'title' | 'source';
+
 export interface CustomView {
   id: number;
   name: string;
-  source_ids?: string | null; // JSON array string
-  group_names?: string | null; // JSON array string
+  rules?: ViewRule | null; // None shows every item
+  sort_order: ViewSort;
+  unread_count: number; // Stored, refreshed after syncs and state changes
+  query?: string | null; // Inbox query, e.g. is:unread author:alice -label:bot
   created_at: number;
   updated_at: number;
 }
 
 export interface CustomViewInput {
   name: string;
+  rules?: ViewRule | null; // Used as-is when set, otherwise built from sourceIds / groupNames
   sourceIds?: number[] | null;
   groupNames?: string[] | null;
+  sortOrder?: ViewSort;
+  query?: string | null;
 }
 
 export interface Group {
@@ -48,12 +102,55 @@ export interface Group {
 
 export interface Source {
   id: number;
-  source_type: 'rss' | 'atom' | 'github';
+  source_type: 'feed' | 'rss' | 'atom' | 'github' | 'github_notifications'; // 'rss'/'atom' are legacy, stored as 'feed'
   name: string;
   config_json: Record<string, any> | string; // Can be string from backend or parsed object
   enabled: boolean;
   last_synced_at: number | null;
   group_ids?: number[]; // Group relationships via source_groups junction table
+  // Feed-level metadata, refreshed on every poll of feed sources
+  feed_title?: string;
+  feed_description?: string;
+  site_url?: string;
+  feed_language?: string;
+  feed_image_url?: string;
+  favicon_path?: string; // Local cache path; use get_source_favicons for displayable data URLs
+  favicon_fetched_at?: number;
+  // Health, updated after every sync attempt
+  last_error?: string;
+  last_error_kind?: SyncErrorKind;
+  last_error_at?: number;
+  consecutive_failures: number;
+  last_success_at?: number;
+  avg_sync_duration_ms?: number;
+  backoff_until?: number; // Background polling skips the source until then
+  disabled_reason?: string; // Set when the source was disabled automatically
+  adaptive_interval_secs?: number; // Learned poll interval, used when adaptive polling is on
+}
+
+export type SyncErrorKind = 'auth' | 'not_found' | 'rate_limited' | 'server' | 'timeout' | 'network' | 'parse' | 'config' | 'other';
+
+export interface SyncRun {
+  id: number;
+  source_id: number;
+  source_name?: string;
+  trigger: 'startup' | 'background' | 'manual';
+  started_at: number;
+  finished_at?: number; // Missing while the run is in progress
+  duration_ms?: number;
+  http_requests: number;
+  items_new: number;
+  items_updated: number;
+  items_unchanged: number;
+  error?: string;
+}
+
+export interface SourceError {
+  id: number;
+  source_id: number;
+  kind: SyncErrorKind;
+  message: string;
+  occurred_at: number;
 }
 
 export interface Secret {
@@ -93,3 +190,140 @@ export interface UpdateSourceInput {
   group_ids?: number[]; // None = don't update, [] = clear groups, [1,2] = set groups
 }
 
+// Stored as config_json.auth on feed sources; the credential itself is the source's secret
+export interface FeedAuthConfig {
+  scheme: 'basic' | 'bearer' | 'header' | 'query';
+  username?: string; // basic: omit to use a "username:password" secret
+  header_name?: string; // header: e.g. "X-API-Key"
+  query_param?: string; // query: e.g. "token"
+}
+
+// Source config keys for adaptive polling (any source type); see get_next_poll_times
+export interface AdaptivePollingConfig {
+  adaptive_polling?: boolean;
+  min_poll_interval?: string; // e.g. "5m" (default)
+  max_poll_interval?: string; // e.g. "24h" (default)
+}
+
+// Local-time window without background polling (sync_quiet_hours preference, a JSON list).
+// Windows ending before they start run past midnight; days are the days they start on.
+export interface QuietWindow {
+  days?: ('mon' | 'tue' | 'wed' | 'thu' | 'fri' | 'sat' | 'sun')[]; // Every day when omitted
+  start: string; // "HH:MM"
+  end: string; // "HH:MM"
+}
+
+export interface SyncStatus {
+  paused: boolean; // sync_paused preference
+  quiet: boolean; // Inside a quiet hours window
+  offline: boolean; // Syncs are queued until a reachability check passes
+  deferred: number; // Sources due but held back
+}
+
+// Payload of the sync:queued, sync:started, sync:progress, sync:items_stored,
+// sync:finished and sync:failed events
+export type SyncEvent =
+  | { stage: 'queued'; source_id: number; trigger: SyncRun['trigger'] }
+  | { stage: 'started'; source_id: number; run_id: number; trigger: SyncRun['trigger'] }
+  | { stage: 'progress'; source_id: number; run_id: number; pages: number }
+  | { stage: 'items_stored'; source_id: number; new: number; updated: number; unchanged: number }
+  | { stage: 'finished'; source_id: number; run_id: number; duration_ms: number }
+  | { stage: 'failed'; source_id: number; run_id: number; kind: SyncErrorKind | 'cancelled' | 'deferred'; error: string };
+
+// Entry of get_item_history; content_changed carries old_length/new_length instead of old/new
+export interface ItemEvent {
+  id: number;
+  item_id: number;
+  event_type: 'title_changed' | 'summary_changed' | 'status_changed' | 'content_changed' | 'state_changed';
+  payload: { old?: string | null; new?: string | null; old_length?: number | null; new_length?: number | null };
+  occurred_at: number;
+}
+
+// List row from get_item_page; bodies come from get_item_with_content
+export interface ItemSummary {
+  id: number;
+  source_id: number;
+  title: string;
+  summary?: string | null;
+  url: string;
+  item_type: string;
+  state: 'unread' | 'read' | 'archived' | 'deleted';
+  created_at: number;
+  updated_at: number;
+  image_url?: string | null;
+  author?: string | null;
+  category?: string | null; // JSON array string
+  comment_count?: number | null;
+  source_name: string;
+  source_group?: string | null;
+  content_status?: string | null;
+  content_completeness?: string | null;
+  has_attachments: boolean;
+  duplicates?: ItemDuplicate[];
+  cluster_members?: ClusterMember[];
+  published_at?: number | null;
+  source_updated_at?: number | null;
+}
+
+// Inbox filters, or a custom view when view_id is set
+export interface ItemFilter {
+  state_filter?: string;
+  group_filter?: string;
+  source_ids?: number[];
+  group_names?: string[];
+  query?: string;
+  view_id?: number;
+  sort?: ViewSort; // Ignored for views, which use their own sort_order
+  group_similar?: boolean; // One entry per cluster of similar stories
+}
+
+export interface ItemCursor {
+  created_at: number;
+  id: number;
+  key?: string;
+  published_at?: number; // Set when sorting by publication date
+}
+
+export interface ItemPage {
+  items: ItemSummary[];
+  total: number; // Across all pages
+  next_cursor: ItemCursor | null; // null on the last page
+}
+
+// Result of search_items: the item plus its rank and highlighted matches
+export interface SearchResult extends Item {
+  rank: number; // BM25, lower is a better match
+  title_highlight: string; // Escaped HTML with <mark> around matches
+  snippet: string; // Best matching passage, escaped HTML with <mark> around matches
+}
+
+// Returned by validate_item_query; position is the 0-based character offset
+export interface QueryError {
+  message: string;
+  position: number;
+}
+
+export interface FeedCandidate {
+  url: string;
+  title?: string | null;
+  source_type: 'rss' | 'atom';
+  discovered_by: 'direct' | 'known_site' | 'link_tag' | 'common_path';
+}
+
+export interface ImportEntryResult {
+  title: string;
+  url: string;
+  status: 'imported' | 'duplicate' | 'invalid' | 'failed';
+  source_id?: number | null;
+  item_id?: number | null; // Set for articles imported from other readers
+  groups: string[];
+  format?: 'rss' | 'atom' | null;
+  message?: string | null;
+}
+
+export interface ImportReport {
+  imported: number;
+  duplicates: number;
+  failed: number;
+  entries: ImportEntryResult[];
+}